@@ -107,7 +107,8 @@ impl TreeTraversal<'_> {
             | syntax::ExprData::BooleanLiteral(_)
             | syntax::ExprData::IntegerLiteral(..)
             | syntax::ExprData::FloatLiteral(_, _)
-            | syntax::ExprData::StringLiteral(_) => Some(expr),
+            | syntax::ExprData::StringLiteral(_)
+            | syntax::ExprData::CharLiteral(_) => Some(expr),
 
             syntax::ExprData::Var(_, base_expr)
             | syntax::ExprData::Dot(base_expr, _)
@@ -126,8 +127,13 @@ impl TreeTraversal<'_> {
                 self.find_in_children(expr, base_expr)
             }
 
-            syntax::ExprData::Concatenate(child_exprs)
-            | syntax::ExprData::Tuple(child_exprs)
+            syntax::ExprData::Concatenate(child_exprs) => {
+                self.find_in_children(expr, child_exprs.iter().map(|(child_expr, _)| child_expr))
+            }
+
+            syntax::ExprData::Tuple(child_exprs)
+            | syntax::ExprData::List(child_exprs)
+            | syntax::ExprData::AwaitAll(child_exprs)
             | syntax::ExprData::Seq(child_exprs) => self.find_in_children(expr, child_exprs),
 
             syntax::ExprData::Call(func_expr, arg_exprs) => self.find_in_children(
@@ -164,6 +170,7 @@ impl TreeTraversal<'_> {
             }
 
             syntax::ExprData::Assign(lhs, rhs)
+            | syntax::ExprData::Index(lhs, rhs)
             | syntax::ExprData::Op(lhs, _, rhs)
             | syntax::ExprData::OpEq(lhs, _, rhs) => self.find_in_children(expr, [lhs, rhs]),
         }
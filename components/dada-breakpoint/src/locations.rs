@@ -1,16 +1,38 @@
-use dada_ir::{code::syntax, input_file::InputFile};
+use dada_ir::{code::syntax, input_file::InputFile, span::FileSpan};
 
-/// Returns all the breakpoints set for a given chunk of code.
-pub fn breakpoints_in_tree(
-    db: &dyn crate::Db,
-    input_file: InputFile,
-    tree: syntax::Tree,
-) -> Vec<syntax::Expr> {
+use crate::breakpoint::Breakpoint;
+
+/// Returns all the breakpoints set for a given input file. A breakpoint's
+/// index (as used in `BreakpointStart` and `BreakpointEnd`) is its position
+/// in this list.
+fn breakpoints(db: &dyn crate::Db, input_file: InputFile) -> Vec<Breakpoint> {
     let locations = input_file.breakpoint_locations(db);
     locations
         .iter()
         .flat_map(|l| crate::breakpoint::find(db, input_file, *l))
-        .filter(|bp| bp.tree == tree)
-        .map(|bp| bp.expr)
+        .collect()
+}
+
+/// Returns the breakpoints set for a given chunk of code, each paired
+/// with its index within the input file.
+pub fn breakpoints_in_tree(
+    db: &dyn crate::Db,
+    input_file: InputFile,
+    tree: syntax::Tree,
+) -> Vec<(usize, syntax::Expr)> {
+    breakpoints(db, input_file)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, bp)| bp.tree == tree)
+        .map(|(index, bp)| (index, bp.expr))
+        .collect()
+}
+
+/// Returns the span of each breakpoint set for a given input file,
+/// in order of breakpoint index.
+pub fn breakpoints_in(db: &dyn crate::Db, input_file: InputFile) -> Vec<FileSpan> {
+    breakpoints(db, input_file)
+        .into_iter()
+        .map(|bp| bp.span(db))
         .collect()
 }
@@ -1,7 +1,10 @@
 use dada_id::prelude::*;
-use dada_ir::code::{
-    bir::{self, BirData},
-    validated::{self, ExprOrigin},
+use dada_ir::{
+    code::{
+        bir::{self, BirData},
+        validated::{self, ExprOrigin},
+    },
+    intrinsic::Intrinsic,
 };
 use dada_parse::prelude::*;
 use salsa::DebugWithDb;
@@ -82,18 +85,20 @@ impl Scope<'_> {
                 with_value,
             } => {
                 self.push_breakpoint_start(brewery, origin);
-                let (loop_context, variables) = self.loop_context(*from_expr);
+                let (loop_context, variables, atomics) = self.loop_context(*from_expr);
                 self.brew_expr_and_assign_to(brewery, loop_context.loop_value, *with_value);
                 self.push_breakpoint_end(brewery, Some(loop_context.loop_value), origin);
                 self.push_clear_variables(brewery, &variables, origin);
+                self.push_end_atomics(brewery, atomics, origin);
                 self.terminate_and_goto(brewery, loop_context.break_block, origin);
             }
 
             validated::ExprData::Continue(from_expr) => {
                 self.push_breakpoint_start(brewery, origin);
-                let (loop_context, variables) = self.loop_context(*from_expr);
+                let (loop_context, variables, atomics) = self.loop_context(*from_expr);
                 self.push_breakpoint_end(brewery, None::<bir::Place>, origin);
                 self.push_clear_variables(brewery, &variables, origin);
+                self.push_end_atomics(brewery, atomics, origin);
                 self.terminate_and_goto(brewery, loop_context.continue_block, origin);
             }
 
@@ -132,6 +137,7 @@ impl Scope<'_> {
             }
 
             validated::ExprData::Await(_)
+            | validated::ExprData::AwaitAll(_)
             | validated::ExprData::If(_, _, _)
             | validated::ExprData::Loop(_)
             | validated::ExprData::Seq(_)
@@ -143,12 +149,14 @@ impl Scope<'_> {
             | validated::ExprData::SignedIntegerLiteral(_)
             | validated::ExprData::FloatLiteral(_)
             | validated::ExprData::StringLiteral(_)
+            | validated::ExprData::CharLiteral(_)
             | validated::ExprData::Call(_, _)
             | validated::ExprData::IntoShared(_)
             | validated::ExprData::Lease(_)
             | validated::ExprData::Share(_)
             | validated::ExprData::Give(_)
             | validated::ExprData::Tuple(_)
+            | validated::ExprData::List(_)
             | validated::ExprData::Concatenate(_)
             | validated::ExprData::Atomic(_) => {
                 let _ = self.brew_expr_to_temporary(brewery, expr);
@@ -202,6 +210,28 @@ impl Scope<'_> {
                 }
             }
 
+            validated::ExprData::AwaitAll(futures) => {
+                self.push_breakpoint_start(brewery, origin);
+                if let Some(places) = futures
+                    .iter()
+                    .map(|future| self.brew_expr_to_temporary(brewery, *future))
+                    .collect::<Option<Vec<_>>>()
+                {
+                    self.terminate_and_continue(
+                        brewery,
+                        |next_block| {
+                            bir::TerminatorData::Assign(
+                                target,
+                                bir::TerminatorExpr::AwaitAll(places),
+                                next_block,
+                            )
+                        },
+                        origin,
+                    );
+                    self.push_breakpoint_end(brewery, Some(target), origin);
+                }
+            }
+
             validated::ExprData::If(condition, if_true, if_false) => {
                 self.push_breakpoint_start(brewery, origin);
                 if let Some(condition_place) = self.brew_expr_to_temporary(brewery, *condition) {
@@ -342,11 +372,17 @@ impl Scope<'_> {
                 self.push_breakpoint_end(brewery, Some(target), origin);
             }
 
+            validated::ExprData::CharLiteral(value) => {
+                self.push_breakpoint_start(brewery, origin);
+                self.push_assignment(brewery, target, bir::ExprData::CharLiteral(*value), origin);
+                self.push_breakpoint_end(brewery, Some(target), origin);
+            }
+
             validated::ExprData::Concatenate(exprs) => {
                 self.push_breakpoint_start(brewery, origin);
                 if let Some(values) = exprs
                     .iter()
-                    .map(|expr| self.brew_expr_to_temporary(brewery, *expr))
+                    .map(|&(expr, spec)| Some((self.brew_expr_to_temporary(brewery, expr)?, spec)))
                     .collect::<Option<Vec<_>>>()
                 {
                     assert_eq!(values.len(), exprs.len());
@@ -376,6 +412,17 @@ impl Scope<'_> {
                     self.push_breakpoint_end(brewery, Some(target), origin);
                 }
             }
+            validated::ExprData::List(exprs) => {
+                self.push_breakpoint_start(brewery, origin);
+                if let Some(values) = exprs
+                    .iter()
+                    .map(|expr| self.brew_expr_to_temporary(brewery, *expr))
+                    .collect::<Option<Vec<_>>>()
+                {
+                    self.push_assignment(brewery, target, bir::ExprData::List(values), origin);
+                    self.push_breakpoint_end(brewery, Some(target), origin);
+                }
+            }
 
             validated::ExprData::Op(lhs, op, rhs) => {
                 self.push_breakpoint_start(brewery, origin);
@@ -394,7 +441,15 @@ impl Scope<'_> {
 
             validated::ExprData::Unary(op, rhs) => {
                 self.push_breakpoint_start(brewery, origin);
-                if let Some(rhs) = self.brew_expr_to_temporary(brewery, *rhs) {
+                if let Some(value) = negated_literal(brewery.validated_tables(), *op, *rhs) {
+                    self.push_assignment(
+                        brewery,
+                        target,
+                        bir::ExprData::SignedIntegerLiteral(value),
+                        origin,
+                    );
+                    self.push_breakpoint_end(brewery, Some(target), origin);
+                } else if let Some(rhs) = self.brew_expr_to_temporary(brewery, *rhs) {
                     self.push_assignment(brewery, target, bir::ExprData::Unary(*op, rhs), origin);
                     self.push_breakpoint_end(brewery, Some(target), origin);
                 }
@@ -419,6 +474,30 @@ impl Scope<'_> {
                 self.push_assignment(brewery, target, bir::ExprData::Unit, origin);
             }
 
+            validated::ExprData::Call(func, args)
+                if args.len() <= 1
+                    && is_intrinsic(brewery.validated_tables(), *func, Intrinsic::Panic) =>
+            {
+                // `panic(message)` never returns, so rather than calling the
+                // intrinsic we evaluate the message and end the block with a
+                // panic terminator.
+                self.push_breakpoint_start(brewery, origin);
+                let message = match args.first() {
+                    Some(arg) => self
+                        .brew_named_expr(brewery, *arg)
+                        .map(|(place, _)| Some(place)),
+                    None => Some(None),
+                };
+                if let Some(message) = message {
+                    self.push_breakpoint_end(brewery, message, origin);
+                    self.terminate_and_diverge(
+                        brewery,
+                        bir::TerminatorData::Panic(message),
+                        origin,
+                    );
+                }
+            }
+
             validated::ExprData::Call(func, args) => {
                 self.push_breakpoint_start(brewery, origin);
                 if let Some(func_place) = self.brew_expr_to_temporary(brewery, *func) {
@@ -453,14 +532,23 @@ impl Scope<'_> {
 
             validated::ExprData::Atomic(subexpr) => {
                 self.push_breakpoint_start(brewery, origin);
-                self.terminate_and_continue(brewery, bir::TerminatorData::StartAtomic, origin);
-
-                // FIXME what if a break exits through an atomic?
-
-                self.brew_expr_and_assign_to(brewery, target, *subexpr);
-
-                self.terminate_and_continue(brewery, bir::TerminatorData::EndAtomic, origin);
+                let atomic_block = brewery.dummy_block(origin);
+                let after_block = self.terminate_and_continue(
+                    brewery,
+                    |_| bir::TerminatorData::StartAtomic(atomic_block),
+                    origin,
+                );
                 self.push_breakpoint_end(brewery, Some(target), origin);
+
+                // A `break` or `continue` that exits the atomic section
+                // finds the `ScopeCause::Atomic` and ends it too.
+                let mut atomic_cursor = self.subscope(Some(atomic_block), ScopeCause::Atomic);
+                atomic_cursor.brew_expr_and_assign_to(brewery, target, *subexpr);
+                atomic_cursor.terminate_and_diverge(
+                    brewery,
+                    bir::TerminatorData::EndAtomic(after_block),
+                    origin,
+                );
             }
 
             validated::ExprData::Declare(vars, subexpr) => {
@@ -512,6 +600,10 @@ impl Scope<'_> {
                 let place = brewery.add(bir::PlaceData::Intrinsic(*intrinsic), origin);
                 (place, vec![origin])
             }
+            validated::PlaceData::NativeFunction(name) => {
+                let place = brewery.add(bir::PlaceData::NativeFunction(*name), origin);
+                (place, vec![origin])
+            }
             validated::PlaceData::Class(class) => {
                 let place = brewery.add(bir::PlaceData::Class(*class), origin);
                 (place, vec![origin])
@@ -522,6 +614,13 @@ impl Scope<'_> {
                 origins.push(origin);
                 (place, origins)
             }
+            validated::PlaceData::Index(base, index) => {
+                let (base, mut origins) = self.brew_place(brewery, *base);
+                let (index, _) = self.brew_place(brewery, *index);
+                let place = brewery.add(bir::PlaceData::Index(base, index), origin);
+                origins.push(origin);
+                (place, origins)
+            }
         }
     }
 
@@ -554,3 +653,31 @@ impl Scope<'_> {
         brewery.add(bir::TargetPlaceData::LocalVariable(bir_var), origin)
     }
 }
+
+/// If `op rhs` negates an integer literal (e.g., `-5`), returns the value it
+/// evaluates to, so that it can be brewed as a single literal. Literals whose
+/// negation overflows are not folded, leaving the interpreter to report the error.
+fn negated_literal(
+    tables: &validated::Tables,
+    op: validated::op::UnaryOp,
+    rhs: validated::Expr,
+) -> Option<i64> {
+    if op != validated::op::UnaryOp::Minus {
+        return None;
+    }
+    match rhs.data(tables) {
+        validated::ExprData::IntegerLiteral(value) => i64::try_from(*value).ok().map(|v| -v),
+        validated::ExprData::SignedIntegerLiteral(value) => value.checked_neg(),
+        _ => None,
+    }
+}
+
+/// True if `expr` names the intrinsic `intrinsic` (e.g., the `panic` in `panic("boom")`).
+fn is_intrinsic(tables: &validated::Tables, expr: validated::Expr, intrinsic: Intrinsic) -> bool {
+    match expr.data(tables) {
+        validated::ExprData::Share(place) | validated::ExprData::Give(place) => {
+            matches!(place.data(tables), validated::PlaceData::Intrinsic(i) if *i == intrinsic)
+        }
+        _ => false,
+    }
+}
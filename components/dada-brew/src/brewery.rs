@@ -22,7 +22,7 @@ use dada_ir::{
 /// in the IR; a [`Cursor`](`crate::cursor::Cursor`) is used for that.
 pub struct Brewery<'me> {
     input_file: InputFile,
-    pub(crate) breakpoints: &'me [syntax::Expr],
+    pub(crate) breakpoints: &'me [(usize, syntax::Expr)],
     validated_tree_data: &'me validated::TreeData,
     validated_origins: &'me validated::Origins,
     tables: &'me mut bir::Tables,
@@ -35,7 +35,7 @@ impl<'me> Brewery<'me> {
     pub fn new(
         db: &'me dyn crate::Db,
         input_file: InputFile,
-        breakpoints: &'me [syntax::Expr],
+        breakpoints: &'me [(usize, syntax::Expr)],
         validated_tree: validated::Tree,
         tables: &'me mut bir::Tables,
         origins: &'me mut bir::Origins,
@@ -46,7 +46,7 @@ impl<'me> Brewery<'me> {
         let dummy_terminator = add(
             tables,
             origins,
-            bir::ControlPointData::Terminator(bir::TerminatorData::Panic),
+            bir::ControlPointData::Terminator(bir::TerminatorData::Panic(None)),
             *validated_tree_data.root_expr.origin_in(validated_origins),
         );
         Self {
@@ -61,8 +61,12 @@ impl<'me> Brewery<'me> {
         }
     }
 
+    /// If `expr` is a breakpoint expression, returns the index of the breakpoint.
     pub fn expr_is_breakpoint(&self, expr: syntax::Expr) -> Option<usize> {
-        self.breakpoints.iter().position(|bp| *bp == expr)
+        self.breakpoints
+            .iter()
+            .find(|(_, bp)| *bp == expr)
+            .map(|&(index, _)| index)
     }
 
     pub fn validated_tables(&self) -> &'me validated::Tables {
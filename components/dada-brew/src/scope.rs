@@ -48,8 +48,15 @@ pub(crate) enum ScopeCause {
 
     /// Loop introduced that might be target of a break
     Loop(LoopContext),
+
+    /// Body of an `atomic` block; leaving it (e.g., via `break`)
+    /// requires an `EndAtomic`
+    Atomic,
 }
 
+/// Jump targets for a loop. `break` and `continue` name the loop they
+/// exit (the innermost enclosing one) by its `expr`, so in nested loops
+/// they lower to a `Goto` into the blocks of the right loop.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
 pub struct LoopContext {
     pub expr: validated::Expr,
@@ -123,22 +130,25 @@ impl Scope<'_> {
 
     /// Find and return loop context for a given loop expression,
     /// along with a list of variables whose values must be cleared
-    /// before breaking or continuing from that loop.
+    /// before breaking or continuing from that loop and the number
+    /// of atomic sections that are exited in doing so.
     ///
     /// Panics if that loop context has not been pushed.
     #[track_caller]
     pub fn loop_context(
         &self,
         loop_expr: validated::Expr,
-    ) -> (LoopContext, Vec<bir::LocalVariable>) {
+    ) -> (LoopContext, Vec<bir::LocalVariable>, usize) {
         let mut variables = vec![];
+        let mut atomics = 0;
 
         for s in self.scopes() {
             variables.extend(s.variables.iter());
             match &s.cause {
                 ScopeCause::Loop(c) if c.expr == loop_expr => {
-                    return (*c, variables);
+                    return (*c, variables, atomics);
                 }
+                ScopeCause::Atomic => atomics += 1,
                 _ => {}
             }
         }
@@ -414,6 +424,19 @@ impl Scope<'_> {
         }
     }
 
+    /// Push an `EndAtomic` for each of the `atomics` atomic sections
+    /// that we are exiting.
+    pub(crate) fn push_end_atomics(
+        &mut self,
+        brewery: &mut Brewery<'_>,
+        atomics: usize,
+        origin: ExprOrigin,
+    ) {
+        for _ in 0..atomics {
+            self.terminate_and_continue(brewery, bir::TerminatorData::EndAtomic, origin);
+        }
+    }
+
     pub(crate) fn terminate_and_goto(
         &mut self,
         brewery: &mut Brewery<'_>,
@@ -17,6 +17,7 @@ pub fn check_input_file(db: &dyn crate::Db, input_file: InputFile) {
             }
             Item::Class(class) => {
                 class.signature(db);
+                class.structure(db);
             }
         }
     }
@@ -1,7 +1,7 @@
 use dada_brew::prelude::*;
 use dada_ir::{
     code::bir::Bir,
-    diagnostic::Diagnostic,
+    diagnostic::{Diagnostic, DiagnosticsBySeverity},
     input_file::InputFile,
     item::Item,
     span::{FileSpan, LineColumn, Offset},
@@ -44,7 +44,20 @@ impl salsa::ParallelDatabase for Db {
 impl Db {
     pub fn new_input_file(&mut self, name: impl ToString, source_text: String) -> InputFile {
         let name = Word::intern(self, name);
-        InputFile::new(self, name, source_text, vec![])
+        InputFile::new(self, name, source_text, vec![], vec![], vec![])
+    }
+
+    /// Declare the functions that the host provides to code in the given file.
+    /// The kernel used to run the code must implement them.
+    pub fn set_native_functions(&mut self, input_file: InputFile, names: &[&str]) {
+        let names = names.iter().map(|name| Word::intern(self, name)).collect();
+        input_file.set_native_functions(self, names);
+    }
+
+    /// Allow code in `input_file` to call the functions and use the classes
+    /// defined in `imported_files`.
+    pub fn set_imported_files(&mut self, input_file: InputFile, imported_files: Vec<InputFile>) {
+        input_file.set_imported_files(self, imported_files);
     }
 
     /// Set the breakpoints within the given file where the interpreter stops and executes callbacks.
@@ -59,6 +72,21 @@ impl Db {
         )
     }
 
+    /// Like [`Self::diagnostics`], but separates the errors from the warnings
+    /// (which do not prevent the program from running).
+    pub fn diagnostics_by_severity(&self, input_file: InputFile) -> DiagnosticsBySeverity {
+        self.diagnostics(input_file).into_iter().collect()
+    }
+
+    /// Returns warnings for code in `item` that can never be executed.
+    /// These are not included in [`Self::diagnostics`].
+    pub fn unreachable_code_warnings(&self, item: Item) -> Vec<Diagnostic> {
+        match item.maybe_brew(self) {
+            Some(bir) => bir.unreachable_point_warnings(self),
+            None => vec![],
+        }
+    }
+
     /// Checks `input_file` for a function with the given name
     pub fn main_function(&self, input_file: InputFile) -> Option<Bir> {
         let source_file = input_file.source_file(self);
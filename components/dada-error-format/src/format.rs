@@ -1,7 +1,7 @@
 use std::io::Cursor;
 
 use ariadne::{Config, Label, Report, ReportKind, Source};
-use dada_ir::input_file::InputFile;
+use dada_ir::{diagnostic::Severity, input_file::InputFile};
 
 /// Options for controlling error formatting when they are printed.
 #[derive(Clone, Copy)]
@@ -53,14 +53,23 @@ fn ariadne_diagnostic(
     diagnostic: &dada_ir::diagnostic::Diagnostic,
     options: FormatOptions,
 ) -> eyre::Result<ariadne::Report<ASpan>> {
+    let kind = match diagnostic.severity {
+        Severity::Error => ReportKind::Error,
+        Severity::Warning => ReportKind::Warning,
+        Severity::Note | Severity::Help => ReportKind::Advice,
+    };
     let mut builder = Report::<ASpan>::build(
-        ReportKind::Error,
+        kind,
         diagnostic.span.input_file,
         diagnostic.span.start.into(),
     )
     .with_message(&diagnostic.message)
     .with_config(Config::default().with_color(options.with_color));
 
+    if let Some(code) = diagnostic.code {
+        builder = builder.with_code(code);
+    }
+
     for label in &diagnostic.labels {
         builder = builder.with_label(Label::new(ASpan(label.span())).with_message(&label.message));
     }
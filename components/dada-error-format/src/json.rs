@@ -0,0 +1,79 @@
+use dada_ir::{
+    diagnostic::{Diagnostic, Label, Severity},
+    span::FileSpan,
+};
+use serde_json::json;
+
+/// Serializes diagnostics as a JSON array, for consumption by editors and
+/// other tools. Each diagnostic is an object like:
+///
+/// ```json
+/// {
+///     "severity": "error",
+///     "message": "the class `Point` has no field named `z`",
+///     "code": "E0001",
+///     "span": { "file": "a.dada", "start": 45, "end": 48,
+///               "start_line": 3, "start_column": 7, "end_line": 3, "end_column": 10 },
+///     "labels": [{ "span": { ... }, "message": "here", "primary": true }],
+///     "children": []
+/// }
+/// ```
+///
+/// The `code` is `null` if the diagnostic has none. Offsets are byte
+/// offsets; lines and columns start at 1. A label is
+/// "primary" if it is placed on the diagnostic's own span.
+pub fn format_diagnostics_json(
+    db: &dyn crate::Db,
+    diagnostics: &[Diagnostic],
+) -> serde_json::Value {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic_json(db, diagnostic))
+        .collect()
+}
+
+fn diagnostic_json(db: &dyn crate::Db, diagnostic: &Diagnostic) -> serde_json::Value {
+    json!({
+        "severity": severity_str(diagnostic.severity),
+        "message": diagnostic.message,
+        "code": diagnostic.code,
+        "span": span_json(db, diagnostic.span),
+        "labels": diagnostic
+            .labels
+            .iter()
+            .map(|label| label_json(db, diagnostic, label))
+            .collect::<Vec<_>>(),
+        "children": format_diagnostics_json(db, &diagnostic.children),
+    })
+}
+
+fn label_json(db: &dyn crate::Db, diagnostic: &Diagnostic, label: &Label) -> serde_json::Value {
+    json!({
+        "span": span_json(db, label.span),
+        "message": label.message,
+        "primary": label.span == diagnostic.span,
+    })
+}
+
+fn span_json(db: &dyn crate::Db, span: FileSpan) -> serde_json::Value {
+    let start = dada_ir::lines::line_column(db, span.input_file, span.start);
+    let end = dada_ir::lines::line_column(db, span.input_file, span.end);
+    json!({
+        "file": span.input_file.name(db).as_str(db),
+        "start": usize::from(span.start),
+        "end": usize::from(span.end),
+        "start_line": start.line1(),
+        "start_column": start.column1(),
+        "end_line": end.line1(),
+        "end_column": end.column1(),
+    })
+}
+
+fn severity_str(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Help => "help",
+        Severity::Note => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
@@ -3,6 +3,7 @@
 #![allow(incomplete_features)]
 
 mod format;
+mod json;
 
 #[salsa::jar(db = Db)]
 pub struct Jar();
@@ -14,3 +15,4 @@ pub use format::format_diagnostics;
 pub use format::format_diagnostics_with_options;
 pub use format::print_diagnostic;
 pub use format::FormatOptions;
+pub use json::format_diagnostics_json;
@@ -0,0 +1,334 @@
+//! A public, single-step interface to the interpreter, intended for
+//! tools (like an IDE extension) that want to drive execution themselves.
+
+use dada_brew::prelude::*;
+use dada_collections::Map;
+use dada_ir::{
+    code::bir::{Bir, ControlPoint},
+    diagnostic::{Diagnostic, Diagnostics, Severity},
+    input_file::InputFile,
+    item::Item,
+    span::FileSpan,
+    word::Word,
+};
+use dada_parse::prelude::*;
+
+use crate::{
+    heap_graph::HeapGraph,
+    kernel::{BufferKernel, Kernel},
+    machine::{
+        json::JsonValue,
+        op::MachineOp,
+        stringify::{DefaultStringify, ValueDisplay},
+        Machine, MachineStats, Object, Value,
+    },
+    run::run_to_completion,
+    step::{reachable_from_roots, ControlFlow, GcRoot, Stepper},
+};
+
+/// Executes a function one step at a time.
+///
+/// # Lifecycle
+///
+/// Create the debugger with [`Debugger::new`], which pushes the stack frame
+/// for the function, and then call [`Debugger::step_once`] until it returns
+/// [`DebugStep::Done`]. Between steps, [`Debugger::current_span`] and
+/// [`Debugger::heap_graph`] can be used to inspect the state of the program.
+///
+/// Internally, some steps (e.g., calls to `print`) suspend the machine while
+/// the kernel performs asynchronous work; this is `ControlFlow::Await`.
+/// `step_once` awaits that work to completion before returning, so
+/// the machine is never observed in the middle of such an operation: every
+/// time `step_once` returns, the machine is ready to execute the next step.
+///
+/// Unlike [`interpret`](crate::interpret), the debugger does not enforce
+/// [`Kernel::max_steps`] or [`Kernel::timeout`]; the caller decides how many
+/// steps to take.
+pub struct Debugger<'me> {
+    db: &'me dyn crate::Db,
+    machine: Machine,
+    kernel: &'me mut dyn Kernel,
+    done: bool,
+    check_invariants: bool,
+
+    /// Input files created by [`Debugger::evaluate`], keyed by the file of
+    /// the frame they were evaluated in and their source text. Salsa never
+    /// frees inputs, so evaluating the same expression again (e.g., a watch
+    /// re-evaluated after every step) reuses the input rather than adding
+    /// another one to the database.
+    evaluate_inputs: Map<(InputFile, String), InputFile>,
+}
+
+/// Where execution is, as returned by [`Debugger::current_pc`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PcInfo {
+    /// Name of the function executing in the top-most stack frame.
+    pub function_name: Word,
+
+    /// Span of the code that will execute on the next step.
+    pub span: FileSpan,
+
+    /// The statement (or terminator) of the function's BIR
+    /// that will execute on the next step.
+    pub control_point: ControlPoint,
+}
+
+/// The result of [`Debugger::step_once`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DebugStep {
+    /// The program has more steps to execute.
+    Running,
+
+    /// The function has returned (and its result, if not `()`,
+    /// has been printed). Further calls to `step_once` are an error.
+    Done,
+}
+
+impl<'me> Debugger<'me> {
+    /// Prepares to execute `bir` with the given arguments. No steps are taken
+    /// until [`Debugger::step_once`] is called.
+    pub fn new(
+        db: &'me dyn crate::Db,
+        bir: Bir,
+        kernel: &'me mut dyn Kernel,
+        arguments: Vec<Value>,
+    ) -> Self {
+        let mut machine = Machine::default();
+        machine.push_frame(db, bir, arguments, None);
+        Self {
+            db,
+            machine,
+            kernel,
+            done: false,
+            check_invariants: cfg!(debug_assertions),
+            evaluate_inputs: Map::default(),
+        }
+    }
+
+    /// Enables or disables checking the machine's internal invariants after
+    /// each step (enabled by default in debug builds). A failed check is reported as an
+    /// [`InvariantViolation`](crate::machine::assert_invariants::InvariantViolation)
+    /// error from [`Debugger::step_once`].
+    pub fn with_invariant_checks(mut self, check_invariants: bool) -> Self {
+        self.check_invariants = check_invariants;
+        self
+    }
+
+    /// Enables or disables recording where each object is allocated
+    /// (disabled by default), which [`Debugger::object_origin`] reports.
+    /// Only objects allocated after this call are recorded.
+    pub fn with_object_origins(mut self, track_object_origins: bool) -> Self {
+        self.machine.object_origins = track_object_origins.then(Default::default);
+        self
+    }
+
+    /// Executes a single step, awaiting any asynchronous kernel
+    /// operations that the step requires.
+    pub async fn step_once(&mut self) -> eyre::Result<DebugStep> {
+        if self.done {
+            eyre::bail!("program has already completed");
+        }
+
+        let mut stepper = Stepper::new(self.db, &mut self.machine, self.kernel)
+            .with_invariant_checks(self.check_invariants);
+        match stepper.step()? {
+            ControlFlow::Next => Ok(DebugStep::Running),
+            ControlFlow::Await(t) => {
+                t.invoke(&mut stepper).await?;
+                Ok(DebugStep::Running)
+            }
+            ControlFlow::Done(pc, v) => {
+                stepper.print_if_not_unit(pc, v).await?;
+                drop(stepper);
+                self.kernel.flush().await?;
+                self.done = true;
+                Ok(DebugStep::Done)
+            }
+        }
+    }
+
+    /// True once [`Debugger::step_once`] has returned [`DebugStep::Done`].
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Returns the span of the code that will execute on the next step,
+    /// or `None` if the program has completed.
+    pub fn current_span(&self) -> Option<FileSpan> {
+        let pc = self.machine.opt_pc()?;
+        Some(pc.span(self.db))
+    }
+
+    /// Like [`Debugger::current_span`], but also identifies the function
+    /// and the statement within it, e.g. so that an IDE can highlight the
+    /// current line. Returns `None` if the program has completed.
+    pub fn current_pc(&self) -> Option<PcInfo> {
+        let pc = self.machine.opt_pc()?;
+        Some(PcInfo {
+            function_name: pc.bir.function_name(self.db),
+            span: pc.span(self.db),
+            control_point: pc.control_point,
+        })
+    }
+
+    /// Returns the value of the local variable `name` in the top-most stack
+    /// frame, rendered as a string (`(expired)` if it has no valid permission),
+    /// or `None` if there is no such variable.
+    ///
+    /// Peeking never mutates permissions: unlike evaluating `name` in the
+    /// program, it does not activate the object or cancel any leases on it,
+    /// so inspecting the program state does not perturb it.
+    pub fn peek(&self, name: &str) -> Option<String> {
+        let value = self.local_variable_value(name)?;
+        Some(self.machine.stringify_value(self.db, value))
+    }
+
+    /// Like [`Debugger::peek`], but converts the value into JSON so that a
+    /// Rust host can extract results. Class instances become objects mapping
+    /// field names to values; expired values become `null`.
+    pub fn peek_json(&self, name: &str) -> Option<serde_json::Value> {
+        let value = self.local_variable_value(name)?;
+        Some(self.machine.value_to_json(self.db, value))
+    }
+
+    /// Like [`Debugger::peek`], but also shows the kind of object the variable
+    /// refers to and its permission (see [`Value::display`]). Handy with `dbg!`
+    /// when developing the interpreter.
+    pub fn peek_display(&self, name: &str) -> Option<ValueDisplay<'_>> {
+        let value = self.local_variable_value(name)?;
+        Some(value.display(self.db, &self.machine))
+    }
+
+    /// Evaluates a "watch" expression (e.g., `p.x + 1`) against the top-most
+    /// stack frame and renders the result as [`Debugger::peek`] does.
+    ///
+    /// The expression may refer to the frame's local variables and to the
+    /// functions and classes in scope for the frame's file. It is compiled as
+    /// the body of a function whose parameters are named after those locals,
+    /// which is then called with their current values and run to completion.
+    /// It runs on a copy of the machine and with a [`BufferKernel`] of its own,
+    /// so neither the program state nor its output is affected (e.g., leases
+    /// the expression cancels remain valid, and anything it prints is dropped).
+    ///
+    /// Each distinct expression (per file) adds an input file to the database,
+    /// which lives as long as the database does; repeating an expression
+    /// reuses its input.
+    pub async fn evaluate(&mut self, expression: &str) -> eyre::Result<String> {
+        let db = self.db;
+        let Some(frame) = self.machine.top_frame() else {
+            eyre::bail!("program has already completed");
+        };
+        let frame_input_file = frame.pc.bir.input_file(db);
+
+        // Each name refers to the innermost local with that name, as in `peek`.
+        let mut names: Vec<Word> = vec![];
+        for (_, name) in frame.pc.bir.data(db).named_locals() {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let arguments: Vec<Value> = names
+            .iter()
+            .map(|name| self.local_variable_value(name.as_str(db)).unwrap())
+            .collect();
+
+        let parameters: Vec<&str> = names.iter().map(|name| name.as_str(db)).collect();
+        let source_text = format!(
+            "async fn evaluate({}) -> {{\n{}\n}}\n",
+            parameters.join(", "),
+            expression
+        );
+        let input_file = *self
+            .evaluate_inputs
+            .entry((frame_input_file, source_text))
+            .or_insert_with_key(|(frame_input_file, source_text)| {
+                let mut imported_files = vec![*frame_input_file];
+                imported_files.extend(frame_input_file.imported_files(db));
+                InputFile::new(
+                    db,
+                    Word::intern(db, "<evaluate>"),
+                    source_text.clone(),
+                    vec![],
+                    vec![],
+                    imported_files,
+                )
+            });
+
+        let errors: Vec<Diagnostic> =
+            dada_check::check_input_file::accumulated::<Diagnostics>(db, input_file)
+                .into_iter()
+                .filter(|diagnostic| diagnostic.severity == Severity::Error)
+                .collect();
+        if !errors.is_empty() {
+            eyre::bail!("{}", dada_error_format::format_diagnostics(db, &errors)?);
+        }
+
+        // The expression may have defined items of its own (e.g., if it has
+        // an unbalanced `}`); the function we generated is always first.
+        let Some(&Item::Function(function)) = input_file.items(db).first() else {
+            unreachable!("no function generated for `{expression}`");
+        };
+        let bir = function.brew(db);
+
+        // Only the frame for `evaluate` is on the copy's stack, so running to
+        // completion stops when it returns.
+        let mut machine = self.machine.snapshot();
+        machine.stack = Default::default();
+        machine.push_frame(db, bir, arguments, None);
+
+        let mut kernel = BufferKernel::new();
+        let mut stepper = Stepper::new(db, &mut machine, &mut kernel)
+            .with_invariant_checks(self.check_invariants);
+        let (_pc, value) = run_to_completion(&mut stepper).await?;
+        drop(stepper);
+        Ok(machine.stringify_value(db, value))
+    }
+
+    /// Finds the value of the local variable `name` in the top-most stack frame.
+    fn local_variable_value(&self, name: &str) -> Option<Value> {
+        let frame = self.machine.top_frame()?;
+        let bir_data = frame.pc.bir.data(self.db);
+        let (local_variable, _) = bir_data
+            .named_locals()
+            .filter(|(_, word)| word.as_str(self.db) == name)
+            .last()?;
+        Some(frame.locals[local_variable])
+    }
+
+    /// Returns the objects that are currently alive, each paired with the
+    /// root that keeps it alive, without collecting anything. Useful for
+    /// finding out why an object is retained for longer than expected.
+    pub fn reachable_objects(&self) -> Vec<(Object, GcRoot)> {
+        reachable_from_roots(&self.machine, &[])
+    }
+
+    /// Span of the code that allocated `object`, if object origins are being
+    /// tracked (see [`Debugger::with_object_origins`]).
+    pub fn object_origin(&self, object: Object) -> Option<FileSpan> {
+        self.machine.object_origin(self.db, object)
+    }
+
+    /// Counts of the objects and permissions allocated and collected so far.
+    pub fn stats(&self) -> MachineStats {
+        self.machine.stats()
+    }
+
+    /// Captures the current state of the stack and heap.
+    pub fn heap_graph(&self) -> HeapGraph {
+        HeapGraph::new(self.db, &self.machine, None)
+    }
+
+    /// Captures the entire state of the program (heap, stack, and program
+    /// counter), so that [`Debugger::restore`] can later rewind to it.
+    pub fn snapshot(&self) -> Machine {
+        self.machine.snapshot()
+    }
+
+    /// Rewinds the program to a state captured by [`Debugger::snapshot`];
+    /// stepping resumes from there. Anything the program did through the
+    /// kernel in the meantime (e.g., printing) is not undone.
+    pub fn restore(&mut self, snapshot: Machine) {
+        self.machine.restore(snapshot);
+        self.done = self.machine.opt_pc().is_none();
+    }
+}
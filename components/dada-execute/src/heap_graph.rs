@@ -13,7 +13,15 @@ use dada_ir::{
 use crate::machine::{op::MachineOp, Machine, Object, Permission, Value};
 
 mod capture;
+mod diff;
 mod graphviz;
+mod json;
+mod mermaid;
+mod text;
+mod walk;
+
+pub use diff::{EdgeChange, EdgeSource, HeapDiff};
+pub use graphviz::{GraphvizOptions, RankDir};
 
 pub struct HeapGraph {
     /// Snapshot of the machine that this is a graph of
@@ -88,9 +96,26 @@ pub(crate) enum ObjectType {
     Class(Class),
     Thunk(Function),
     RustThunk(&'static str),
+    List,
+    Result { ok: bool },
     Reservation,
 }
 
+impl ObjectType {
+    /// Name under which objects of this type are shown (e.g., `Point`).
+    pub(crate) fn name<'db>(self, db: &'db dyn crate::Db) -> &'db str {
+        match self {
+            ObjectType::Class(class) => class.name(db).as_str(db),
+            ObjectType::Thunk(function) => function.name(db).as_str(db),
+            ObjectType::RustThunk(d) => d,
+            ObjectType::List => "List",
+            ObjectType::Result { ok: true } => "ok",
+            ObjectType::Result { ok: false } => "err",
+            ObjectType::Reservation => "(reservation)",
+        }
+    }
+}
+
 id!(pub(crate) struct ValueEdge);
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -132,8 +157,9 @@ pub(crate) struct PermissionNodeData {
     lessor: Option<PermissionNode>,
 }
 
-#[derive(Copy, Clone, Debug)]
-pub(crate) enum PermissionNodeLabel {
+/// The kind of permission through which an edge reaches its target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PermissionNodeLabel {
     My,
     Our,
     Leased,
@@ -110,15 +110,28 @@ impl<'me> HeapGraphCapture<'me> {
                 &thunk.arguments,
             )),
             ObjectData::Tuple(_tuple) => self.data_target(db, object, &"<tuple>"), // FIXME
+            ObjectData::List(list) => ValueEdgeTarget::Object(self.instance_node(
+                object,
+                ObjectType::List,
+                &list.elements,
+            )),
+            ObjectData::Result(r) => ValueEdgeTarget::Object(self.instance_node(
+                object,
+                ObjectType::Result { ok: r.ok },
+                &[r.value],
+            )),
             ObjectData::Class(c) => ValueEdgeTarget::Class(*c),
             ObjectData::Function(f) => ValueEdgeTarget::Function(*f),
             ObjectData::Intrinsic(_)
+            | ObjectData::NativeFunction(_)
             | ObjectData::Bool(_)
             | ObjectData::UnsignedInt(_)
             | ObjectData::Int(_)
             | ObjectData::SignedInt(_)
             | ObjectData::Float(_)
             | ObjectData::String(_)
+            | ObjectData::Char(_)
+            | ObjectData::Range(_)
             | ObjectData::Unit(_) => {
                 let string = DefaultStringify::stringify_object(self.machine, self.db, object);
                 self.data_target(db, object, &string)
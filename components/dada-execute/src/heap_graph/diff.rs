@@ -0,0 +1,144 @@
+//! Machine-readable comparison of two heap-graphs.
+
+use dada_collections::{IndexMap, Set};
+use dada_id::InternKey;
+use dada_ir::code::bir::LocalVariable;
+
+use crate::machine::{FrameIndex, Object};
+
+use super::{HeapGraph, PermissionNodeLabel, ValueEdge, ValueEdgeTarget};
+
+/// The differences between two snapshots of the heap, as computed by
+/// [`HeapGraph::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeapDiff {
+    /// Objects that exist in the later snapshot but not the earlier one.
+    pub objects_created: Vec<Object>,
+
+    /// Objects that exist in the earlier snapshot but not the later one.
+    pub objects_freed: Vec<Object>,
+
+    /// Edges whose permission label differs between the two snapshots.
+    pub changed_edges: Vec<EdgeChange>,
+}
+
+/// An edge whose permission label changed between two snapshots.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EdgeChange {
+    pub source: EdgeSource,
+
+    /// Label in the earlier snapshot, or `None` if the edge was not present.
+    pub before: Option<PermissionNodeLabel>,
+
+    /// Label in the later snapshot, or `None` if the edge is no longer present.
+    pub after: Option<PermissionNodeLabel>,
+}
+
+/// Where an edge in the heap-graph starts.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum EdgeSource {
+    /// A local variable of the given stack frame (`0` is the bottom-most frame).
+    Local {
+        frame: FrameIndex,
+        local: LocalVariable,
+    },
+
+    /// The in-flight value of the given stack frame.
+    InFlight(FrameIndex),
+
+    /// The field (or list element, or thunk argument) with the given index.
+    Field { object: Object, index: usize },
+}
+
+impl HeapGraph {
+    /// Compares this heap-graph (the earlier snapshot) against `other` (the later
+    /// snapshot), reporting the objects created and freed and the edges whose
+    /// permission changed in between. An edge that appears (e.g., because a
+    /// variable was assigned) is reported with a `before` of `None`.
+    ///
+    /// Objects and edges are listed in a deterministic order.
+    pub fn diff(&self, other: &HeapGraph) -> HeapDiff {
+        let objects_before = self.machine.heap.all_objects();
+        let objects_after = other.machine.heap.all_objects();
+        let before_set: Set<Object> = objects_before.iter().copied().collect();
+        let after_set: Set<Object> = objects_after.iter().copied().collect();
+
+        let edges_before = self.edge_labels();
+        let edges_after = other.edge_labels();
+        let mut changed_edges = vec![];
+        for (&source, &before) in &edges_before {
+            let after = edges_after.get(&source).copied();
+            if Some(before) != after {
+                changed_edges.push(EdgeChange {
+                    source,
+                    before: Some(before),
+                    after,
+                });
+            }
+        }
+        for (&source, &after) in &edges_after {
+            if !edges_before.contains_key(&source) {
+                changed_edges.push(EdgeChange {
+                    source,
+                    before: None,
+                    after: Some(after),
+                });
+            }
+        }
+
+        HeapDiff {
+            objects_created: objects_after
+                .into_iter()
+                .filter(|o| !before_set.contains(o))
+                .collect(),
+            objects_freed: objects_before
+                .into_iter()
+                .filter(|o| !after_set.contains(o))
+                .collect(),
+            changed_edges,
+        }
+    }
+
+    /// Returns the permission label of every edge in this graph, keyed by where
+    /// the edge starts, in the order the edges are reached from the stack.
+    fn edge_labels(&self) -> IndexMap<EdgeSource, PermissionNodeLabel> {
+        let mut labels = IndexMap::default();
+        let mut edges = vec![];
+        for (index, stack_frame_node) in self.stack.iter().enumerate() {
+            let frame = FrameIndex::from(index);
+            let stack_frame_data = stack_frame_node.data(&self.tables);
+            for variable in &stack_frame_data.variables {
+                let local = variable.id;
+                edges.push((EdgeSource::Local { frame, local }, variable.value));
+            }
+            if let Some(value) = stack_frame_data.in_flight_value {
+                edges.push((EdgeSource::InFlight(frame), value));
+            }
+        }
+
+        edges.reverse();
+
+        let mut visited = Set::default();
+        while let Some((source, edge)) = edges.pop() {
+            labels.insert(source, self.edge_label(edge));
+            let ValueEdgeTarget::Object(object_node) = edge.data(&self.tables).target else {
+                continue;
+            };
+            if !visited.insert(object_node) {
+                continue;
+            }
+            let object_data = object_node.data(&self.tables);
+            let object = object_data.object;
+            for (index, &field) in object_data.fields.iter().enumerate().rev() {
+                edges.push((EdgeSource::Field { object, index }, field));
+            }
+        }
+
+        labels
+    }
+
+    fn edge_label(&self, edge: ValueEdge) -> PermissionNodeLabel {
+        let permission = edge.data(&self.tables).permission;
+        permission.data(&self.tables).label
+    }
+}
@@ -1,15 +1,127 @@
-use dada_collections::{IndexSet, Map};
+use dada_collections::Map;
 use dada_id::InternKey;
 use dada_validate::prelude::*;
+use salsa::DebugWithDb;
+
+use crate::machine::op::MachineOp;
 
 use super::{
-    DataNode, HeapGraph, ObjectType, PermissionNode, PermissionNodeLabel, ValueEdge, ValueEdgeData,
-    ValueEdgeTarget,
+    walk::HeapWalk, DataNode, HeapGraph, ObjectType, PermissionNode, PermissionNodeLabel,
+    ValueEdge, ValueEdgeData, ValueEdgeTarget,
 };
 
 const UNCHANGED: &str = "slategray";
 const CHANGED: &str = "black";
 
+/// Options that customize how a [`HeapGraph`] is rendered to graphviz.
+///
+/// The colors are graphviz color names (e.g., `"red"`) used for the
+/// edges whose permission has the given label.
+#[derive(Clone, Debug)]
+pub struct GraphvizOptions {
+    pub my_color: String,
+    pub our_color: String,
+    pub leased_color: String,
+    pub shared_color: String,
+    pub expired_color: String,
+
+    /// If `Some(n)`, heap nodes more than `n` edges away from the stack
+    /// are not expanded but rendered as a `...` placeholder instead.
+    /// The heap is then walked breadth-first (see [`Self::breadth_first`]),
+    /// so that the distance of a node is that of its shortest path.
+    pub max_depth: Option<usize>,
+
+    /// Background color of the `(in-flight)` row in the stack, which holds
+    /// the value currently being computed (e.g., `"lightyellow"`). If `None`
+    /// (the default), the row is rendered like any other variable.
+    pub in_flight_bgcolor: Option<String>,
+
+    /// If true, each edge label is suffixed with the id of the underlying
+    /// permission node (e.g., `leased #3`). Useful when debugging leases.
+    pub include_permission_ids: bool,
+
+    /// Data values (e.g., strings) whose rendering is at least this many
+    /// characters long are truncated, keeping only their first
+    /// `truncated_head_len` and last `truncated_tail_len` characters.
+    pub truncate_data_at: usize,
+    pub truncated_head_len: usize,
+    pub truncated_tail_len: usize,
+
+    /// Direction in which the graph is laid out.
+    pub rankdir: RankDir,
+
+    /// Graphviz shape used for heap nodes (e.g., `"note"` or `"box"`).
+    pub node_shape: String,
+
+    /// If true, each heap object gets a tooltip with the span of the code
+    /// that allocated it. Only has an effect if the machine was tracking
+    /// object origins (see [`Machine::object_origins`](crate::machine::Machine::object_origins)).
+    pub include_object_origins: bool,
+
+    /// Heap nodes are named (`node0`, `node1`, ...) in the order the
+    /// traversal first reaches them, never from machine object ids, so
+    /// the output doesn't depend on the order in which objects were
+    /// allocated. By default the traversal is depth-first; if true, it is
+    /// breadth-first instead, so nodes are numbered by their distance from
+    /// the stack.
+    pub breadth_first: bool,
+}
+
+/// Direction in which a graphviz graph is laid out; see
+/// [`GraphvizOptions::rankdir`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RankDir {
+    /// Left-to-right (`rankdir = "LR"`), with the stack on the left.
+    #[default]
+    LeftToRight,
+
+    /// Top-to-bottom (`rankdir = "TB"`), with the stack at the top.
+    TopToBottom,
+}
+
+impl RankDir {
+    fn as_str(self) -> &'static str {
+        match self {
+            RankDir::LeftToRight => "LR",
+            RankDir::TopToBottom => "TB",
+        }
+    }
+}
+
+impl Default for GraphvizOptions {
+    fn default() -> Self {
+        Self {
+            my_color: "red".to_string(),
+            our_color: "blue".to_string(),
+            leased_color: "red".to_string(),
+            shared_color: "blue".to_string(),
+            expired_color: "grey".to_string(),
+            max_depth: None,
+            in_flight_bgcolor: None,
+            include_permission_ids: false,
+            truncate_data_at: 40,
+            truncated_head_len: 20,
+            truncated_tail_len: 20,
+            rankdir: RankDir::default(),
+            node_shape: "note".to_string(),
+            include_object_origins: false,
+            breadth_first: false,
+        }
+    }
+}
+
+impl GraphvizOptions {
+    fn permission_color(&self, label: PermissionNodeLabel) -> &str {
+        match label {
+            PermissionNodeLabel::My => &self.my_color,
+            PermissionNodeLabel::Our => &self.our_color,
+            PermissionNodeLabel::Leased => &self.leased_color,
+            PermissionNodeLabel::Shared => &self.shared_color,
+            PermissionNodeLabel::Expired => &self.expired_color,
+        }
+    }
+}
+
 impl HeapGraph {
     /// Plots this heap-graph by itself.
     ///
@@ -23,17 +135,33 @@ impl HeapGraph {
         db: &dyn crate::Db,
         include_temporaries: bool,
         diff_against: Option<&HeapGraph>,
+    ) -> String {
+        self.graphviz_alone_with_options(
+            db,
+            include_temporaries,
+            diff_against,
+            &GraphvizOptions::default(),
+        )
+    }
+
+    /// Like [`Self::graphviz_alone`], but with custom [`GraphvizOptions`].
+    pub fn graphviz_alone_with_options(
+        &self,
+        db: &dyn crate::Db,
+        include_temporaries: bool,
+        diff_against: Option<&HeapGraph>,
+        options: &GraphvizOptions,
     ) -> String {
         let mut output = vec![];
         let mut writer = GraphvizWriter {
             diff_against,
+            options,
             db,
             name_prefix: "",
             writer: &mut std::io::Cursor::new(&mut output),
             indent: 0,
             include_temporaries,
-            node_queue: Default::default(),
-            node_set: Default::default(),
+            walk: Default::default(),
             permissions: Default::default(),
             value_edge_list: vec![],
         };
@@ -49,16 +177,17 @@ impl HeapGraph {
         include_temporaries: bool,
         heap_graph_end: &HeapGraph,
     ) -> String {
+        let options = GraphvizOptions::default();
         let mut output = vec![];
         let mut writer = GraphvizWriter {
             diff_against: None,
+            options: &options,
             db,
             name_prefix: "",
             writer: &mut std::io::Cursor::new(&mut output),
             indent: 0,
             include_temporaries,
-            node_queue: Default::default(),
-            node_set: Default::default(),
+            walk: Default::default(),
             permissions: Default::default(),
             value_edge_list: vec![],
         };
@@ -120,8 +249,8 @@ impl HeapGraph {
         contents: impl FnOnce(&mut GraphvizWriter<'_>) -> eyre::Result<()>,
     ) -> eyre::Result<()> {
         w.indent("digraph {")?;
-        w.println(r#"node[shape = "note"];"#)?;
-        w.println(r#"rankdir = "LR";"#)?;
+        w.println(format!(r#"node[shape = "{}"];"#, w.options.node_shape))?;
+        w.println(format!(r#"rankdir = "{}";"#, w.options.rankdir.as_str()))?;
 
         contents(w)?;
 
@@ -138,7 +267,12 @@ impl HeapGraph {
         let value_edge_list = std::mem::take(&mut w.value_edge_list);
         for value_edge in &value_edge_list {
             let permission_data = value_edge.permission.data(&self.tables);
-            let label = permission_data.label.as_str();
+            let label = if w.options.include_permission_ids {
+                let id = usize::from(value_edge.permission);
+                format!("{} #{id}", permission_data.label.as_str())
+            } else {
+                permission_data.label.as_str().to_string()
+            };
 
             let style = if !permission_data.tenants.is_empty() {
                 "dotted"
@@ -153,11 +287,7 @@ impl HeapGraph {
                 | PermissionNodeLabel::Shared => ("1.0", "empty"),
             };
 
-            let color = match permission_data.label {
-                PermissionNodeLabel::My | PermissionNodeLabel::Leased => "red",
-                PermissionNodeLabel::Shared | PermissionNodeLabel::Our => "blue",
-                PermissionNodeLabel::Expired => "grey",
-            };
+            let color = w.options.permission_color(permission_data.label);
 
             w.println(format!(
                 r#"{source:?}:{source_port} -> {target:?} [label="{label}", style="{style}", penwidth={penwidth}, arrowtype="{arrowtype}", color="{color}"];"#,
@@ -220,12 +350,14 @@ impl HeapGraph {
             )?;
 
             if let Some(in_flight_value) = &stack_frame_data.in_flight_value {
+                let bgcolor = w.options.in_flight_bgcolor.as_deref();
                 self.print_field(
                     w,
                     in_flight_value,
                     Some(&"(in-flight)".to_string()),
                     "stack",
                     field_index,
+                    bgcolor,
                 )?;
                 field_index += 1;
             }
@@ -238,8 +370,16 @@ impl HeapGraph {
     }
 
     fn print_heap(&self, w: &mut GraphvizWriter<'_>) -> eyre::Result<()> {
-        while let Some(edge) = w.node_queue.pop() {
-            self.print_heap_node(w, edge)?;
+        // Walking depth-first, a node can first be reached through a longer
+        // path than its shortest one, and would be truncated too early.
+        let breadth_first = w.options.breadth_first || w.options.max_depth.is_some();
+        while let Some((edge, depth)) = w.walk.next(breadth_first) {
+            if matches!(w.options.max_depth, Some(max_depth) if depth > max_depth) {
+                let name = w.node_name(&edge);
+                w.println(format!(r#"{name} [label = "..."];"#))?;
+            } else {
+                self.print_heap_node(w, edge)?;
+            }
         }
         Ok(())
     }
@@ -255,14 +395,17 @@ impl HeapGraph {
         match edge {
             ValueEdgeTarget::Object(o) => {
                 let data = o.data(&self.tables);
+                if w.options.include_object_origins {
+                    if let Some(span) = self.machine.object_origin(w.db, data.object) {
+                        w.println(format!(
+                            r#"tooltip = "allocated at {:?}","#,
+                            span.debug(w.db)
+                        ))?;
+                    }
+                }
                 let field_names: Vec<_> = self.field_names(w.db, data.ty, data.fields.len());
                 w.indent(r#"label = <<table border="0">"#)?;
-                let class_name = match data.ty {
-                    ObjectType::Class(class) => class.name(w.db).as_str(w.db),
-                    ObjectType::Thunk(function) => function.name(w.db).as_str(w.db),
-                    ObjectType::RustThunk(d) => d,
-                    ObjectType::Reservation => "(reservation)",
-                };
+                let class_name = data.ty.name(w.db);
                 w.println(format!(r#"<tr><td border="1">{class_name}</td></tr>"#))?;
                 self.print_fields(w, &name, field_names, &data.fields, 0)?;
                 w.undent(r#"</table>>"#)?;
@@ -296,7 +439,7 @@ impl HeapGraph {
         Ok(())
     }
 
-    fn field_names(
+    pub(super) fn field_names(
         &self,
         db: &dyn crate::Db,
         ty: ObjectType,
@@ -317,7 +460,11 @@ impl HeapGraph {
                 .map(|i| Some(i.name.to_string(db)))
                 .collect(),
 
-            ObjectType::RustThunk(_) => (0..num_fields).map(|i| Some(format!("{i}"))).collect(),
+            ObjectType::RustThunk(_) | ObjectType::List => {
+                (0..num_fields).map(|i| Some(format!("{i}"))).collect()
+            }
+
+            ObjectType::Result { .. } => vec![Some("value".to_string())],
 
             ObjectType::Reservation => vec![Some("reserved".to_string())],
         }
@@ -332,7 +479,7 @@ impl HeapGraph {
         mut index: usize,
     ) -> eyre::Result<usize> {
         for (edge, name) in edges.into_iter().zip(names) {
-            self.print_field(w, edge, name.as_ref(), source, index)?;
+            self.print_field(w, edge, name.as_ref(), source, index, None)?;
             index += 1;
         }
         Ok(index)
@@ -345,6 +492,7 @@ impl HeapGraph {
         name: Option<&String>,
         source: &str,
         index: usize,
+        bgcolor: Option<&str>,
     ) -> Result<(), eyre::Error> {
         let did_change = self.value_edge_did_change(w, *edge);
         let color = if did_change { CHANGED } else { UNCHANGED };
@@ -358,10 +506,14 @@ impl HeapGraph {
                     port: index,
                 });
 
-            let mut string = format!(r#"<tr><td port="{index}"><font color="{color}">"#);
+            let bgcolor = match bgcolor {
+                Some(bgcolor) => format!(r#" bgcolor="{bgcolor}""#),
+                None => String::new(),
+            };
+            let mut string = format!(r#"<tr><td port="{index}"{bgcolor}><font color="{color}">"#);
             match edge.target {
                 ValueEdgeTarget::Data(d) => {
-                    let data_str = self.data_str(d);
+                    let data_str = self.data_str_with_options(d, w.options);
                     string.push_str(name);
                     string.push_str(": ");
                     string.push_str(&data_str);
@@ -385,14 +537,27 @@ impl HeapGraph {
         Ok(())
     }
 
-    fn data_str(&self, d: DataNode) -> String {
+    pub(super) fn data_str(&self, d: DataNode) -> String {
+        self.data_str_with_options(d, &GraphvizOptions::default())
+    }
+
+    /// Renders the data node `d`, truncated as specified by `options`.
+    fn data_str_with_options(&self, d: DataNode, options: &GraphvizOptions) -> String {
         let data_str = format!("{:?}", d.data(&self.tables).debug);
         let data = html_escape::encode_text(&data_str).to_string();
-        if data.len() < 40 {
+        let num_chars = data.chars().count();
+        // If the head and tail would overlap, there is nothing to cut.
+        if num_chars < options.truncate_data_at
+            || num_chars < options.truncated_head_len + options.truncated_tail_len
+        {
             data
         } else {
-            let len = data.len() - 20;
-            format!("{}[...]{}", &data[0..20], &data[len..])
+            let head: String = data.chars().take(options.truncated_head_len).collect();
+            let tail: String = data
+                .chars()
+                .skip(num_chars - options.truncated_tail_len)
+                .collect();
+            format!("{head}[...]{tail}")
         }
     }
 
@@ -469,12 +634,8 @@ struct GraphvizWriter<'w> {
     /// in the output (usually false).
     include_temporaries: bool,
 
-    /// Queue of edges to process.
-    node_queue: Vec<ValueEdgeTarget>,
-
-    /// Set of all edges we have ever processed; when a new edge
-    /// is added to this set, it is pushed to the queue.
-    node_set: IndexSet<ValueEdgeTarget>,
+    /// Numbers the heap nodes and tracks which remain to be printed.
+    walk: HeapWalk,
 
     /// A collection of edges from fields to their values,
     /// accumulated as we walk the `HeapGraph` and then
@@ -498,6 +659,9 @@ struct GraphvizWriter<'w> {
 
     /// Graphviz to diff against, if any, used for styling.
     diff_against: Option<&'w HeapGraph>,
+
+    /// Options that customize the output.
+    options: &'w GraphvizOptions,
 }
 
 /// Identifies a particular "place" in the graphviz output;
@@ -525,11 +689,11 @@ impl GraphvizWriter<'_> {
             writer: &mut *self.writer,
             indent: self.indent,
             include_temporaries: self.include_temporaries,
-            node_queue: Default::default(),
-            node_set: Default::default(),
+            walk: Default::default(),
             permissions: Default::default(),
             value_edge_list: vec![],
             diff_against: self.diff_against,
+            options: self.options,
         }
     }
 
@@ -540,11 +704,11 @@ impl GraphvizWriter<'_> {
             writer: &mut *self.writer,
             indent: self.indent,
             include_temporaries: self.include_temporaries,
-            node_queue: Default::default(),
-            node_set: Default::default(),
+            walk: Default::default(),
             permissions: Default::default(),
             value_edge_list: vec![],
             diff_against: Some(diff_against),
+            options: self.options,
         }
     }
 
@@ -590,10 +754,7 @@ impl GraphvizWriter<'_> {
     }
 
     fn node_name(&mut self, edge: &ValueEdgeTarget) -> String {
-        let (index, new) = self.node_set.insert_full(*edge);
-        if new {
-            self.node_queue.push(*edge);
-        }
+        let index = self.walk.reach(*edge);
         let np = self.name_prefix;
         format!("{np}node{index}")
     }
@@ -0,0 +1,181 @@
+use dada_collections::IndexSet;
+use dada_id::InternKey;
+use serde_json::json;
+
+use crate::machine::stringify::DefaultStringify;
+
+use super::{walk::HeapWalk, HeapGraph, PermissionNode, ValueEdge, ValueEdgeTarget};
+
+impl HeapGraph {
+    /// Serializes this heap-graph to JSON, for tools that want to consume
+    /// it programmatically rather than parsing the graphviz output.
+    ///
+    /// The heap is walked in the same way as the graphviz rendering
+    /// (temporaries are omitted), so the set of nodes -- and their
+    /// names (`node0`, `node1`, ...) -- match the DOT output.
+    ///
+    /// The schema is:
+    ///
+    /// ```notrust
+    /// {
+    ///   "stack": [{ "function": "main", "variables": [{ "name": "p", "value": EDGE }], "in_flight": EDGE | null }],
+    ///   "nodes": [{ "id": "node0", "kind": "object" | "class" | "function", "name": "Point", "fields": [..] }],
+    ///   "permissions": [{ "id": 0, "label": "my", "tenants": [1], "lessor": null }],
+    /// }
+    /// ```
+    ///
+    /// where `EDGE` is `{ "permission": 0, "target": TARGET }` and `TARGET`
+    /// is one of `{ "node": "node0" }`, `{ "data": "22" }`, or `"expired"`.
+    pub fn to_json(&self, db: &dyn crate::Db) -> serde_json::Value {
+        let mut writer = JsonWriter {
+            db,
+            walk: Default::default(),
+            permissions: Default::default(),
+        };
+
+        let stack: Vec<_> = self
+            .stack
+            .iter()
+            .map(|stack_frame_node| {
+                let stack_frame_data = stack_frame_node.data(&self.tables);
+                let variables: Vec<_> = stack_frame_data
+                    .variables
+                    .iter()
+                    .filter_map(|v| {
+                        let name = v.name?;
+                        Some(json!({
+                            "name": name.as_str(db),
+                            "value": self.json_value_edge(&mut writer, v.value),
+                        }))
+                    })
+                    .collect();
+                let in_flight = stack_frame_data
+                    .in_flight_value
+                    .map(|v| self.json_value_edge(&mut writer, v));
+                json!({
+                    "function": stack_frame_data.function_name.as_str(db),
+                    "variables": variables,
+                    "in_flight": in_flight,
+                })
+            })
+            .collect();
+
+        let mut nodes = vec![];
+        while let Some((target, _)) = writer.walk.next(false) {
+            nodes.push(self.json_node(&mut writer, target));
+        }
+
+        // Permissions can reference one another (via tenants/lessor), so keep
+        // going until we have emitted every permission that was referenced.
+        let mut permissions = vec![];
+        let mut index = 0;
+        while let Some(&permission) = writer.permissions.get_index(index) {
+            let data = permission.data(&self.tables);
+            let tenants: Vec<_> = data
+                .tenants
+                .iter()
+                .map(|&t| writer.permission_id(t))
+                .collect();
+            let lessor = data.lessor.map(|l| writer.permission_id(l));
+            permissions.push(json!({
+                "id": index,
+                "label": data.label.as_str(),
+                "tenants": tenants,
+                "lessor": lessor,
+            }));
+            index += 1;
+        }
+
+        json!({
+            "stack": stack,
+            "nodes": nodes,
+            "permissions": permissions,
+        })
+    }
+
+    fn json_node(&self, w: &mut JsonWriter<'_>, target: ValueEdgeTarget) -> serde_json::Value {
+        let db = w.db;
+        let id = w.node_name(&target);
+        match target {
+            ValueEdgeTarget::Object(o) => {
+                let data = o.data(&self.tables);
+                let name = data.ty.name(db);
+                let field_names = self.field_names(db, data.ty, data.fields.len());
+                let fields: Vec<_> = data
+                    .fields
+                    .iter()
+                    .zip(field_names)
+                    .map(|(&edge, name)| {
+                        json!({
+                            "name": name,
+                            "value": self.json_value_edge(w, edge),
+                        })
+                    })
+                    .collect();
+                json!({
+                    "id": id,
+                    "kind": "object",
+                    "name": name,
+                    "fields": fields,
+                })
+            }
+            ValueEdgeTarget::Class(c) => json!({
+                "id": id,
+                "kind": "class",
+                "name": c.name(db).as_str(db),
+            }),
+            ValueEdgeTarget::Function(f) => json!({
+                "id": id,
+                "kind": "function",
+                "name": f.name(db).as_str(db),
+            }),
+            ValueEdgeTarget::Data(_) | ValueEdgeTarget::Expired => {
+                unreachable!("we do not create nodes for data, expired")
+            }
+        }
+    }
+
+    fn json_value_edge(&self, w: &mut JsonWriter<'_>, edge: ValueEdge) -> serde_json::Value {
+        let edge_data = edge.data(&self.tables);
+        let permission = w.permission_id(edge_data.permission);
+        let target = match edge_data.target {
+            ValueEdgeTarget::Data(d) => {
+                let object = d.data(&self.tables).object;
+                json!({ "data": self.machine.stringify_object(w.db, object) })
+            }
+            ValueEdgeTarget::Expired => json!("expired"),
+            ValueEdgeTarget::Object(_)
+            | ValueEdgeTarget::Class(_)
+            | ValueEdgeTarget::Function(_) => {
+                json!({ "node": w.node_name(&edge_data.target) })
+            }
+        };
+        json!({
+            "permission": permission,
+            "target": target,
+        })
+    }
+}
+
+struct JsonWriter<'w> {
+    /// The crate database.
+    db: &'w dyn crate::Db,
+
+    /// Numbers the nodes and tracks which remain to be emitted.
+    walk: HeapWalk,
+
+    /// Set of all permissions referenced so far; the index
+    /// of a permission in this set is its id in the output.
+    permissions: IndexSet<PermissionNode>,
+}
+
+impl JsonWriter<'_> {
+    fn node_name(&mut self, target: &ValueEdgeTarget) -> String {
+        let index = self.walk.reach(*target);
+        format!("node{index}")
+    }
+
+    fn permission_id(&mut self, permission: PermissionNode) -> usize {
+        self.permissions.insert_full(permission).0
+    }
+}
@@ -0,0 +1,163 @@
+use dada_id::InternKey;
+
+use super::{walk::HeapWalk, HeapGraph, ValueEdge, ValueEdgeTarget};
+
+impl HeapGraph {
+    /// Plots this heap-graph as a [Mermaid](https://mermaid-js.github.io/) flowchart,
+    /// suitable for embedding in markdown.
+    ///
+    /// Each stack frame becomes a subgraph containing one node per variable;
+    /// objects become nodes whose data fields are shown inline. Edges are
+    /// labeled with their permission and are dotted if the permission has tenants.
+    ///
+    /// # Parameters
+    ///
+    /// * `db` -- the salsa database
+    /// * `include_temporaries` -- if true, print temporaries into output (verbose, hard to understand, good for debugging)
+    pub fn mermaid(&self, db: &dyn crate::Db, include_temporaries: bool) -> String {
+        let mut w = MermaidWriter {
+            db,
+            walk: Default::default(),
+            lines: vec!["graph LR".to_string()],
+            edges: vec![],
+        };
+
+        for (frame_index, stack_frame_node) in self.stack.iter().enumerate() {
+            let stack_frame_data = stack_frame_node.data(&self.tables);
+            let function_name = stack_frame_data.function_name.as_str(db);
+            w.lines.push(format!(
+                r#"  subgraph frame{frame_index} ["{}"]"#,
+                escape(function_name)
+            ));
+
+            let variables = stack_frame_data
+                .variables
+                .iter()
+                .filter_map(|v| match v.name {
+                    Some(word) => Some((word.as_str(db).to_string(), v.value)),
+                    None if include_temporaries => Some((format!("{:?}", v.id), v.value)),
+                    None => None,
+                });
+            let in_flight = stack_frame_data
+                .in_flight_value
+                .map(|v| ("(in-flight)".to_string(), v));
+
+            for ((name, value), index) in variables.chain(in_flight).zip(0..) {
+                let node = format!("frame{frame_index}_{index}");
+                let label = self.mermaid_field(&mut w, &node, &name, None, value);
+                w.lines.push(format!(r#"    {node}["{}"]"#, escape(&label)));
+            }
+
+            w.lines.push("  end".to_string());
+        }
+
+        while let Some((target, _)) = w.walk.next(false) {
+            self.mermaid_node(&mut w, target);
+        }
+
+        let MermaidWriter {
+            mut lines, edges, ..
+        } = w;
+        lines.extend(edges);
+        let mut output = lines.join("\n");
+        output.push('\n');
+        output
+    }
+
+    fn mermaid_node(&self, w: &mut MermaidWriter<'_>, target: ValueEdgeTarget) {
+        let db = w.db;
+        let name = w.node_name(&target);
+        let label = match target {
+            ValueEdgeTarget::Object(o) => {
+                let data = o.data(&self.tables);
+                let class_name = data.ty.name(db);
+                let field_names = self.field_names(db, data.ty, data.fields.len());
+                let mut label = format!("<b>{}</b>", escape(class_name));
+                for (&edge, field_name) in data.fields.iter().zip(field_names) {
+                    let Some(field_name) = field_name else {
+                        continue;
+                    };
+                    let field_label =
+                        self.mermaid_field(w, &name, &field_name, Some(&field_name), edge);
+                    label.push_str("<br/>");
+                    label.push_str(&escape(&field_label));
+                }
+                label
+            }
+            ValueEdgeTarget::Class(c) => format!("<b>{}</b>", escape(c.name(db).as_str(db))),
+            ValueEdgeTarget::Function(f) => {
+                format!("<b>{}()</b>", escape(f.name(db).as_str(db)))
+            }
+            ValueEdgeTarget::Data(_) | ValueEdgeTarget::Expired => {
+                unreachable!("we do not create mermaid nodes for data, expired")
+            }
+        };
+        w.lines.push(format!(r#"  {name}["{label}"]"#));
+    }
+
+    /// Returns the text to show for a variable/field called `name` whose
+    /// value is `edge`. Plain data is shown inline; if the value is an object,
+    /// an edge from `source` is recorded instead, labeled with the permission
+    /// (prefixed by `edge_prefix`, if any).
+    fn mermaid_field(
+        &self,
+        w: &mut MermaidWriter<'_>,
+        source: &str,
+        name: &str,
+        edge_prefix: Option<&str>,
+        edge: ValueEdge,
+    ) -> String {
+        let edge_data = edge.data(&self.tables);
+        match edge_data.target {
+            ValueEdgeTarget::Data(d) => format!("{name}: {}", self.data_str(d)),
+            ValueEdgeTarget::Expired => name.to_string(),
+            ValueEdgeTarget::Object(_)
+            | ValueEdgeTarget::Class(_)
+            | ValueEdgeTarget::Function(_) => {
+                let permission_data = edge_data.permission.data(&self.tables);
+                let permission = permission_data.label.as_str();
+                let label = match edge_prefix {
+                    Some(prefix) => format!("{prefix}: {permission}"),
+                    None => permission.to_string(),
+                };
+                let arrow = if !permission_data.tenants.is_empty() {
+                    "-.->"
+                } else {
+                    "-->"
+                };
+                let target = w.node_name(&edge_data.target);
+                w.edges.push(format!(
+                    r#"  {source} {arrow}|"{}"| {target}"#,
+                    escape(&label)
+                ));
+                name.to_string()
+            }
+        }
+    }
+}
+
+/// Escapes a string for use in a quoted mermaid label.
+fn escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+struct MermaidWriter<'w> {
+    /// The crate database.
+    db: &'w dyn crate::Db,
+
+    /// Numbers the nodes and tracks which remain to be declared.
+    walk: HeapWalk,
+
+    /// Node and subgraph declarations.
+    lines: Vec<String>,
+
+    /// Edges between nodes, emitted after all the declarations.
+    edges: Vec<String>,
+}
+
+impl MermaidWriter<'_> {
+    fn node_name(&mut self, target: &ValueEdgeTarget) -> String {
+        let index = self.walk.reach(*target);
+        format!("node{index}")
+    }
+}
@@ -0,0 +1,133 @@
+use dada_id::InternKey;
+
+use super::{walk::HeapWalk, HeapGraph, ValueEdge, ValueEdgeTarget};
+
+impl HeapGraph {
+    /// Renders this heap-graph as an indented tree of plain text, for
+    /// environments (like CI logs) where graphviz is not available.
+    ///
+    /// Each stack frame lists its variables; each object reachable from a
+    /// variable is shown with the permission used to reach it, followed by its
+    /// fields (indented). Objects are numbered (`Point@0`) the first time they
+    /// are reached; later references are shown as `Point@0 (see above)`.
+    /// Unlike the graphviz output, data is shown in full, without escaping.
+    ///
+    /// # Parameters
+    ///
+    /// * `db` -- the salsa database
+    /// * `include_temporaries` -- if true, print temporaries into output (verbose, hard to understand, good for debugging)
+    pub fn to_text(&self, db: &dyn crate::Db, include_temporaries: bool) -> String {
+        let mut w = TextWriter {
+            db,
+            walk: Default::default(),
+            lines: vec![],
+        };
+
+        for stack_frame_node in &self.stack {
+            let stack_frame_data = stack_frame_node.data(&self.tables);
+            w.lines
+                .push(stack_frame_data.function_name.as_str(db).to_string());
+
+            let variables = stack_frame_data
+                .variables
+                .iter()
+                .filter_map(|v| match v.name {
+                    Some(word) => Some((word.as_str(db).to_string(), v.value)),
+                    None if include_temporaries => Some((format!("{:?}", v.id), v.value)),
+                    None => None,
+                });
+            let in_flight = stack_frame_data
+                .in_flight_value
+                .map(|v| ("(in-flight)".to_string(), v));
+
+            for (name, value) in variables.chain(in_flight) {
+                self.text_field(&mut w, 1, &name, value);
+            }
+        }
+
+        let mut output = w.lines.join("\n");
+        output.push('\n');
+        output
+    }
+
+    /// Pushes the line for a variable/field called `name` whose value is `edge`,
+    /// followed by the fields of the object it refers to (if this is the first
+    /// time we have reached that object), and so on recursively.
+    ///
+    /// Uses an explicit stack rather than recursion, so that long chains of
+    /// objects (e.g., linked lists) cannot overflow the Rust stack.
+    fn text_field(&self, w: &mut TextWriter<'_>, depth: usize, name: &str, edge: ValueEdge) {
+        let mut stack = vec![(depth, name.to_string(), edge)];
+        while let Some((depth, name, edge)) = stack.pop() {
+            self.text_field_line(w, depth, &name, edge, &mut stack);
+        }
+    }
+
+    /// Pushes the line for a variable/field called `name` whose value is `edge`.
+    /// If this is the first time we have reached the object it refers to,
+    /// pushes its fields onto `stack` (so that they are printed next).
+    fn text_field_line(
+        &self,
+        w: &mut TextWriter<'_>,
+        depth: usize,
+        name: &str,
+        edge: ValueEdge,
+        stack: &mut Vec<(usize, String, ValueEdge)>,
+    ) {
+        let db = w.db;
+        let indent = "  ".repeat(depth);
+        let edge_data = edge.data(&self.tables);
+        let permission = edge_data.permission.data(&self.tables).label.as_str();
+        match edge_data.target {
+            ValueEdgeTarget::Data(d) => {
+                let data = &d.data(&self.tables).debug;
+                w.lines.push(format!("{indent}{name}: {data:?}"));
+            }
+            ValueEdgeTarget::Expired => {
+                w.lines.push(format!("{indent}{name}: (expired)"));
+            }
+            ValueEdgeTarget::Class(c) => {
+                let class_name = c.name(db).as_str(db);
+                w.lines
+                    .push(format!("{indent}{name}: {permission} class {class_name}"));
+            }
+            ValueEdgeTarget::Function(f) => {
+                let function_name = f.name(db).as_str(db);
+                w.lines
+                    .push(format!("{indent}{name}: {permission} {function_name}()"));
+            }
+            ValueEdgeTarget::Object(o) => {
+                let data = o.data(&self.tables);
+                let type_name = data.ty.name(db);
+                let (index, new) = w.walk.number(edge_data.target);
+                if !new {
+                    w.lines.push(format!(
+                        "{indent}{name}: {permission} {type_name}@{index} (see above)"
+                    ));
+                    return;
+                }
+
+                w.lines
+                    .push(format!("{indent}{name}: {permission} {type_name}@{index}"));
+                let field_names = self.field_names(db, data.ty, data.fields.len());
+                let fields = data.fields.iter().zip(field_names).rev();
+                for (&field, field_name) in fields {
+                    if let Some(field_name) = field_name {
+                        stack.push((depth + 1, field_name, field));
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct TextWriter<'w> {
+    /// The crate database.
+    db: &'w dyn crate::Db,
+
+    /// Numbers the objects we have printed so far.
+    walk: HeapWalk,
+
+    /// Lines of output.
+    lines: Vec<String>,
+}
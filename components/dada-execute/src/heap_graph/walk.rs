@@ -0,0 +1,60 @@
+use std::collections::VecDeque;
+
+use dada_collections::IndexSet;
+
+use super::ValueEdgeTarget;
+
+/// Walks the nodes of a [`HeapGraph`](super::HeapGraph) reachable from the
+/// stack; shared by the various writers (graphviz, mermaid, ...).
+///
+/// Each node is numbered the first time it is reached, and the writers name
+/// nodes after that number (`node0`, `node1`, ...), so the names depend only
+/// on the shape of the graph and not on machine object ids.
+#[derive(Default)]
+pub(super) struct HeapWalk {
+    /// Set of all nodes we have ever reached; the index of a node
+    /// in this set is its number.
+    node_set: IndexSet<ValueEdgeTarget>,
+
+    /// Nodes that were reached but not yet visited, along with their
+    /// depth (the number of edges from the stack).
+    node_queue: VecDeque<(ValueEdgeTarget, usize)>,
+
+    /// Depth of the node currently being visited (0 for the stack).
+    depth: usize,
+}
+
+impl HeapWalk {
+    /// Returns the number of `target`. The first time `target` is
+    /// reached, it is queued to be visited, one level deeper than
+    /// the node currently being visited.
+    pub(super) fn reach(&mut self, target: ValueEdgeTarget) -> usize {
+        let (index, new) = self.node_set.insert_full(target);
+        if new {
+            self.node_queue.push_back((target, self.depth + 1));
+        }
+        index
+    }
+
+    /// Returns the number of `target`, and true if this is the
+    /// first time it was reached. Unlike [`Self::reach`], `target`
+    /// is not queued; for writers that visit nodes as they go.
+    pub(super) fn number(&mut self, target: ValueEdgeTarget) -> (usize, bool) {
+        self.node_set.insert_full(target)
+    }
+
+    /// Pops the next node to visit, along with its depth: the most recently
+    /// reached one (depth-first), or the least recently reached one if
+    /// `breadth_first`.
+    pub(super) fn next(&mut self, breadth_first: bool) -> Option<(ValueEdgeTarget, usize)> {
+        let next = if breadth_first {
+            self.node_queue.pop_front()
+        } else {
+            self.node_queue.pop_back()
+        };
+        if let Some((_, depth)) = next {
+            self.depth = depth;
+        }
+        next
+    }
+}
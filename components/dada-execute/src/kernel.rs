@@ -1,7 +1,8 @@
 //! The "kernel" is the interface from the interpreter to the outside world.
 
-use std::{cmp::Ordering, sync::Arc};
+use std::{cmp::Ordering, sync::Arc, time::Duration};
 
+use dada_collections::Map;
 use dada_ir::{code::bir::Bir, input_file::InputFile, span::FileSpan};
 use salsa::DebugWithDb;
 
@@ -10,6 +11,9 @@ use crate::{
     machine::{ProgramCounter, Value},
 };
 
+/// Default value for [`Kernel::max_stack_depth`].
+pub const DEFAULT_MAX_STACK_DEPTH: usize = 256;
+
 #[async_trait::async_trait]
 pub trait Kernel: Send + Sync {
     /// Implementation for the `print` intrinsic, that prints a line of text.
@@ -20,6 +24,31 @@ pub trait Kernel: Send + Sync {
     /// * `text` -- the string to print
     async fn print(&mut self, await_pc: ProgramCounter, text: &str) -> eyre::Result<()>;
 
+    /// Implementation for the `eprint` intrinsic, that prints a line of text
+    /// to the error stream (stderr).
+    ///
+    /// By default, prints to the same place as [`Self::print`].
+    ///
+    /// # Parameters
+    ///
+    /// * `await_pc` -- the program counter when the thunk was awaited
+    /// * `text` -- the string to print
+    async fn print_err(&mut self, await_pc: ProgramCounter, text: &str) -> eyre::Result<()> {
+        self.print(await_pc, text).await
+    }
+
+    /// Implementation for the `read_line` intrinsic, that reads a line of text
+    /// (without the trailing newline). Returns the empty string at the end of the input.
+    ///
+    /// By default, reports an error, as there is no input to read.
+    ///
+    /// # Parameters
+    ///
+    /// * `await_pc` -- the program counter when the thunk was awaited
+    async fn read_line(&mut self, _await_pc: ProgramCounter) -> eyre::Result<String> {
+        eyre::bail!("stdin not supported by this kernel")
+    }
+
     /// Prints a newline.
     ///
     /// # Parameters
@@ -29,24 +58,88 @@ pub trait Kernel: Send + Sync {
         self.print(await_pc, "\n").await
     }
 
+    /// Invoked once when the program has finished running (after its result,
+    /// if any, has been printed), so that buffered output can be written out.
+    ///
+    /// By default, does nothing.
+    async fn flush(&mut self) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// Invoked when we reach the start of a breakpoint expression, before
+    /// [`Self::breakpoint_start`]. If this returns false, neither
+    /// `breakpoint_start` nor the matching `breakpoint_end` are invoked.
+    ///
+    /// By default, every breakpoint fires unconditionally.
+    fn breakpoint_condition(
+        &mut self,
+        _db: &dyn crate::Db,
+        _breakpoint_input_file: InputFile,
+        _breakpoint_index: usize,
+        _generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
+    ) -> eyre::Result<bool> {
+        Ok(true)
+    }
+
     /// Indicates that we have reached the start of a breakpoint expression.
+    /// `hit_count` is the number of times this breakpoint has been reached
+    /// during the current execution, including this one (so it starts at 1).
     fn breakpoint_start(
         &mut self,
         db: &dyn crate::Db,
         breakpoint_input_file: InputFile,
         breakpoint_index: usize,
+        hit_count: usize,
         generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
     ) -> eyre::Result<()>;
 
     /// Indicates that we have reached the end of a breakpoint expression.
+    /// `hit_count` is as in [`Self::breakpoint_start`].
     fn breakpoint_end(
         &mut self,
         db: &dyn crate::Db,
         breakpoint_input_file: InputFile,
         breakpoint_index: usize,
+        hit_count: usize,
         breakpoint_span: FileSpan,
         generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
     ) -> eyre::Result<()>;
+
+    /// Maximum number of stack frames; calls that would exceed this
+    /// fail with a "stack overflow" error.
+    fn max_stack_depth(&self) -> usize {
+        DEFAULT_MAX_STACK_DEPTH
+    }
+
+    /// Maximum number of steps to execute before stopping with an error;
+    /// `None` (the default) means no limit.
+    fn max_steps(&self) -> Option<u64> {
+        None
+    }
+
+    /// Maximum wall-clock time to run for before stopping with an error;
+    /// `None` (the default) means no limit. The limit is checked between
+    /// steps, so it does not interrupt the kernel while the program is
+    /// awaiting it (e.g., in `read_line`). Setting a timeout requires a
+    /// system clock, so kernels for `wasm32-unknown-unknown` should leave it
+    /// unset.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Implementation for a function provided by the host (see
+    /// `InputFile::native_functions`). The arguments and the result are
+    /// converted to and from JSON so that the host never sees machine values.
+    ///
+    /// By default, there are no native functions.
+    fn call_native_function(
+        &mut self,
+        _db: &dyn crate::Db,
+        name: &str,
+        _arguments: &[serde_json::Value],
+    ) -> eyre::Result<serde_json::Value> {
+        eyre::bail!("no native function named `{name}`")
+    }
 }
 
 #[derive(Default)]
@@ -54,15 +147,32 @@ pub struct BufferKernel {
     stop_at_breakpoint: bool,
     breakpoint_callback: Option<BreakpointCallback>,
     track_output_ranges: bool,
+    max_steps: Option<u64>,
+    timeout: Option<Duration>,
+    max_stack_depth: Option<usize>,
 
     /// Collects the output of the program.
     buffer: String,
 
+    /// Collects the error output of the program (from `eprint`).
+    err_buffer: String,
+
+    /// Input that remains to be read by `read_line`.
+    input: String,
+
     /// Tracks which program counter is responsible for which output.
     buffer_pcs: Vec<OutputRange>,
 
+    /// Conditions registered for particular breakpoints, keyed by their
+    /// input file and index; breakpoints without an entry here fire
+    /// unconditionally.
+    breakpoint_conditions: Map<(InputFile, usize), BreakpointCondition>,
+
+    /// Native functions registered by the host, by name.
+    native_functions: Map<String, NativeFunction>,
+
     /// When we start a breakpoint, we push an entry here.
-    started_breakpoints: Vec<(InputFile, usize, HeapGraph)>,
+    started_breakpoints: Vec<(InputFile, usize, usize, HeapGraph)>,
 
     /// When we end a breakpoint, we construct a `BreakpointHeapGraph` and
     /// either invoke `breakpoint_callback` or else buffer it here.
@@ -85,6 +195,10 @@ impl OutputRange {
 pub struct BreakpointRecord {
     pub breakpoint_input_file: InputFile,
     pub breakpoint_index: usize,
+
+    /// Number of times the breakpoint had been reached (starting at 1).
+    pub hit_count: usize,
+
     pub breakpoint_span: FileSpan,
     pub heap_at_start: HeapGraph,
     pub heap_at_end: HeapGraph,
@@ -100,6 +214,11 @@ impl BreakpointRecord {
 type BreakpointCallback =
     Arc<dyn Fn(&dyn crate::Db, &mut BufferKernel, BreakpointRecord) + Send + Sync>;
 
+type BreakpointCondition = Arc<dyn Fn(&dyn crate::Db, &HeapGraph) -> bool + Send + Sync>;
+
+type NativeFunction =
+    Arc<dyn Fn(&[serde_json::Value]) -> eyre::Result<serde_json::Value> + Send + Sync>;
+
 impl BufferKernel {
     pub fn new() -> Self {
         Self::default()
@@ -126,6 +245,35 @@ impl BufferKernel {
         }
     }
 
+    /// Builder method: if `max_steps` is `Some`, then execution stops
+    /// with an error after that many steps.
+    pub fn max_steps(self, max_steps: Option<u64>) -> Self {
+        Self { max_steps, ..self }
+    }
+
+    /// Builder method: if `timeout` is `Some`, then execution stops
+    /// with an error once it has run for that long.
+    pub fn timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Builder method: overrides the maximum stack depth
+    /// (defaults to [`DEFAULT_MAX_STACK_DEPTH`]).
+    pub fn max_stack_depth(self, max_stack_depth: usize) -> Self {
+        Self {
+            max_stack_depth: Some(max_stack_depth),
+            ..self
+        }
+    }
+
+    /// Builder method: supplies the text that `read_line` will read from.
+    pub fn input(self, input: impl Into<String>) -> Self {
+        Self {
+            input: input.into(),
+            ..self
+        }
+    }
+
     /// Builder method: invoke the given callback instead of accumulating the
     /// heap graph.
     pub fn breakpoint_callback(
@@ -138,6 +286,35 @@ impl BufferKernel {
         }
     }
 
+    /// Builder method: breakpoint `index` of `input_file` only fires if
+    /// `condition` holds for the heap at the start of the breakpoint expression.
+    pub fn breakpoint_condition(
+        mut self,
+        input_file: InputFile,
+        index: usize,
+        condition: impl Fn(&dyn crate::Db, &HeapGraph) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.breakpoint_conditions
+            .insert((input_file, index), Arc::new(condition));
+        self
+    }
+
+    /// Builder method: calls to the native function `name` run `function`.
+    /// The name must also be declared on the input file (see
+    /// `Db::set_native_functions`) for Dada code to refer to it.
+    pub fn native_function(
+        mut self,
+        name: impl Into<String>,
+        function: impl Fn(&[serde_json::Value]) -> eyre::Result<serde_json::Value>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.native_functions
+            .insert(name.into(), Arc::new(function));
+        self
+    }
+
     pub async fn interpret(
         &mut self,
         db: &dyn crate::Db,
@@ -178,6 +355,11 @@ impl BufferKernel {
         std::mem::take(&mut self.buffer)
     }
 
+    /// Borrow the buffered error output.
+    pub fn err_buffer(&self) -> &str {
+        &self.err_buffer
+    }
+
     /// Append text into the output buffer
     pub fn append(&mut self, s: &str) {
         self.buffer.push_str(s);
@@ -287,19 +469,48 @@ impl Kernel for BufferKernel {
         Ok(())
     }
 
+    async fn print_err(&mut self, _await_pc: ProgramCounter, message: &str) -> eyre::Result<()> {
+        self.err_buffer.push_str(message);
+        Ok(())
+    }
+
+    async fn read_line(&mut self, _await_pc: ProgramCounter) -> eyre::Result<String> {
+        let (line, rest) = match self.input.split_once('\n') {
+            Some((line, rest)) => (line.to_string(), rest.to_string()),
+            None => (std::mem::take(&mut self.input), String::new()),
+        };
+        self.input = rest;
+        Ok(line)
+    }
+
+    fn breakpoint_condition(
+        &mut self,
+        db: &dyn crate::Db,
+        input_file: InputFile,
+        index: usize,
+        generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
+    ) -> eyre::Result<bool> {
+        match self.breakpoint_conditions.get(&(input_file, index)) {
+            Some(condition) => Ok(condition(db, &generate_heap_graph())),
+            None => Ok(true),
+        }
+    }
+
     fn breakpoint_start(
         &mut self,
         db: &dyn crate::Db,
         input_file: InputFile,
         index: usize,
+        hit_count: usize,
         generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
     ) -> eyre::Result<()> {
         tracing::debug!(
-            "breakpoint_start(input_file={:?}, index={:?})",
+            "breakpoint_start(input_file={:?}, index={:?}, hit_count={:?})",
             input_file.debug(db),
-            index
+            index,
+            hit_count
         );
-        let tuple = (input_file, index, generate_heap_graph());
+        let tuple = (input_file, index, hit_count, generate_heap_graph());
         self.started_breakpoints.push(tuple);
         Ok(())
     }
@@ -309,16 +520,18 @@ impl Kernel for BufferKernel {
         db: &dyn crate::Db,
         input_file: InputFile,
         index: usize,
+        hit_count: usize,
         span: FileSpan,
         generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
     ) -> eyre::Result<()> {
         tracing::debug!(
-            "breakpoint_end(input_file={:?}, index={:?})",
+            "breakpoint_end(input_file={:?}, index={:?}, hit_count={:?})",
             input_file.debug(db),
-            index
+            index,
+            hit_count
         );
 
-        let Some((breakpoint_input_file, breakpoint_index, heap_at_start)) =
+        let Some((breakpoint_input_file, breakpoint_index, hit_count, heap_at_start)) =
             self.started_breakpoints.pop()
         else {
             panic!(
@@ -332,6 +545,7 @@ impl Kernel for BufferKernel {
         let breakpoint_record = BreakpointRecord {
             breakpoint_input_file,
             breakpoint_index,
+            hit_count,
             breakpoint_span: span,
             heap_at_start,
             heap_at_end: generate_heap_graph(),
@@ -350,4 +564,28 @@ impl Kernel for BufferKernel {
 
         Ok(())
     }
+
+    fn max_stack_depth(&self) -> usize {
+        self.max_stack_depth.unwrap_or(DEFAULT_MAX_STACK_DEPTH)
+    }
+
+    fn max_steps(&self) -> Option<u64> {
+        self.max_steps
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn call_native_function(
+        &mut self,
+        _db: &dyn crate::Db,
+        name: &str,
+        arguments: &[serde_json::Value],
+    ) -> eyre::Result<serde_json::Value> {
+        match self.native_functions.get(name) {
+            Some(function) => function(arguments),
+            None => eyre::bail!("no native function named `{name}`"),
+        }
+    }
 }
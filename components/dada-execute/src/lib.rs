@@ -13,6 +13,7 @@ pub trait Db:
     + dada_ir::Db
     + dada_parse::Db
     + dada_brew::Db
+    + dada_check::Db
     + dada_error_format::Db
     + dada_validate::Db
 {
@@ -23,11 +24,13 @@ impl<T> Db for T where
         + dada_ir::Db
         + dada_parse::Db
         + dada_brew::Db
+        + dada_check::Db
         + dada_error_format::Db
         + dada_validate::Db
 {
 }
 
+mod debugger;
 mod error;
 mod ext;
 pub mod heap_graph;
@@ -38,5 +41,7 @@ mod run;
 mod step;
 mod thunk;
 
+pub use debugger::{DebugStep, Debugger, PcInfo};
 pub use error::DiagnosticError;
-pub use run::interpret;
+pub use run::{call_function, interpret};
+pub use step::GcRoot;
@@ -1,20 +1,23 @@
 //! Defines the "abstract machine" that executes a Dada program.
 
-use dada_collections::IndexVec;
+use dada_collections::{IndexVec, Map};
 use dada_id::id;
 use dada_ir::{
     class::Class,
     code::bir::{self, TerminatorData},
     function::Function,
+    input_file::InputFile,
     intrinsic::Intrinsic,
     span::FileSpan,
     storage::{Joint, Leased},
+    word::Word,
 };
 use generational_arena::Arena;
 
 use crate::thunk::RustThunk;
 
 pub mod assert_invariants;
+pub mod json;
 pub mod op;
 pub mod stringify;
 
@@ -32,6 +35,17 @@ pub struct Machine {
 
     /// For convenience, store a single unit object,
     pub unit_object: Object,
+
+    /// Number of times each breakpoint has been reached during this execution.
+    pub breakpoint_hits: Map<(InputFile, usize), usize>,
+
+    /// Counts of the objects and permissions allocated and collected so far.
+    pub stats: MachineStats,
+
+    /// If `Some`, maps each live object to the program counter at which it
+    /// was allocated. Tracking is off by default, as it costs a map insertion
+    /// per allocation; see [`op::MachineOp::object_origin`].
+    pub object_origins: Option<Map<Object, ProgramCounter>>,
 }
 
 impl Default for Machine {
@@ -42,10 +56,50 @@ impl Default for Machine {
             heap,
             stack: Default::default(),
             unit_object,
+            breakpoint_hits: Default::default(),
+            stats: Default::default(),
+            object_origins: None,
         }
     }
 }
 
+impl Machine {
+    /// True if `tenant` was leased from `lessor`, either directly or via a
+    /// chain of subleases. This is the relationship the heap graph follows
+    /// (through each permission's lessor) to find the place a lease came from.
+    pub fn is_tenant_of(&self, tenant: Permission, lessor: Permission) -> bool {
+        self[lessor]
+            .tenants()
+            .iter()
+            .any(|&t| t == tenant || self.is_tenant_of(tenant, t))
+    }
+
+    /// Describes `permission` as `my`, `leased`, `our`, `shared` or `expired`.
+    pub fn permission_label(&self, permission: Permission) -> &'static str {
+        match &self[permission] {
+            PermissionData::Expired(_) => "expired",
+            PermissionData::Valid(v) => v.as_str(),
+        }
+    }
+}
+
+/// Allocation statistics for a run of the machine, useful for seeing the
+/// allocation pressure of a program (e.g., in a hot loop).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MachineStats {
+    /// Number of objects allocated (not counting the singleton unit object).
+    pub objects_allocated: u64,
+
+    /// Number of objects freed by the garbage collector.
+    pub objects_collected: u64,
+
+    /// Number of permissions allocated, including expired ones.
+    pub permissions_allocated: u64,
+
+    /// Number of permissions freed by the garbage collector.
+    pub permissions_collected: u64,
+}
+
 /// A value is a reference to an object.
 /// It combines the object itself with a permission.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -79,7 +133,7 @@ impl Heap {
         self.objects.get(object.index)
     }
 
-    fn all_objects(&self) -> Vec<Object> {
+    pub(crate) fn all_objects(&self) -> Vec<Object> {
         let mut vec: Vec<_> = self
             .objects
             .iter()
@@ -154,6 +208,9 @@ pub enum ObjectData {
     /// A reference to an intrinsic, like `print`.
     Intrinsic(Intrinsic),
 
+    /// A reference to a function provided by the host.
+    NativeFunction(Word),
+
     /// The value returned by an `async fn` -- captures the function
     /// that was called along with its arguments. When this value is
     /// awaited, the function is actually pushed onto the stack.
@@ -166,6 +223,16 @@ pub enum ObjectData {
     /// A tuple of objects like `(a, b, c)`.
     Tuple(Tuple),
 
+    /// A list of objects like `[a, b, c]`.
+    List(List),
+
+    /// A range of integers, like the result of `range(0, 3)`.
+    Range(Range),
+
+    /// The outcome of a fallible operation, like the result of `ok(22)`
+    /// or `err("not found")`.
+    Result(Outcome),
+
     /// Boolean.
     Bool(bool),
 
@@ -184,6 +251,9 @@ pub enum ObjectData {
     /// String.
     String(String),
 
+    /// A single character.
+    Char(char),
+
     /// Zero-sized unit value.
     Unit(()),
 }
@@ -195,20 +265,77 @@ impl ObjectData {
             ObjectData::Class(_) => "a class".to_string(),
             ObjectData::Function(_) => "a function".to_string(),
             ObjectData::Intrinsic(_) => "a function".to_string(),
+            ObjectData::NativeFunction(_) => "a function".to_string(),
             ObjectData::ThunkFn(f) => {
                 format!("a suspended call to `{}`", f.function.name(db).as_str(db))
             }
             ObjectData::ThunkRust(_) => "a thunk".to_string(),
             ObjectData::Tuple(_) => "a tuple".to_string(),
+            ObjectData::List(_) => "a list".to_string(),
+            ObjectData::Range(_) => "a range".to_string(),
+            ObjectData::Result(_) => "a result".to_string(),
             ObjectData::Bool(_) => "a boolean".to_string(),
             ObjectData::UnsignedInt(_) => "an unsigned integer".to_string(),
             ObjectData::Int(_) => "an integer".to_string(),
             ObjectData::SignedInt(_) => "a signed integer".to_string(),
             ObjectData::Float(_) => "a float".to_string(),
             ObjectData::String(_) => "a string".to_string(),
+            ObjectData::Char(_) => "a character".to_string(),
             ObjectData::Unit(()) => "nothing".to_string(),
         }
     }
+
+    /// True for integers and floats.
+    pub fn is_number(&self) -> bool {
+        matches!(
+            self,
+            ObjectData::Int(_)
+                | ObjectData::UnsignedInt(_)
+                | ObjectData::SignedInt(_)
+                | ObjectData::Float(_)
+        )
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, ObjectData::Float(_))
+    }
+
+    /// Converts a number to a float (possibly losing precision), or returns
+    /// `None` if this is not a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            ObjectData::Int(v) | ObjectData::UnsignedInt(v) => Some(v as f64),
+            ObjectData::SignedInt(v) => Some(v as f64),
+            ObjectData::Float(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// A short name for the runtime type of this value, as returned by the
+    /// `type_of` intrinsic: the class name for instances (e.g., `Point`) and a
+    /// lowercase name (e.g., `int`, `string`) for everything else.
+    pub fn type_name(&self, db: &dyn crate::Db) -> String {
+        match self {
+            ObjectData::Instance(i) => i.class.name(db).as_str(db).to_string(),
+            ObjectData::Class(_) => "class".to_string(),
+            ObjectData::Function(_) | ObjectData::Intrinsic(_) | ObjectData::NativeFunction(_) => {
+                "function".to_string()
+            }
+            ObjectData::ThunkFn(_) | ObjectData::ThunkRust(_) => "thunk".to_string(),
+            ObjectData::Tuple(_) => "tuple".to_string(),
+            ObjectData::List(_) => "list".to_string(),
+            ObjectData::Range(_) => "range".to_string(),
+            ObjectData::Result(_) => "result".to_string(),
+            ObjectData::Bool(_) => "bool".to_string(),
+            ObjectData::UnsignedInt(_) => "uint".to_string(),
+            ObjectData::Int(_) => "int".to_string(),
+            ObjectData::SignedInt(_) => "sint".to_string(),
+            ObjectData::Float(_) => "float".to_string(),
+            ObjectData::String(_) => "string".to_string(),
+            ObjectData::Char(_) => "char".to_string(),
+            ObjectData::Unit(()) => "unit".to_string(),
+        }
+    }
 }
 
 macro_rules! object_data_from_impls {
@@ -231,11 +358,15 @@ object_data_from_impls! {
     ThunkFn(ThunkFn),
     ThunkRust(RustThunk),
     Tuple(Tuple),
+    List(List),
+    Range(Range),
+    Result(Outcome),
     Bool(bool),
     UnsignedInt(u64),
     SignedInt(i64),
     Float(f64),
     String(String),
+    Char(char),
     Unit(()),
 }
 
@@ -261,6 +392,27 @@ pub struct Tuple {
     pub fields: Vec<Value>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct List {
+    pub elements: Vec<Value>,
+}
+
+/// The integers from `start` up to `end`, which is included only if
+/// `inclusive` is true.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Range {
+    pub start: i64,
+    pub end: i64,
+    pub inclusive: bool,
+}
+
+/// Either `ok(value)` (if `ok` is true) or `err(value)`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub ok: bool,
+    pub value: Value,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Permission {
     index: generational_arena::Index,
@@ -277,7 +429,9 @@ impl std::fmt::Debug for Permission {
 pub enum PermissionData {
     /// No permission: if the place is non-none, executing this place is
     /// what caused the permission to be revoked. If None, the permission
-    /// was never granted (e.g., uninitialized memory).
+    /// was never granted (e.g., uninitialized memory). In particular, local
+    /// variables start out with such a permission and are reset to one
+    /// when cleared, so reading them reports "variable used before assignment".
     Expired(Option<ProgramCounter>),
 
     Valid(ValidPermissionData),
@@ -408,6 +562,32 @@ pub struct Frame {
     pub pc: ProgramCounter,
     pub locals: IndexVec<bir::LocalVariable, Value>,
     pub expected_return_ty: Option<ExpectedTy>,
+
+    /// Number of `atomic` sections that this frame is currently
+    /// executing inside of. Incremented by `StartAtomic` and
+    /// decremented by `EndAtomic`.
+    pub atomic_depth: usize,
+
+    /// Breakpoints whose condition did not hold when their
+    /// `BreakpointStart` executed in this frame; the matching
+    /// `BreakpointEnd` is skipped as well.
+    pub suppressed_breakpoints: Vec<(InputFile, usize)>,
+
+    /// Results of the thunks awaited so far by the `AwaitAll`
+    /// terminator that this frame is currently executing.
+    pub awaited_values: Vec<Value>,
+}
+
+/// Summary of a single stack frame, for reporting errors.
+/// See [`op::MachineOp::stack_trace`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Name of the function executing in this frame.
+    pub function_name: Word,
+
+    /// Span of the code currently executing in this frame
+    /// (for frames other than the top-most, this is the call site).
+    pub span: FileSpan,
 }
 
 /// Describes a type we expect a value to have.
@@ -475,13 +655,72 @@ impl ProgramCounter {
         )
     }
 
+    /// True if this PC represents a statement that gives away a place
+    /// (e.g., `q = p.give`).
+    pub fn is_give(&self, db: &dyn crate::Db) -> bool {
+        let bir_data = self.bir.data(db);
+        let bir::ControlPointData::Statement(statement) = &bir_data.tables[self.control_point]
+        else {
+            return false;
+        };
+        let bir::ActionData::AssignExpr(_, expr) = statement.action else {
+            return false;
+        };
+        matches!(bir_data.tables[expr], bir::ExprData::Give(_))
+    }
+
     pub fn span(&self, db: &dyn crate::Db) -> FileSpan {
-        // FIXME: This code is copied/adapter from Stepper::span_from_bir,
-        // it seems like we could create some helper functions, maybe on the
-        // Bir type itself.
+        self.bir
+            .point_span(db, self.control_point)
+            .expect("program counter refers to a control point in its own BIR")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salsa::AsId;
+
+    use super::*;
+    use crate::machine::op::MachineOp;
+
+    #[test]
+    fn subleases_are_tenants_of_the_original_lessor() {
+        // Nothing here looks at the program counter, so a placeholder is
+        // good enough.
+        let pc = ProgramCounter {
+            bir: bir::Bir::from_id(salsa::Id::from(0_u32)),
+            control_point: bir::ControlPoint::zero(),
+        };
+        let leased = |joint| ValidPermissionData {
+            joint,
+            leased: Leased::Yes,
+            easements: vec![],
+            tenants: vec![],
+            pc,
+        };
 
-        let origins = self.bir.origins(db);
-        let syntax_expr = origins[self.control_point];
-        self.bir.span_of(db, syntax_expr)
+        // `owner` is leased to `lease`, which is subleased (shared) to `sublease`.
+        let mut machine = Machine::default();
+        let owner = machine.new_permission(ValidPermissionData::my(pc));
+        let lease = machine.new_permission(leased(Joint::No));
+        let sublease = machine.new_permission(leased(Joint::Yes));
+        let other = machine.new_permission(ValidPermissionData::our(pc));
+        let expired = machine.expired_permission(None);
+        machine[owner].assert_valid_mut().tenants.push(lease);
+        machine[lease].assert_valid_mut().tenants.push(sublease);
+
+        assert!(machine.is_tenant_of(lease, owner));
+        assert!(machine.is_tenant_of(sublease, lease));
+        assert!(machine.is_tenant_of(sublease, owner));
+        assert!(!machine.is_tenant_of(owner, lease));
+        assert!(!machine.is_tenant_of(owner, owner));
+        assert!(!machine.is_tenant_of(other, owner));
+        assert!(!machine.is_tenant_of(lease, expired));
+
+        assert_eq!(machine.permission_label(owner), "my");
+        assert_eq!(machine.permission_label(lease), "leased");
+        assert_eq!(machine.permission_label(sublease), "shared");
+        assert_eq!(machine.permission_label(other), "our");
+        assert_eq!(machine.permission_label(expired), "expired");
     }
 }
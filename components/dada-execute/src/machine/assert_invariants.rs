@@ -1,8 +1,33 @@
 use dada_collections::Map;
-use dada_ir::storage::{Joint, Leased};
+use dada_ir::{
+    span::FileSpan,
+    storage::{Joint, Leased},
+};
 
 use crate::machine::{op::MachineOp, Frame, Object, ObjectData, Permission, PermissionData, Value};
 
+/// Report produced when one of the machine invariants does not hold.
+/// This represents a bug in dada's type system or operational semantics.
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+#[error("invariant {invariant} violated: {message}")]
+pub struct InvariantViolation {
+    /// Name of the invariant that failed (e.g., `I0`).
+    pub invariant: &'static str,
+
+    /// Description of what went wrong.
+    pub message: String,
+
+    /// The permission involved in the violation, if any.
+    pub permission: Option<Permission>,
+
+    /// The objects involved in the violation.
+    pub objects: Vec<Object>,
+
+    /// Span of the code being executed when the violation was detected,
+    /// if known.
+    pub span: Option<FileSpan>,
+}
+
 pub(super) struct AssertInvariants<'me> {
     machine: &'me dyn MachineOp,
 
@@ -18,7 +43,7 @@ impl<'me> AssertInvariants<'me> {
         }
     }
 
-    pub(super) fn assert_all_ok(&mut self) -> eyre::Result<()> {
+    pub(super) fn assert_all_ok(&mut self) -> Result<(), InvariantViolation> {
         for frame in self.machine.frames() {
             self.assert_frame_ok(frame)?;
         }
@@ -34,31 +59,38 @@ impl<'me> AssertInvariants<'me> {
         Ok(())
     }
 
-    fn assert_frame_ok(&mut self, frame: &Frame) -> eyre::Result<()> {
+    fn assert_frame_ok(&mut self, frame: &Frame) -> Result<(), InvariantViolation> {
         for v in &frame.locals {
             self.assert_value_ok(v)?;
         }
 
+        self.assert_values_ok(&frame.awaited_values)?;
+
         Ok(())
     }
 
-    fn assert_object_ok(&mut self, object: Object) -> eyre::Result<()> {
+    fn assert_object_ok(&mut self, object: Object) -> Result<(), InvariantViolation> {
         let object_data: &ObjectData = &self.machine[object];
         match object_data {
             ObjectData::Instance(i) => self.assert_values_ok(&i.fields)?,
             ObjectData::ThunkFn(f) => self.assert_values_ok(&f.arguments)?,
             ObjectData::ThunkRust(f) => self.assert_values_ok(&f.arguments)?,
             ObjectData::Tuple(t) => self.assert_values_ok(&t.fields)?,
+            ObjectData::List(l) => self.assert_values_ok(&l.elements)?,
+            ObjectData::Result(r) => self.assert_values_ok(&[r.value])?,
 
             ObjectData::Class(_)
             | ObjectData::Function(_)
             | ObjectData::Intrinsic(_)
+            | ObjectData::NativeFunction(_)
             | ObjectData::Bool(_)
             | ObjectData::UnsignedInt(_)
             | ObjectData::Int(_)
             | ObjectData::SignedInt(_)
             | ObjectData::Float(_)
             | ObjectData::String(_)
+            | ObjectData::Char(_)
+            | ObjectData::Range(_)
             | ObjectData::Unit(_) => {
                 // no reachable data
             }
@@ -66,11 +98,11 @@ impl<'me> AssertInvariants<'me> {
         Ok(())
     }
 
-    fn assert_permission_ok(&mut self, _permission: Permission) -> eyre::Result<()> {
+    fn assert_permission_ok(&mut self, _permission: Permission) -> Result<(), InvariantViolation> {
         Ok(())
     }
 
-    fn assert_values_ok(&mut self, values: &[Value]) -> eyre::Result<()> {
+    fn assert_values_ok(&mut self, values: &[Value]) -> Result<(), InvariantViolation> {
         for v in values {
             self.assert_value_ok(v)?;
         }
@@ -78,7 +110,7 @@ impl<'me> AssertInvariants<'me> {
         Ok(())
     }
 
-    fn assert_value_ok(&mut self, value: &Value) -> eyre::Result<()> {
+    fn assert_value_ok(&mut self, value: &Value) -> Result<(), InvariantViolation> {
         let PermissionData::Valid(valid) = &self.machine[value.permission] else {
             return Ok(());
         };
@@ -87,12 +119,16 @@ impl<'me> AssertInvariants<'me> {
             // Invariant I0: Every owned or exclusive permission should be associated with exactly one object across the entire machine.
             if let Some(other_object) = self.permission_map.insert(value.permission, value.object) {
                 if value.object != other_object {
-                    eyre::bail!(
-                        "owned permission {:?} associated with at least two objects: {:?} and {:?}",
-                        value.permission,
-                        value.object,
-                        other_object
-                    );
+                    return Err(InvariantViolation {
+                        invariant: "I0",
+                        message: format!(
+                            "owned permission {:?} associated with at least two objects: {:?} and {:?}",
+                            value.permission, value.object, other_object
+                        ),
+                        permission: Some(value.permission),
+                        objects: vec![value.object, other_object],
+                        span: None,
+                    });
                 }
             }
         }
@@ -100,3 +136,51 @@ impl<'me> AssertInvariants<'me> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dada_ir::code::bir;
+    use salsa::AsId;
+
+    use super::*;
+    use crate::machine::{op::MachineOp, Machine, ProgramCounter, Tuple, ValidPermissionData};
+
+    #[test]
+    fn shared_owned_permission_is_reported() {
+        // The invariant checks never look at the program counter, so a
+        // placeholder is good enough here.
+        let pc = ProgramCounter {
+            bir: bir::Bir::from_id(salsa::Id::from(0_u32)),
+            control_point: bir::ControlPoint::zero(),
+        };
+
+        // Corrupt the machine: one owned permission used for two different objects.
+        let mut machine = Machine::default();
+        let permission = machine.new_permission(ValidPermissionData::my(pc));
+        let object1 = machine.new_object(ObjectData::Int(1));
+        let object2 = machine.new_object(ObjectData::Int(2));
+        machine.new_object(ObjectData::Tuple(Tuple {
+            fields: vec![
+                Value {
+                    object: object1,
+                    permission,
+                },
+                Value {
+                    object: object2,
+                    permission,
+                },
+            ],
+        }));
+
+        let violation = AssertInvariants {
+            machine: &machine,
+            permission_map: Default::default(),
+        }
+        .assert_all_ok()
+        .expect_err("expected an invariant violation");
+        assert_eq!(violation.invariant, "I0");
+        assert_eq!(violation.permission, Some(permission));
+        assert_eq!(violation.objects, vec![object2, object1]);
+        assert_eq!(violation.span, None);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::machine::{
+    ObjectData, PermissionData, ProgramCounter, Tuple, ValidPermissionData, Value,
+};
+
+use super::{op::MachineOp, stringify::DefaultStringify, Object};
+
+#[extension_trait::extension_trait]
+pub(crate) impl<T: ?Sized + MachineOp> JsonValue for T {
+    /// Converts a given value into JSON, for handing results to a Rust host.
+    ///
+    /// Primitives become the corresponding JSON values, tuples and lists become
+    /// arrays, and class instances become objects mapping field names to values.
+    /// Expired values become `null`. If an object is reached again while
+    /// converting its own fields, the cycle is broken with a marker like
+    /// `{"$cycle": "Point"}`. Other values (classes, functions, thunks) become
+    /// the string that `print` would show.
+    fn value_to_json(&self, db: &dyn crate::Db, value: Value) -> serde_json::Value {
+        self.value_to_json_in(db, value, &mut vec![])
+    }
+
+    /// Converts `value` into JSON; `in_progress` are the objects whose fields
+    /// we are in the middle of converting.
+    fn value_to_json_in(
+        &self,
+        db: &dyn crate::Db,
+        value: Value,
+        in_progress: &mut Vec<Object>,
+    ) -> serde_json::Value {
+        if let PermissionData::Expired(_) = self[value.permission] {
+            return serde_json::Value::Null;
+        }
+
+        let object = value.object;
+        if in_progress.contains(&object) {
+            return serde_json::json!({ "$cycle": self[object].type_name(db) });
+        }
+
+        match &self[object] {
+            ObjectData::Bool(v) => serde_json::Value::from(*v),
+            ObjectData::UnsignedInt(v) | ObjectData::Int(v) => serde_json::Value::from(*v),
+            ObjectData::SignedInt(v) => serde_json::Value::from(*v),
+            ObjectData::Float(v) => serde_json::Value::from(*v),
+            ObjectData::String(s) => serde_json::Value::from(s.as_str()),
+            ObjectData::Char(c) => serde_json::Value::from(c.to_string()),
+            ObjectData::Unit(()) => serde_json::Value::Null,
+            ObjectData::Tuple(t) => {
+                in_progress.push(object);
+                let elements = self.values_to_json(db, &t.fields, in_progress);
+                in_progress.pop();
+                serde_json::Value::Array(elements)
+            }
+            ObjectData::List(l) => {
+                in_progress.push(object);
+                let elements = self.values_to_json(db, &l.elements, in_progress);
+                in_progress.pop();
+                serde_json::Value::Array(elements)
+            }
+            ObjectData::Result(r) => {
+                in_progress.push(object);
+                let value = self.value_to_json_in(db, r.value, in_progress);
+                in_progress.pop();
+                let tag = if r.ok { "ok" } else { "err" };
+                serde_json::json!({ tag: value })
+            }
+            ObjectData::Instance(i) => {
+                in_progress.push(object);
+                let fields = i
+                    .class
+                    .field_infos(db)
+                    .into_iter()
+                    .map(|field| field.name.as_str(db).to_string())
+                    .zip(self.values_to_json(db, &i.fields, in_progress))
+                    .collect();
+                in_progress.pop();
+                serde_json::Value::Object(fields)
+            }
+            ObjectData::Class(_)
+            | ObjectData::Function(_)
+            | ObjectData::Intrinsic(_)
+            | ObjectData::NativeFunction(_)
+            | ObjectData::ThunkFn(_)
+            | ObjectData::ThunkRust(_)
+            | ObjectData::Range(_) => serde_json::Value::from(self.stringify_object(db, object)),
+        }
+    }
+
+    /// Converts JSON from a Rust host into a value, the inverse of
+    /// [`Self::value_to_json`]: `null` becomes `()` and arrays become tuples.
+    /// Returns `None` if the JSON (or one of its elements) is an object, which
+    /// has no corresponding Dada value. The new values are attributed to `pc`.
+    fn json_to_value(&mut self, pc: ProgramCounter, json: &serde_json::Value) -> Option<Value> {
+        let (data, permission) = match json {
+            serde_json::Value::Null => (ObjectData::Unit(()), ValidPermissionData::our(pc)),
+            serde_json::Value::Bool(v) => (ObjectData::Bool(*v), ValidPermissionData::our(pc)),
+            serde_json::Value::Number(n) => {
+                let data = match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                    (Some(v), _, _) => ObjectData::Int(v),
+                    (None, Some(v), _) => ObjectData::SignedInt(v),
+                    (None, None, Some(v)) => ObjectData::Float(v),
+                    (None, None, None) => return None,
+                };
+                (data, ValidPermissionData::our(pc))
+            }
+            serde_json::Value::String(s) => {
+                (ObjectData::String(s.clone()), ValidPermissionData::our(pc))
+            }
+            serde_json::Value::Array(elements) => {
+                let fields = elements
+                    .iter()
+                    .map(|element| self.json_to_value(pc, element))
+                    .collect::<Option<Vec<_>>>()?;
+                (
+                    ObjectData::Tuple(Tuple { fields }),
+                    ValidPermissionData::my(pc),
+                )
+            }
+            serde_json::Value::Object(_) => return None,
+        };
+        let permission = self.new_permission(permission);
+        let object = self.new_object(data);
+        Some(Value { object, permission })
+    }
+
+    fn values_to_json(
+        &self,
+        db: &dyn crate::Db,
+        values: &[Value],
+        in_progress: &mut Vec<Object>,
+    ) -> Vec<serde_json::Value> {
+        values
+            .iter()
+            .map(|&value| self.value_to_json_in(db, value, in_progress))
+            .collect()
+    }
+}
@@ -1,11 +1,12 @@
 use std::fmt::Debug;
 
 use dada_collections::IndexVec;
-use dada_ir::code::bir;
+use dada_ir::{code::bir, input_file::InputFile, span::FileSpan};
 
 use super::{
-    assert_invariants::AssertInvariants, ExpectedTy, Frame, FrameIndex, Machine, Object,
-    ObjectData, Permission, PermissionData, ProgramCounter, ValidPermissionData, Value,
+    assert_invariants::{AssertInvariants, InvariantViolation},
+    ExpectedTy, Frame, FrameIndex, FrameInfo, Machine, MachineStats, Object, ObjectData,
+    Permission, PermissionData, ProgramCounter, ValidPermissionData, Value,
 };
 
 pub(crate) trait MachineOp:
@@ -38,6 +39,11 @@ pub(crate) trait MachineOp:
     fn unit_object(&self) -> Object;
     fn all_objects(&self) -> Vec<Object>;
 
+    /// Span of the code that allocated `object`, or `None` if the machine is
+    /// not tracking object origins (see [`Machine::object_origins`]) or the
+    /// object was allocated with no stack frame.
+    fn object_origin(&self, db: &dyn crate::Db, object: Object) -> Option<FileSpan>;
+
     fn permission(&self, permission: Permission) -> &PermissionData;
     fn permission_mut(&mut self, permission: Permission) -> &mut PermissionData;
     fn take_permission(&mut self, permission: Permission) -> PermissionData;
@@ -56,9 +62,57 @@ pub(crate) trait MachineOp:
     // Read PC from top-most frame, or None if stack is empty.
     fn opt_pc(&self) -> Option<ProgramCounter>;
 
+    /// Records that the top-most frame has entered an `atomic` section.
+    fn enter_atomic(&mut self);
+
+    /// Records that the top-most frame has exited an `atomic` section.
+    fn exit_atomic(&mut self);
+
+    /// Total number of `atomic` sections we are currently nested within,
+    /// summed across all frames on the stack. While this is nonzero,
+    /// execution must not be suspended.
+    fn atomic_depth(&self) -> usize;
+
+    /// Describes each frame on the stack, starting from the top-most one.
+    fn stack_trace(&self, db: &dyn crate::Db) -> Vec<FrameInfo>;
+
+    /// Records that breakpoint `index` in `input_file` has been reached and
+    /// returns the number of times it has been reached so far (starting at 1).
+    fn record_breakpoint_hit(&mut self, input_file: InputFile, index: usize) -> usize;
+
+    /// Number of times breakpoint `index` in `input_file` has been reached.
+    fn breakpoint_hit_count(&self, input_file: InputFile, index: usize) -> usize;
+
+    /// Records that the breakpoint `index` in `input_file` was started in the
+    /// top-most frame but its condition did not hold, so it should not fire.
+    fn suppress_breakpoint(&mut self, input_file: InputFile, index: usize);
+
+    /// If breakpoint `index` in `input_file` was suppressed in the top-most frame,
+    /// forgets that and returns true.
+    fn take_suppressed_breakpoint(&mut self, input_file: InputFile, index: usize) -> bool;
+
+    /// Records the result of one of the thunks awaited by the `AwaitAll`
+    /// terminator executing in the top-most frame.
+    fn push_awaited_value(&mut self, value: Value);
+
+    /// Returns (and clears) the results recorded by [`Self::push_awaited_value`]
+    /// for the top-most frame.
+    fn take_awaited_values(&mut self) -> Vec<Value>;
+
+    /// Counts of the objects and permissions allocated and collected so far.
+    fn stats(&self) -> MachineStats;
+
+    /// Records that the garbage collector freed the given number of
+    /// objects and permissions.
+    fn record_collection(&mut self, objects: usize, permissions: usize);
+
     /// Clones the machine into a snapshot of the underlying data.
     /// Used for heapgraphs and introspection.
     fn snapshot(&self) -> Machine;
+
+    /// Restores the machine (heap, stack, and program counter) to a
+    /// state previously captured with [`MachineOp::snapshot`].
+    fn restore(&mut self, snapshot: Machine);
 }
 
 impl MachineOp for Machine {
@@ -110,6 +164,9 @@ impl MachineOp for Machine {
             },
             locals,
             expected_return_ty,
+            atomic_depth: 0,
+            suppressed_breakpoints: vec![],
+            awaited_values: vec![],
         });
     }
 
@@ -159,6 +216,9 @@ impl MachineOp for Machine {
 
     #[track_caller]
     fn take_object(&mut self, object: Object) -> ObjectData {
+        if let Some(object_origins) = &mut self.object_origins {
+            object_origins.remove(&object);
+        }
         self.heap
             .objects
             .remove(object.index)
@@ -169,7 +229,13 @@ impl MachineOp for Machine {
         if let ObjectData::Unit(()) = data {
             return self.unit_object;
         }
-        self.heap.new_object(data)
+        self.stats.objects_allocated += 1;
+        let pc = self.opt_pc();
+        let object = self.heap.new_object(data);
+        if let (Some(object_origins), Some(pc)) = (&mut self.object_origins, pc) {
+            object_origins.insert(object, pc);
+        }
+        object
     }
 
     fn unit_object(&self) -> Object {
@@ -180,6 +246,11 @@ impl MachineOp for Machine {
         self.heap.all_objects()
     }
 
+    fn object_origin(&self, db: &dyn crate::Db, object: Object) -> Option<FileSpan> {
+        let pc = self.object_origins.as_ref()?.get(&object)?;
+        Some(pc.span(db))
+    }
+
     #[track_caller]
     fn permission(&self, permission: Permission) -> &PermissionData {
         self.heap
@@ -202,6 +273,7 @@ impl MachineOp for Machine {
     }
 
     fn new_permission(&mut self, data: ValidPermissionData) -> Permission {
+        self.stats.permissions_allocated += 1;
         self.heap.new_permission(PermissionData::Valid(data))
     }
 
@@ -210,6 +282,7 @@ impl MachineOp for Machine {
     }
 
     fn expired_permission(&mut self, place: Option<ProgramCounter>) -> Permission {
+        self.stats.permissions_allocated += 1;
         self.heap.new_permission(PermissionData::Expired(place))
     }
 
@@ -233,9 +306,100 @@ impl MachineOp for Machine {
         self.stack.frames.last_mut().unwrap().pc = pc;
     }
 
+    #[track_caller]
+    fn enter_atomic(&mut self) {
+        self.stack.frames.last_mut().unwrap().atomic_depth += 1;
+    }
+
+    #[track_caller]
+    fn exit_atomic(&mut self) {
+        let top_frame = self.stack.frames.last_mut().unwrap();
+        assert!(
+            top_frame.atomic_depth > 0,
+            "exit_atomic without enter_atomic"
+        );
+        top_frame.atomic_depth -= 1;
+    }
+
+    fn atomic_depth(&self) -> usize {
+        self.stack.frames.iter().map(|f| f.atomic_depth).sum()
+    }
+
+    fn stack_trace(&self, db: &dyn crate::Db) -> Vec<FrameInfo> {
+        self.stack
+            .frames
+            .iter()
+            .rev()
+            .map(|frame| FrameInfo {
+                function_name: frame.pc.bir.function_name(db),
+                span: frame.pc.span(db),
+            })
+            .collect()
+    }
+
+    fn record_breakpoint_hit(&mut self, input_file: InputFile, index: usize) -> usize {
+        let hits = self.breakpoint_hits.entry((input_file, index)).or_default();
+        *hits += 1;
+        *hits
+    }
+
+    fn breakpoint_hit_count(&self, input_file: InputFile, index: usize) -> usize {
+        self.breakpoint_hits
+            .get(&(input_file, index))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    #[track_caller]
+    fn suppress_breakpoint(&mut self, input_file: InputFile, index: usize) {
+        let top_frame = self.stack.frames.last_mut().unwrap();
+        top_frame.suppressed_breakpoints.push((input_file, index));
+    }
+
+    #[track_caller]
+    fn take_suppressed_breakpoint(&mut self, input_file: InputFile, index: usize) -> bool {
+        let top_frame = self.stack.frames.last_mut().unwrap();
+        match top_frame
+            .suppressed_breakpoints
+            .iter()
+            .rposition(|&b| b == (input_file, index))
+        {
+            Some(position) => {
+                top_frame.suppressed_breakpoints.remove(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[track_caller]
+    fn push_awaited_value(&mut self, value: Value) {
+        let top_frame = self.stack.frames.last_mut().unwrap();
+        top_frame.awaited_values.push(value);
+    }
+
+    #[track_caller]
+    fn take_awaited_values(&mut self) -> Vec<Value> {
+        let top_frame = self.stack.frames.last_mut().unwrap();
+        std::mem::take(&mut top_frame.awaited_values)
+    }
+
+    fn stats(&self) -> MachineStats {
+        self.stats
+    }
+
+    fn record_collection(&mut self, objects: usize, permissions: usize) {
+        self.stats.objects_collected += objects as u64;
+        self.stats.permissions_collected += permissions as u64;
+    }
+
     fn snapshot(&self) -> Machine {
         self.clone()
     }
+
+    fn restore(&mut self, snapshot: Machine) {
+        *self = snapshot;
+    }
 }
 
 impl std::ops::Index<FrameIndex> for Machine {
@@ -305,7 +469,7 @@ pub(crate) impl MachineOpExtMut for &mut dyn MachineOp {
 
 #[extension_trait::extension_trait]
 pub(crate) impl MachineOpExt for &dyn MachineOp {
-    fn assert_invariants(self, db: &dyn crate::Db) -> eyre::Result<()> {
+    fn assert_invariants(self, db: &dyn crate::Db) -> Result<(), InvariantViolation> {
         AssertInvariants::new(db, self).assert_all_ok()
     }
 }
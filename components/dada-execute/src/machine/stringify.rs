@@ -1,6 +1,9 @@
 use dada_ir::word::Word;
 
-use crate::machine::{ObjectData, PermissionData, Value};
+use crate::{
+    ext::DadaExecuteClassExt,
+    machine::{Machine, ObjectData, PermissionData, Value},
+};
 
 use super::{op::MachineOp, Object};
 
@@ -8,43 +11,112 @@ use super::{op::MachineOp, Object};
 pub(crate) impl<T: ?Sized + MachineOp> DefaultStringify for T {
     /// Converts a given value into a string. This should
     /// eventually be customizable.
+    ///
+    /// If an object is reached again while converting its own fields,
+    /// it is shown as `...` there, so that printing terminates even if
+    /// the heap contains a cycle.
     fn stringify_value(&self, db: &dyn crate::Db, value: Value) -> String {
+        self.stringify_value_in(db, value, &mut vec![])
+    }
+
+    // FIXME: There is no way for *users* to write a fn that "inspects" the permission
+    // like this. We should maybe just not print them, but it's kind of useful...?
+    fn stringify_object(&self, db: &dyn crate::Db, object: Object) -> String {
+        self.stringify_object_in(db, object, &mut vec![])
+    }
+
+    /// Converts `value` into a string; `in_progress` are the objects whose
+    /// fields we are in the middle of converting.
+    fn stringify_value_in(
+        &self,
+        db: &dyn crate::Db,
+        value: Value,
+        in_progress: &mut Vec<Object>,
+    ) -> String {
         if let PermissionData::Expired(_) = self[value.permission] {
             "(expired)".to_string()
         } else {
-            self.stringify_object(db, value.object)
+            self.stringify_object_in(db, value.object, in_progress)
         }
     }
 
-    // FIXME: There is no way for *users* to write a fn that "inspects" the permission
-    // like this. We should maybe just not print them, but it's kind of useful...?
-    fn stringify_object(&self, db: &dyn crate::Db, object: Object) -> String {
+    fn stringify_object_in(
+        &self,
+        db: &dyn crate::Db,
+        object: Object,
+        in_progress: &mut Vec<Object>,
+    ) -> String {
         tracing::debug!(
             "stringify(object = {:?}, object-data = {:?})",
             object,
             self[object]
         );
-        match &self[object] {
+        if in_progress.contains(&object) {
+            return "...".to_string();
+        }
+
+        in_progress.push(object);
+        let string = match &self[object] {
             ObjectData::String(s) => s.to_string(),
+            ObjectData::Char(c) => c.to_string(),
             ObjectData::Bool(v) => format!("{v}"),
             ObjectData::SignedInt(v) => format!("{v}_i"),
+            // Always show a fractional part so that floats are distinguishable from integers.
+            ObjectData::Float(v) if v.is_finite() && v.fract() == 0.0 => format!("{v:.1}"),
             ObjectData::Float(v) => format!("{v}"),
             ObjectData::UnsignedInt(v) => format!("{v}_u"),
             ObjectData::Int(v) => format!("{v}"),
             ObjectData::Unit(_) => "()".to_string(),
+            ObjectData::Range(r) if r.inclusive => format!("{}..={}", r.start, r.end),
+            ObjectData::Range(r) => format!("{}..{}", r.start, r.end),
             ObjectData::Intrinsic(i) => i.as_str(db).to_string(),
+            ObjectData::NativeFunction(name) => name.as_str(db).to_string(),
             ObjectData::Function(f) => f.name(db).as_str(db).to_string(),
-            ObjectData::ThunkFn(f) => {
-                self.object_string(db, Some(f.function.name(db)), &f.arguments)
-            }
-            ObjectData::Instance(i) => self.object_string(db, Some(i.class.name(db)), &i.fields),
+            ObjectData::ThunkFn(f) => self.object_string(
+                db,
+                Some(f.function.name(db)),
+                &[],
+                &f.arguments,
+                in_progress,
+            ),
+            ObjectData::Instance(i) => self.object_string(
+                db,
+                Some(i.class.name(db)),
+                i.class.field_names(db),
+                &i.fields,
+                in_progress,
+            ),
             ObjectData::Class(c) => c.name(db).as_str(db).to_string(),
             ObjectData::ThunkRust(r) => format!("{r:?}"),
-            ObjectData::Tuple(t) => self.object_string(db, None, &t.fields),
-        }
+            ObjectData::Tuple(t) => self.object_string(db, None, &[], &t.fields, in_progress),
+            ObjectData::List(l) => {
+                let elements: Vec<_> = l
+                    .elements
+                    .iter()
+                    .map(|&element| self.stringify_value_in(db, element, in_progress))
+                    .collect();
+                format!("[{}]", elements.join(", "))
+            }
+            ObjectData::Result(r) => {
+                let tag = if r.ok { "ok" } else { "err" };
+                let value = self.stringify_value_in(db, r.value, in_progress);
+                format!("{tag}({value})")
+            }
+        };
+        in_progress.pop();
+        string
     }
 
-    fn object_string(&self, db: &dyn crate::Db, name: Option<Word>, fields: &[Value]) -> String {
+    /// Renders `name(field0, field1)`, labeling each field with its name
+    /// (`name(x: field0, y: field1)`) if `field_names` has one for it.
+    fn object_string(
+        &self,
+        db: &dyn crate::Db,
+        name: Option<Word>,
+        field_names: &[Word],
+        fields: &[Value],
+        in_progress: &mut Vec<Object>,
+    ) -> String {
         let mut output = String::new();
         if let Some(name) = name {
             output.push_str(name.as_str(db));
@@ -54,9 +126,56 @@ pub(crate) impl<T: ?Sized + MachineOp> DefaultStringify for T {
             if index > 0 {
                 output.push_str(", ");
             }
-            output.push_str(&self.stringify_value(db, *field));
+            if let Some(field_name) = field_names.get(index) {
+                output.push_str(field_name.as_str(db));
+                output.push_str(": ");
+            }
+            output.push_str(&self.stringify_value_in(db, *field, in_progress));
         }
         output.push(')');
         output
     }
 }
+
+impl Value {
+    /// Returns a wrapper that formats this value with its contents, the kind
+    /// of object it refers to, and its permission, like `(1, 2) (a tuple, my)`.
+    /// Meant for debugging the interpreter (e.g., `dbg!(value.display(db, machine.view()))`);
+    /// the output format is not stable.
+    pub fn display<'me>(self, db: &'me dyn crate::Db, machine: &'me Machine) -> ValueDisplay<'me> {
+        ValueDisplay {
+            db,
+            machine,
+            value: self,
+        }
+    }
+}
+
+/// Formats a [`Value`] for debugging; see [`Value::display`].
+pub struct ValueDisplay<'me> {
+    db: &'me dyn crate::Db,
+    machine: &'me Machine,
+    value: Value,
+}
+
+impl std::fmt::Display for ValueDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = self.machine[self.value.object].kind_str(self.db);
+        match self.machine[self.value.permission].valid() {
+            Some(permission) => write!(
+                f,
+                "{} ({}, {})",
+                self.machine.stringify_object(self.db, self.value.object),
+                kind,
+                permission.as_str(),
+            ),
+            None => write!(f, "(expired) ({kind})"),
+        }
+    }
+}
+
+impl std::fmt::Debug for ValueDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
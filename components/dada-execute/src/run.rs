@@ -1,9 +1,12 @@
-use dada_ir::code::bir::Bir;
+use std::time::Instant;
+
+use dada_brew::prelude::*;
+use dada_ir::{code::bir::Bir, function::Function};
 use salsa::DebugWithDb;
 
 use crate::{
     kernel::Kernel,
-    machine::{op::MachineOp, Machine, Value},
+    machine::{json::JsonValue, op::MachineOp, Machine, ProgramCounter, Value},
     step::{ControlFlow, Stepper},
 };
 
@@ -24,16 +27,88 @@ pub async fn interpret(
     let machine: &mut Machine = &mut Machine::default();
     machine.push_frame(db, bir, arguments, None);
     let mut stepper = Stepper::new(db, machine, kernel);
+    let (pc, v) = run_to_completion(&mut stepper).await?;
+    stepper.print_if_not_unit(pc, v).await?;
+    drop(stepper);
+    kernel.flush().await
+}
+
+/// Calls `function` with the given arguments and runs it to completion,
+/// returning its result. Intended for hosts (like tests of Dada libraries)
+/// that want to invoke a function other than `main`.
+///
+/// Arguments and result are exchanged as JSON, since machine values only make
+/// sense alongside the machine that holds them: arrays become tuples and
+/// `null` becomes `()`, and the result is converted as by
+/// [`Debugger::peek_json`](crate::Debugger::peek_json).
+/// Async functions are run to completion rather than returning a thunk.
+#[tracing::instrument(level = "debug", skip(db, kernel))]
+pub async fn call_function(
+    function: Function,
+    db: &dyn crate::Db,
+    kernel: &mut dyn Kernel,
+    arguments: &[serde_json::Value],
+) -> eyre::Result<serde_json::Value> {
+    let bir = function.brew(db);
+    let bir_data = bir.data(db);
+    if arguments.len() != bir_data.num_parameters {
+        eyre::bail!(
+            "`{}` expects {} argument(s), but {} were given",
+            function.name(db).as_str(db),
+            bir_data.num_parameters,
+            arguments.len()
+        );
+    }
 
+    let machine: &mut Machine = &mut Machine::default();
+    let entry_pc = ProgramCounter::at_block(bir, bir_data.start_point);
+    let arguments = arguments
+        .iter()
+        .map(|argument| match machine.json_to_value(entry_pc, argument) {
+            Some(value) => Ok(value),
+            None => Err(eyre::eyre!(
+                "cannot pass the JSON object `{argument}` to Dada"
+            )),
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    machine.push_frame(db, bir, arguments, None);
+
+    let mut stepper = Stepper::new(db, machine, kernel);
+    let (_pc, v) = run_to_completion(&mut stepper).await?;
+    drop(stepper);
+    kernel.flush().await?;
+    Ok(machine.value_to_json(db, v))
+}
+
+/// Steps until the function in the bottom-most frame returns, respecting
+/// [`Kernel::max_steps`] and [`Kernel::timeout`].
+pub(crate) async fn run_to_completion(
+    stepper: &mut Stepper<'_>,
+) -> eyre::Result<(ProgramCounter, Value)> {
+    let max_steps = stepper.max_steps();
+    // Only read the clock if there is a timeout: `Instant::now` panics on
+    // targets without one, like `wasm32-unknown-unknown`.
+    let timeout = stepper.timeout().map(|timeout| (timeout, Instant::now()));
+    let mut steps: u64 = 0;
     loop {
+        if let Some(max_steps) = max_steps {
+            if steps >= max_steps {
+                return Err(stepper.step_limit_exceeded(max_steps));
+            }
+            steps += 1;
+        }
+
+        if let Some((timeout, start)) = timeout {
+            if start.elapsed() >= timeout {
+                return Err(stepper.time_limit_exceeded(timeout));
+            }
+        }
+
         tracing::trace!("machine = {:#?}", stepper);
         match stepper.step()? {
             ControlFlow::Next => (),
-            ControlFlow::Await(t) => t.invoke(&mut stepper).await?,
-            ControlFlow::Done(pc, v) => {
-                stepper.print_if_not_unit(pc, v).await?;
-                return Ok(());
-            }
+            ControlFlow::Await(t) => t.invoke(stepper).await?,
+            ControlFlow::Done(pc, v) => return Ok((pc, v)),
         }
     }
 }
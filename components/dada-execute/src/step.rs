@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use dada_id::prelude::*;
 use dada_ir::{
     class::Class,
@@ -5,6 +7,7 @@ use dada_ir::{
         bir::{self, ControlPoint, ControlPointData, TerminatorData, TerminatorExpr},
         syntax,
     },
+    diagnostic::DiagnosticBuilder,
     error,
     in_ir_db::InIrDbExt,
     origin_table::HasOriginIn,
@@ -18,11 +21,15 @@ use crate::{
     heap_graph::HeapGraph,
     kernel::Kernel,
     machine::{
-        op::MachineOp, Frame, Object, ObjectData, ProgramCounter, Tuple, ValidPermissionData, Value,
+        op::{MachineOp, MachineOpExtMut},
+        stringify::DefaultStringify,
+        Frame, List, Object, ObjectData, ProgramCounter, Tuple, ValidPermissionData, Value,
     },
     thunk::RustThunk,
 };
 
+pub(crate) use self::gc::reachable_from_roots;
+pub use self::gc::GcRoot;
 use self::traversal::PlaceTraversal;
 
 mod access;
@@ -34,6 +41,7 @@ mod await_thunk;
 mod call;
 mod check_signature;
 mod concatenate;
+mod equality;
 mod gc;
 mod give;
 mod into_shared;
@@ -51,6 +59,9 @@ pub(crate) struct Stepper<'me> {
     /// Kernel for core operations. This is normally `Some`, but we sometimes
     /// temporarily swap with `None` for callbacks.
     kernel: Option<&'me mut dyn Kernel>,
+
+    /// If true, check the machine invariants after each step.
+    check_invariants: bool,
 }
 
 impl std::fmt::Debug for Stepper<'_> {
@@ -84,9 +95,19 @@ impl<'me> Stepper<'me> {
             db,
             machine,
             kernel: Some(kernel),
+            check_invariants: cfg!(debug_assertions),
         }
     }
 
+    /// Enables or disables checking the machine invariants after each step.
+    /// The checks are for debugging the interpreter itself and cost time
+    /// proportional to the size of the heap, so they are on by default only
+    /// in debug builds.
+    pub(crate) fn with_invariant_checks(mut self, check_invariants: bool) -> Self {
+        self.check_invariants = check_invariants;
+        self
+    }
+
     /// Advances execution by a single step, returning either an error
     /// or an indication of what caller should do next.
     ///
@@ -135,6 +156,30 @@ impl<'me> Stepper<'me> {
         }
     }
 
+    /// The kernel's limit on the number of steps, if any.
+    pub(crate) fn max_steps(&self) -> Option<u64> {
+        self.kernel.as_ref().unwrap().max_steps()
+    }
+
+    /// Returns the error to report when the driver has executed `max_steps`
+    /// steps without completing; it is reported at the current program counter.
+    pub(crate) fn step_limit_exceeded(&self, max_steps: u64) -> eyre::Report {
+        let span = self.machine.pc().span(self.db);
+        error!(span, "step limit of {max_steps} exceeded").eyre(self.db)
+    }
+
+    /// The kernel's limit on the wall-clock running time, if any.
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.kernel.as_ref().unwrap().timeout()
+    }
+
+    /// Returns the error to report when the driver has run for longer than
+    /// `timeout`; it is reported at the current program counter.
+    pub(crate) fn time_limit_exceeded(&self, timeout: Duration) -> eyre::Report {
+        let span = self.machine.pc().span(self.db);
+        error!(span, "time limit of {timeout:?} exceeded").eyre(self.db)
+    }
+
     /// After a `ControlFlow::Await` is returned, the caller is responsible for
     /// invoking `awaken` with the resulting value. After awaken is called,
     /// the caller should start calling `step` again.
@@ -198,20 +243,47 @@ impl<'me> Stepper<'me> {
                 *self.machine.local_mut(*lv) = Value { object, permission };
             }
             bir::ActionData::BreakpointStart(input_file, index) => {
+                let hit_count = self.machine.record_breakpoint_hit(*input_file, *index);
                 let kernel = self.kernel.take().unwrap();
-                let result = kernel.breakpoint_start(self.db, *input_file, *index, &mut || {
-                    HeapGraph::new(self.db, self.machine, None)
-                });
+                let result: eyre::Result<()> = try {
+                    let mut generate_heap_graph = || HeapGraph::new(self.db, self.machine, None);
+                    if kernel.breakpoint_condition(
+                        self.db,
+                        *input_file,
+                        *index,
+                        &mut generate_heap_graph,
+                    )? {
+                        kernel.breakpoint_start(
+                            self.db,
+                            *input_file,
+                            *index,
+                            hit_count,
+                            &mut generate_heap_graph,
+                        )?
+                    } else {
+                        self.machine.suppress_breakpoint(*input_file, *index)
+                    }
+                };
                 self.kernel = Some(kernel);
                 result?
             }
+            bir::ActionData::BreakpointEnd(input_file, index, _, _)
+                if self.machine.take_suppressed_breakpoint(*input_file, *index) => {}
             bir::ActionData::BreakpointEnd(input_file, index, expr, in_flight_place) => {
                 let span = self.span_from_syntax_expr(*expr);
+                let hit_count = self.machine.breakpoint_hit_count(*input_file, *index);
                 let kernel = self.kernel.take().unwrap();
-                let result = kernel.breakpoint_end(self.db, *input_file, *index, span, &mut || {
-                    let in_flight_value = try { self.peek_place(table, (*in_flight_place)?)? };
-                    HeapGraph::new(self.db, self.machine, in_flight_value)
-                });
+                let result = kernel.breakpoint_end(
+                    self.db,
+                    *input_file,
+                    *index,
+                    hit_count,
+                    span,
+                    &mut || {
+                        let in_flight_value = try { self.peek_place(table, (*in_flight_place)?)? };
+                        HeapGraph::new(self.db, self.machine, in_flight_value)
+                    },
+                );
                 self.kernel = Some(kernel);
                 result?
             }
@@ -279,10 +351,17 @@ impl<'me> Stepper<'me> {
         );
 
         match terminator_data {
-            // FIXME: implement atomics
-            TerminatorData::StartAtomic(b)
-            | TerminatorData::EndAtomic(b)
-            | TerminatorData::Goto(b) => {
+            TerminatorData::Goto(b) => {
+                self.machine.set_pc(pc.move_to(*b));
+                Ok(ControlFlow::Next)
+            }
+            TerminatorData::StartAtomic(b) => {
+                self.machine.enter_atomic();
+                self.machine.set_pc(pc.move_to(*b));
+                Ok(ControlFlow::Next)
+            }
+            TerminatorData::EndAtomic(b) => {
+                self.machine.exit_atomic();
                 self.machine.set_pc(pc.move_to(*b));
                 Ok(ControlFlow::Next)
             }
@@ -294,6 +373,15 @@ impl<'me> Stepper<'me> {
                 }
                 Ok(ControlFlow::Next)
             }
+            TerminatorData::Switch(place, arms, default) => {
+                let value = self.eval_place_to_int(table, *place)?;
+                let target = arms
+                    .iter()
+                    .find(|&&(arm_value, _)| Some(arm_value) == value)
+                    .map_or(*default, |&(_, target)| target);
+                self.machine.set_pc(pc.move_to(target));
+                Ok(ControlFlow::Next)
+            }
 
             TerminatorData::Assign(
                 destination,
@@ -316,12 +404,48 @@ impl<'me> Stepper<'me> {
                 _destination,
                 TerminatorExpr::Await(thunk_place),
                 _next_block,
-            ) => match self.await_thunk(table, *thunk_place)? {
-                await_thunk::AwaitResult::PushedNewFrame => Ok(ControlFlow::Next),
-                await_thunk::AwaitResult::RustThunk(rust_thunk) => {
-                    Ok(ControlFlow::Await(rust_thunk))
+            ) => {
+                self.check_not_atomic(pc)?;
+
+                match self.await_thunk(table, *thunk_place)? {
+                    await_thunk::AwaitResult::PushedNewFrame => Ok(ControlFlow::Next),
+                    await_thunk::AwaitResult::RustThunk(rust_thunk) => {
+                        Ok(ControlFlow::Await(rust_thunk))
+                    }
                 }
-            },
+            }
+
+            TerminatorData::Assign(
+                destination,
+                TerminatorExpr::AwaitAll(thunk_places),
+                next_block,
+            ) => {
+                self.check_not_atomic(pc)?;
+
+                // We execute this terminator once per thunk: each time, await the
+                // next thunk, whose result `resume_with` records on the frame.
+                // Once all thunks have completed, store the tuple of results.
+                let num_awaited = self.machine.top_frame().unwrap().awaited_values.len();
+                match thunk_places.get(num_awaited) {
+                    Some(thunk_place) => match self.await_thunk(table, *thunk_place)? {
+                        await_thunk::AwaitResult::PushedNewFrame => Ok(ControlFlow::Next),
+                        await_thunk::AwaitResult::RustThunk(rust_thunk) => {
+                            Ok(ControlFlow::Await(rust_thunk))
+                        }
+                    },
+                    None => {
+                        let fields = self.machine.take_awaited_values();
+                        let results = if fields.is_empty() {
+                            self.machine.our_value(pc, ())
+                        } else {
+                            self.machine.my_value(pc, Tuple { fields })
+                        };
+                        self.assign_value_to_place(table, *destination, results)?;
+                        self.machine.set_pc(pc.move_to(*next_block));
+                        Ok(ControlFlow::Next)
+                    }
+                }
+            }
 
             TerminatorData::Return(place) => {
                 let return_value = self.give_place(table, *place)?;
@@ -356,15 +480,40 @@ impl<'me> Stepper<'me> {
             }
             TerminatorData::Error => {
                 let span = self.span_from_bir(pc.control_point);
-                Err(error!(span, "compilation error encountered 😢").eyre(self.db))
+                let diagnostic = error!(span, "compilation error encountered 😢");
+                Err(self.with_stack_trace(diagnostic).eyre(self.db))
             }
-            TerminatorData::Panic => {
+            TerminatorData::Panic(message) => {
                 let span = self.span_from_bir(pc.control_point);
-                Err(error!(span, "panic! omg! 😱").eyre(self.db))
+                let diagnostic = match *message {
+                    Some(place) => {
+                        let value = self.give_place(table, place)?;
+                        let message =
+                            DefaultStringify::stringify_value(&*self.machine, self.db, value);
+                        error!(span, "panic: {message}")
+                    }
+                    None => error!(span, "panic! omg! 😱"),
+                };
+                Err(self.with_stack_trace(diagnostic).eyre(self.db))
             }
         }
     }
 
+    /// Labels the diagnostic with each frame on the stack: the primary label
+    /// names the function that failed, and each caller gets a secondary label
+    /// at its call site, so that the diagnostic shows how we reached the error.
+    fn with_stack_trace(&self, mut diagnostic: DiagnosticBuilder) -> DiagnosticBuilder {
+        for (index, frame) in self.machine.stack_trace(self.db).into_iter().enumerate() {
+            let function_name = frame.function_name.as_str(self.db);
+            diagnostic = if index == 0 {
+                diagnostic.primary_label(format!("in `{function_name}`"))
+            } else {
+                diagnostic.secondary_label(frame.span, format!("called from `{function_name}`"))
+            };
+        }
+        diagnostic
+    }
+
     /// When we call a function or await a thunk, we leave the calling
     /// frame on the stack; when the result comes back, we need to store it
     /// in the expected place and jump to the next basic block. Given the resulting
@@ -383,8 +532,11 @@ impl<'me> Stepper<'me> {
         // Otherwise, this function was invoked from `top`. We have to store the return
         // value into the location where `top` expects it.
         let top_table = &top.pc.bir.data(self.db).tables;
-        let ControlPointData::Terminator(TerminatorData::Assign(top_place, _, top_basic_block)) =
-            &top_table[top.pc.control_point]
+        let ControlPointData::Terminator(TerminatorData::Assign(
+            top_place,
+            top_expr,
+            top_basic_block,
+        )) = &top_table[top.pc.control_point]
         else {
             unreachable!("calling frame should be at an assign terminator")
         };
@@ -399,6 +551,13 @@ impl<'me> Stepper<'me> {
             ));
         }
 
+        // For `AwaitAll`, stay on the terminator so that it can await the
+        // next thunk (or, if this was the last one, store all the results).
+        if let TerminatorExpr::AwaitAll(_) = top_expr {
+            self.machine.push_awaited_value(value);
+            return Ok(());
+        }
+
         let new_pc = top.pc.move_to(*top_basic_block);
         self.assign_value_to_place(top_table, *top_place, value)?;
         self.machine.set_pc(new_pc);
@@ -424,6 +583,24 @@ impl<'me> Stepper<'me> {
         }
     }
 
+    /// Reads an integer (of any kind) from `place`, returning `None` if it
+    /// is an unsigned value too large to be represented as an `i64`.
+    fn eval_place_to_int(
+        &mut self,
+        table: &bir::Tables,
+        place: bir::Place,
+    ) -> eyre::Result<Option<i64>> {
+        let object = self.read_place(table, place)?;
+        match &self.machine[object] {
+            ObjectData::Int(i) | ObjectData::UnsignedInt(i) => Ok(i64::try_from(*i).ok()),
+            ObjectData::SignedInt(i) => Ok(Some(*i)),
+            data => {
+                let span = self.span_from_bir(place);
+                Err(Self::unexpected_kind(self.db, span, data, "an integer"))
+            }
+        }
+    }
+
     fn eval_expr(&mut self, table: &bir::Tables, expr: bir::Expr) -> eyre::Result<Value> {
         match expr.data(table) {
             bir::ExprData::BooleanLiteral(v) => Ok(Value {
@@ -464,14 +641,26 @@ impl<'me> Stepper<'me> {
                     .machine
                     .new_permission(ValidPermissionData::our(self.machine.pc())),
             }),
+            bir::ExprData::CharLiteral(v) => Ok(Value {
+                object: self.machine.new_object(ObjectData::Char(*v)),
+                permission: self
+                    .machine
+                    .new_permission(ValidPermissionData::our(self.machine.pc())),
+            }),
             bir::ExprData::Unit => Ok(Value {
                 object: self.machine.new_object(ObjectData::Unit(())),
                 permission: self
                     .machine
                     .new_permission(ValidPermissionData::our(self.machine.pc())),
             }),
+            // `<rvalue>.share`: the operand is a temporary holding a freshly produced
+            // value, so it is converted into a shared value. If it was owned, the
+            // result is jointly owned (`our`) and has no lessor that could cancel it.
             bir::ExprData::IntoShared(place) => self.into_shared_place(table, *place),
             bir::ExprData::Lease(place) => self.lease_place(table, *place),
+            // `<place>.share`: the place stays valid and the result is a shared lease
+            // (a tenant of the place's permission), so writing to the place later
+            // cancels the result.
             bir::ExprData::Share(place) => self.share_place(table, *place),
             bir::ExprData::Give(place) => self.give_place(table, *place),
             bir::ExprData::Tuple(places) => {
@@ -486,6 +675,18 @@ impl<'me> Stepper<'me> {
                         .new_permission(ValidPermissionData::my(self.machine.pc())),
                 })
             }
+            bir::ExprData::List(places) => {
+                let elements = places
+                    .iter()
+                    .map(|place| self.give_place(table, *place))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value {
+                    object: self.machine.new_object(ObjectData::List(List { elements })),
+                    permission: self
+                        .machine
+                        .new_permission(ValidPermissionData::my(self.machine.pc())),
+                })
+            }
             bir::ExprData::Concatenate(places) => self.concatenate(table, places),
             bir::ExprData::Op(lhs, op, rhs) => {
                 let lhs_traversal = self.traverse_to_object(table, *lhs)?;
@@ -520,6 +721,7 @@ impl<'me> Stepper<'me> {
             "the class `{class_name}` has no field named `{}`",
             name.as_str(db)
         )
+        .code("E0001")
         .secondary_label(
             class_span,
             format!("the class `{class_name}` is declared here"),
@@ -527,21 +729,25 @@ impl<'me> Stepper<'me> {
         .eyre(db)
     }
 
+    /// Atomic sections are uninterruptible: we can never
+    /// suspend execution while inside of one.
+    fn check_not_atomic(&self, pc: ProgramCounter) -> eyre::Result<()> {
+        if self.machine.atomic_depth() > 0 {
+            let span = self.span_from_bir(pc.control_point);
+            return Err(error!(span, "cannot await inside an atomic section").eyre(self.db));
+        }
+        Ok(())
+    }
+
     fn span_from_bir(
         &self,
         expr: impl HasOriginIn<bir::Origins, Origin = syntax::Expr>,
     ) -> FileSpan {
-        let bir = self.machine.pc().bir;
-        let origins = bir.origins(self.db);
-        let syntax_expr = origins[expr];
-        self.span_from_syntax_expr(syntax_expr)
+        self.machine.pc().bir.origin_span(self.db, expr)
     }
 
     fn span_from_bir_name(&self, name: bir::Name) -> FileSpan {
-        let bir = self.machine.pc().bir;
-        let origins = bir.origins(self.db);
-        let syntax_name = origins[name];
-        bir.span_of(self.db, syntax_name)
+        self.machine.pc().bir.origin_span(self.db, name)
     }
 
     fn span_from_syntax_expr(&self, syntax_expr: syntax::Expr) -> FileSpan {
@@ -1,11 +1,12 @@
 use dada_ir::{
+    code::bir::{ControlPointData, TerminatorData, TerminatorExpr},
     error,
     storage::{Atomic, Joint, Leased},
 };
 
 use crate::{
     error::DiagnosticBuilderExt,
-    machine::{Object, ObjectData, Permission, PermissionData, Value},
+    machine::{Object, ObjectData, Permission, PermissionData, ProgramCounter, Value},
 };
 
 use super::{
@@ -68,6 +69,26 @@ impl Stepper<'_> {
     #[tracing::instrument(level = "Debug", skip(self))]
     pub(super) fn write_place(&mut self, traversal: &PlaceTraversal) -> eyre::Result<()> {
         let ap = &traversal.accumulated_permissions;
+
+        // The tasks awaited by an `await all` may eventually run concurrently,
+        // so a task must not write to non-atomic data that it has only leased:
+        // whoever owns that data could be accessing it at the same time.
+        // (Conservatively, this includes data leased from within the task itself.)
+        if let (Joint::No, Atomic::No, Leased::Yes) = (ap.joint, ap.atomic, ap.leased) {
+            if let Some(await_all_pc) = self.enclosing_await_all() {
+                let span = self.machine.pc().span(self.db);
+                return Err(error!(
+                    span,
+                    "cannot write to leased data inside of `await all` unless it is atomic"
+                )
+                .secondary_label(
+                    await_all_pc.span(self.db),
+                    "tasks awaited here may run concurrently",
+                )
+                .eyre(self.db));
+            }
+        }
+
         match (ap.joint, ap.atomic) {
             (Joint::Yes, Atomic::Yes) => {
                 // Writing to a shared, atomic location NYI.
@@ -256,13 +277,24 @@ impl Stepper<'_> {
                     self.push_reachable_via_fields(&v.fields, &mut reachable, &mut queue);
                 }
 
+                ObjectData::List(l) => {
+                    self.push_reachable_via_fields(&l.elements, &mut reachable, &mut queue);
+                }
+
+                ObjectData::Result(r) => {
+                    self.push_reachable_via_fields(&[r.value], &mut reachable, &mut queue);
+                }
+
                 ObjectData::Bool(_)
                 | ObjectData::Class(_)
                 | ObjectData::Float(_)
                 | ObjectData::Function(_)
                 | ObjectData::Intrinsic(_)
+                | ObjectData::NativeFunction(_)
                 | ObjectData::SignedInt(_)
                 | ObjectData::String(_)
+                | ObjectData::Char(_)
+                | ObjectData::Range(_)
                 | ObjectData::ThunkRust(_)
                 | ObjectData::Unit(_)
                 | ObjectData::Int(_)
@@ -304,4 +336,21 @@ impl Stepper<'_> {
             }
         }
     }
+
+    /// If the current frame is (transitively) executing one of the tasks of an
+    /// `await all`, returns the program counter of that `await all`.
+    fn enclosing_await_all(&self) -> Option<ProgramCounter> {
+        // Skip the top frame, which is the one doing the writing.
+        let callers = self.machine.frames().iter().rev().skip(1);
+        callers.map(|frame| frame.pc).find(|pc| {
+            matches!(
+                &pc.bir.data(self.db).tables[pc.control_point],
+                ControlPointData::Terminator(TerminatorData::Assign(
+                    _,
+                    TerminatorExpr::AwaitAll(_),
+                    _
+                ))
+            )
+        })
+    }
 }
@@ -29,6 +29,7 @@ impl Stepper<'_> {
             Address::Field(o, f) => match &self.machine[o] {
                 ObjectData::Instance(i) => i.fields[f],
                 ObjectData::Tuple(v) => v.fields[f],
+                ObjectData::List(l) => l.elements[f],
                 d => panic!("unexpected thing with fields: {d:?}"),
             },
         }
@@ -48,6 +49,7 @@ impl Stepper<'_> {
             Address::Field(o, f) => match &mut self.machine[o] {
                 ObjectData::Instance(i) => i.fields[f] = value,
                 ObjectData::Tuple(v) => v.fields[f] = value,
+                ObjectData::List(l) => l.elements[f] = value,
                 d => panic!("unexpected thing with fields: {d:?}"),
             },
         }
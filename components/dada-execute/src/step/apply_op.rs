@@ -36,19 +36,30 @@ impl Stepper<'_> {
         };
         let overflow_error = || {
             let span = self.span_from_bir(expr);
-            Err(error!(span, "overflow").eyre(self.db))
+            Err(error!(span, "arithmetic overflow").eyre(self.db))
+        };
+        let shift_error = || {
+            let span = self.span_from_bir(expr);
+            Err(error!(span, "shift amount must be between 0 and 63").eyre(self.db))
         };
         match (&self.machine[lhs], &self.machine[rhs]) {
             (&ObjectData::Bool(lhs), &ObjectData::Bool(rhs)) => match op {
                 Op::EqualEqual => Ok(self.machine.our_value(self.machine.pc(), lhs == rhs)),
+                Op::NotEqual => Ok(self.machine.our_value(self.machine.pc(), lhs != rhs)),
                 Op::GreaterEqual => Ok(self.machine.our_value(self.machine.pc(), lhs >= rhs)),
                 Op::LessEqual => Ok(self.machine.our_value(self.machine.pc(), lhs <= rhs)),
+                // The eager forms of `&&` and `||`: both operands have
+                // already been evaluated.
+                Op::BitAnd => Ok(self.machine.our_value(self.machine.pc(), lhs & rhs)),
+                Op::BitOr => Ok(self.machine.our_value(self.machine.pc(), lhs | rhs)),
+                Op::BitXor => Ok(self.machine.our_value(self.machine.pc(), lhs ^ rhs)),
                 _ => op_error(),
             },
             (&ObjectData::UnsignedInt(lhs), &ObjectData::UnsignedInt(rhs))
             | (&ObjectData::UnsignedInt(lhs), &ObjectData::Int(rhs))
             | (&ObjectData::Int(lhs), &ObjectData::UnsignedInt(rhs)) => match op {
                 Op::EqualEqual => Ok(self.machine.our_value(self.machine.pc(), lhs == rhs)),
+                Op::NotEqual => Ok(self.machine.our_value(self.machine.pc(), lhs != rhs)),
                 Op::GreaterEqual => Ok(self.machine.our_value(self.machine.pc(), lhs >= rhs)),
                 Op::LessEqual => Ok(self.machine.our_value(self.machine.pc(), lhs <= rhs)),
                 Op::Plus => match lhs.checked_add(rhs) {
@@ -67,11 +78,27 @@ impl Stepper<'_> {
                     Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
                     None => div_zero_error(),
                 },
+                Op::Modulo => match lhs.checked_rem(rhs) {
+                    Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
+                    None => div_zero_error(),
+                },
                 Op::LessThan => Ok(self.machine.our_value(self.machine.pc(), lhs < rhs)),
                 Op::GreaterThan => Ok(self.machine.our_value(self.machine.pc(), lhs > rhs)),
+                Op::BitAnd => Ok(self.machine.our_value(self.machine.pc(), lhs & rhs)),
+                Op::BitOr => Ok(self.machine.our_value(self.machine.pc(), lhs | rhs)),
+                Op::BitXor => Ok(self.machine.our_value(self.machine.pc(), lhs ^ rhs)),
+                Op::ShiftLeft => match shift_amount(rhs).and_then(|rhs| lhs.checked_shl(rhs)) {
+                    Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
+                    None => shift_error(),
+                },
+                Op::ShiftRight => match shift_amount(rhs).and_then(|rhs| lhs.checked_shr(rhs)) {
+                    Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
+                    None => shift_error(),
+                },
             },
             (&ObjectData::Int(lhs), &ObjectData::Int(rhs)) => match op {
                 Op::EqualEqual => Ok(self.machine.our_value(self.machine.pc(), lhs == rhs)),
+                Op::NotEqual => Ok(self.machine.our_value(self.machine.pc(), lhs != rhs)),
                 Op::GreaterEqual => Ok(self.machine.our_value(self.machine.pc(), lhs >= rhs)),
                 Op::LessEqual => Ok(self.machine.our_value(self.machine.pc(), lhs <= rhs)),
                 Op::Plus => match lhs.checked_add(rhs) {
@@ -98,8 +125,35 @@ impl Stepper<'_> {
                         .our_value(self.machine.pc(), ObjectData::Int(value))),
                     None => div_zero_error(),
                 },
+                Op::Modulo => match lhs.checked_rem(rhs) {
+                    Some(value) => Ok(self
+                        .machine
+                        .our_value(self.machine.pc(), ObjectData::Int(value))),
+                    None => div_zero_error(),
+                },
                 Op::LessThan => Ok(self.machine.our_value(self.machine.pc(), lhs < rhs)),
                 Op::GreaterThan => Ok(self.machine.our_value(self.machine.pc(), lhs > rhs)),
+                Op::BitAnd => Ok(self
+                    .machine
+                    .our_value(self.machine.pc(), ObjectData::Int(lhs & rhs))),
+                Op::BitOr => Ok(self
+                    .machine
+                    .our_value(self.machine.pc(), ObjectData::Int(lhs | rhs))),
+                Op::BitXor => Ok(self
+                    .machine
+                    .our_value(self.machine.pc(), ObjectData::Int(lhs ^ rhs))),
+                Op::ShiftLeft => match shift_amount(rhs).and_then(|rhs| lhs.checked_shl(rhs)) {
+                    Some(value) => Ok(self
+                        .machine
+                        .our_value(self.machine.pc(), ObjectData::Int(value))),
+                    None => shift_error(),
+                },
+                Op::ShiftRight => match shift_amount(rhs).and_then(|rhs| lhs.checked_shr(rhs)) {
+                    Some(value) => Ok(self
+                        .machine
+                        .our_value(self.machine.pc(), ObjectData::Int(value))),
+                    None => shift_error(),
+                },
             },
             (&ObjectData::SignedInt(lhs), &ObjectData::SignedInt(rhs)) => {
                 self.apply_signed_int(expr, op, lhs, rhs)
@@ -114,20 +168,52 @@ impl Stepper<'_> {
             },
             (&ObjectData::Float(lhs), &ObjectData::Float(rhs)) => match op {
                 Op::EqualEqual => Ok(self.machine.our_value(self.machine.pc(), lhs == rhs)),
+                Op::NotEqual => Ok(self.machine.our_value(self.machine.pc(), lhs != rhs)),
                 Op::GreaterEqual => Ok(self.machine.our_value(self.machine.pc(), lhs >= rhs)),
                 Op::LessEqual => Ok(self.machine.our_value(self.machine.pc(), lhs <= rhs)),
                 Op::Plus => Ok(self.machine.our_value(self.machine.pc(), lhs + rhs)),
                 Op::Minus => Ok(self.machine.our_value(self.machine.pc(), lhs - rhs)),
                 Op::Times => Ok(self.machine.our_value(self.machine.pc(), lhs * rhs)),
                 Op::DividedBy => Ok(self.machine.our_value(self.machine.pc(), lhs / rhs)),
+                Op::Modulo => Ok(self.machine.our_value(self.machine.pc(), lhs % rhs)),
                 Op::LessThan => Ok(self.machine.our_value(self.machine.pc(), lhs < rhs)),
                 Op::GreaterThan => Ok(self.machine.our_value(self.machine.pc(), lhs > rhs)),
+                Op::BitAnd | Op::BitOr | Op::BitXor | Op::ShiftLeft | Op::ShiftRight => op_error(),
             },
+            // Comparing an integer with a float promotes the integer to a float.
+            (&ObjectData::Int(lhs) | &ObjectData::UnsignedInt(lhs), &ObjectData::Float(rhs)) => {
+                match compare_floats(op, lhs as f64, rhs) {
+                    Some(val) => Ok(self.machine.our_value(self.machine.pc(), val)),
+                    None => op_error(),
+                }
+            }
+            (&ObjectData::SignedInt(lhs), &ObjectData::Float(rhs)) => {
+                match compare_floats(op, lhs as f64, rhs) {
+                    Some(val) => Ok(self.machine.our_value(self.machine.pc(), val)),
+                    None => op_error(),
+                }
+            }
+            (&ObjectData::Float(lhs), &ObjectData::Int(rhs) | &ObjectData::UnsignedInt(rhs)) => {
+                match compare_floats(op, lhs, rhs as f64) {
+                    Some(val) => Ok(self.machine.our_value(self.machine.pc(), val)),
+                    None => op_error(),
+                }
+            }
+            (&ObjectData::Float(lhs), &ObjectData::SignedInt(rhs)) => {
+                match compare_floats(op, lhs, rhs as f64) {
+                    Some(val) => Ok(self.machine.our_value(self.machine.pc(), val)),
+                    None => op_error(),
+                }
+            }
             (ObjectData::String(lhs), ObjectData::String(rhs)) => match op {
                 Op::EqualEqual => {
                     let val = lhs == rhs;
                     Ok(self.machine.our_value(self.machine.pc(), val))
                 }
+                Op::NotEqual => {
+                    let val = lhs != rhs;
+                    Ok(self.machine.our_value(self.machine.pc(), val))
+                }
                 Op::GreaterEqual => {
                     let val = lhs >= rhs;
                     Ok(self.machine.our_value(self.machine.pc(), val))
@@ -138,8 +224,30 @@ impl Stepper<'_> {
                 }
                 _ => op_error(),
             },
+            (&ObjectData::Char(lhs), &ObjectData::Char(rhs)) => match op {
+                Op::EqualEqual => Ok(self.machine.our_value(self.machine.pc(), lhs == rhs)),
+                Op::NotEqual => Ok(self.machine.our_value(self.machine.pc(), lhs != rhs)),
+                Op::GreaterEqual => Ok(self.machine.our_value(self.machine.pc(), lhs >= rhs)),
+                Op::LessEqual => Ok(self.machine.our_value(self.machine.pc(), lhs <= rhs)),
+                Op::LessThan => Ok(self.machine.our_value(self.machine.pc(), lhs < rhs)),
+                Op::GreaterThan => Ok(self.machine.our_value(self.machine.pc(), lhs > rhs)),
+                _ => op_error(),
+            },
+            (ObjectData::Tuple(_), ObjectData::Tuple(_))
+            | (ObjectData::List(_), ObjectData::List(_)) => match op {
+                Op::EqualEqual => {
+                    let val = self.objects_equal(lhs, rhs);
+                    Ok(self.machine.our_value(self.machine.pc(), val))
+                }
+                Op::NotEqual => {
+                    let val = !self.objects_equal(lhs, rhs);
+                    Ok(self.machine.our_value(self.machine.pc(), val))
+                }
+                _ => op_error(),
+            },
             (&ObjectData::Unit(()), &ObjectData::Unit(())) => match op {
                 Op::EqualEqual => Ok(self.machine.our_value(self.machine.pc(), true)),
+                Op::NotEqual => Ok(self.machine.our_value(self.machine.pc(), false)),
                 Op::GreaterEqual => Ok(self.machine.our_value(self.machine.pc(), lhs >= rhs)),
                 Op::LessEqual => Ok(self.machine.our_value(self.machine.pc(), lhs <= rhs)),
                 _ => op_error(),
@@ -161,10 +269,15 @@ impl Stepper<'_> {
         };
         let overflow_error = || {
             let span = self.span_from_bir(expr);
-            Err(error!(span, "overflow").eyre(self.db))
+            Err(error!(span, "arithmetic overflow").eyre(self.db))
+        };
+        let shift_error = || {
+            let span = self.span_from_bir(expr);
+            Err(error!(span, "shift amount must be between 0 and 63").eyre(self.db))
         };
         match op {
             Op::EqualEqual => Ok(self.machine.our_value(self.machine.pc(), lhs == rhs)),
+            Op::NotEqual => Ok(self.machine.our_value(self.machine.pc(), lhs != rhs)),
             Op::GreaterEqual => Ok(self.machine.our_value(self.machine.pc(), lhs >= rhs)),
             Op::LessEqual => Ok(self.machine.our_value(self.machine.pc(), lhs <= rhs)),
             Op::Plus => match lhs.checked_add(rhs) {
@@ -190,8 +303,50 @@ impl Stepper<'_> {
                     }
                 }
             },
+            Op::Modulo => match lhs.checked_rem(rhs) {
+                Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
+                None => {
+                    if rhs != -1 {
+                        div_zero_error()
+                    } else {
+                        let span = self.span_from_bir(expr);
+                        Err(error!(span, "signed division overflow").eyre(self.db))
+                    }
+                }
+            },
             Op::LessThan => Ok(self.machine.our_value(self.machine.pc(), lhs < rhs)),
             Op::GreaterThan => Ok(self.machine.our_value(self.machine.pc(), lhs > rhs)),
+            Op::BitAnd => Ok(self.machine.our_value(self.machine.pc(), lhs & rhs)),
+            Op::BitOr => Ok(self.machine.our_value(self.machine.pc(), lhs | rhs)),
+            Op::BitXor => Ok(self.machine.our_value(self.machine.pc(), lhs ^ rhs)),
+            Op::ShiftLeft => match shift_amount(rhs).and_then(|rhs| lhs.checked_shl(rhs)) {
+                Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
+                None => shift_error(),
+            },
+            Op::ShiftRight => match shift_amount(rhs).and_then(|rhs| lhs.checked_shr(rhs)) {
+                Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
+                None => shift_error(),
+            },
         }
     }
 }
+
+/// Converts the right-hand side of a shift into a shift amount, if it is
+/// not negative. `checked_shl` and `checked_shr` reject amounts that are
+/// too large.
+fn shift_amount(rhs: impl TryInto<u32>) -> Option<u32> {
+    rhs.try_into().ok()
+}
+
+/// Applies `op` to two floats if it is a comparison; returns `None` otherwise.
+fn compare_floats(op: Op, lhs: f64, rhs: f64) -> Option<bool> {
+    match op {
+        Op::EqualEqual => Some(lhs == rhs),
+        Op::NotEqual => Some(lhs != rhs),
+        Op::GreaterEqual => Some(lhs >= rhs),
+        Op::LessEqual => Some(lhs <= rhs),
+        Op::LessThan => Some(lhs < rhs),
+        Op::GreaterThan => Some(lhs > rhs),
+        _ => None,
+    }
+}
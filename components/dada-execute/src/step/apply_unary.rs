@@ -1,5 +1,5 @@
 use dada_ir::{
-    code::{bir, validated::op::Op},
+    code::{bir, validated::op::UnaryOp},
     error,
 };
 
@@ -15,7 +15,7 @@ impl Stepper<'_> {
     pub(super) fn apply_unary(
         &mut self,
         expr: bir::Expr,
-        op: Op,
+        op: UnaryOp,
         rhs: Object,
     ) -> eyre::Result<Value> {
         let op_error = || {
@@ -28,17 +28,25 @@ impl Stepper<'_> {
             )
             .eyre(self.db))
         };
+        let overflow_error = || {
+            let span = self.span_from_bir(expr);
+            Err(error!(span, "arithmetic overflow").eyre(self.db))
+        };
         match (op, &self.machine[rhs]) {
-            (Op::Minus, &ObjectData::SignedInt(rhs)) => {
-                Ok(self.machine.our_value(self.machine.pc(), -rhs))
-            }
-            (Op::Minus, &ObjectData::Int(rhs)) => match i64::try_from(rhs) {
+            (UnaryOp::Minus, &ObjectData::SignedInt(rhs)) => match rhs.checked_neg() {
+                Some(value) => Ok(self.machine.our_value(self.machine.pc(), value)),
+                None => overflow_error(),
+            },
+            (UnaryOp::Minus, &ObjectData::Int(rhs)) => match i64::try_from(rhs) {
                 Ok(rhs) => Ok(self.machine.our_value(self.machine.pc(), -rhs)),
-                Err(_) => {
-                    let span = self.span_from_bir(expr);
-                    Err(error!(span, "overflow").eyre(self.db))
-                }
+                Err(_) => overflow_error(),
             },
+            (UnaryOp::Minus, &ObjectData::Float(rhs)) => {
+                Ok(self.machine.our_value(self.machine.pc(), -rhs))
+            }
+            (UnaryOp::Not, &ObjectData::Bool(rhs)) => {
+                Ok(self.machine.our_value(self.machine.pc(), !rhs))
+            }
             _ => op_error(),
         }
     }
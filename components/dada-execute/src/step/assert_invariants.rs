@@ -11,10 +11,20 @@ use crate::machine::op::MachineOpExt;
 use super::Stepper;
 
 impl Stepper<'_> {
+    /// If invariant checks are enabled (see [`Stepper::with_invariant_checks`]),
+    /// checks the invariants and reports any failure as an
+    /// [`InvariantViolation`](crate::machine::assert_invariants::InvariantViolation)
+    /// tagged with the span currently being executed.
     pub(crate) fn assert_invariants(&self) -> eyre::Result<()> {
-        // Convert an assertion failure into a panic intentionally;
-        // it's not the same as other sorts of failures.
-        self.machine.assert_invariants(self.db).unwrap();
+        if !self.check_invariants {
+            return Ok(());
+        }
+
+        if let Err(mut violation) = self.machine.assert_invariants(self.db) {
+            violation.span = self.machine.opt_pc().map(|pc| pc.span(self.db));
+            return Err(violation.into());
+        }
+
         Ok(())
     }
 }
@@ -41,6 +41,7 @@ impl Stepper<'_> {
                 arguments,
                 expected_return_ty,
             }) => {
+                self.check_stack_depth(self.span_from_bir(thunk_place))?;
                 let bir = function.brew(self.db);
                 self.machine
                     .push_frame(self.db, bir, arguments, expected_return_ty);
@@ -1,10 +1,19 @@
 use dada_brew::prelude::*;
-use dada_ir::{code::bir, error, signature::InputTy, word::Word};
+use dada_ir::{
+    code::bir,
+    error,
+    signature::{DefaultValue, InputTy},
+    span::FileSpan,
+    word::Word,
+};
 use dada_validate::prelude::*;
+use eyre::Context;
 
 use crate::{
     error::DiagnosticBuilderExt,
-    machine::{op::MachineOpExtMut, Instance, ObjectData, ProgramCounter, ThunkFn, Value},
+    machine::{
+        json::JsonValue, op::MachineOpExtMut, Instance, ObjectData, ProgramCounter, ThunkFn, Value,
+    },
     step::intrinsic::IntrinsicDefinition,
 };
 
@@ -34,8 +43,18 @@ impl Stepper<'_> {
         match &self.machine[function_value.object] {
             &ObjectData::Class(c) => {
                 let signature = c.signature(self.db);
-                self.match_labels(table, pc, labels, &signature.inputs)?;
+                let fields = &c.structure(self.db).fields;
+
+                // Fields with default values may be omitted.
+                let argument_indices =
+                    self.match_arguments(table, pc, labels, &signature.inputs, |index| {
+                        fields[index].default.is_some()
+                    })?;
+
                 let arguments = self.give_arguments(table, argument_places)?;
+                let arguments = order_arguments(arguments, &argument_indices, |index| {
+                    self.default_value(pc, fields[index].default.unwrap())
+                });
                 self.check_signature(&arguments, signature)?;
                 let instance = Instance {
                     class: c,
@@ -47,9 +66,11 @@ impl Stepper<'_> {
             }
             &ObjectData::Function(function) => {
                 let signature = function.signature(self.db);
-                self.match_labels(table, pc, labels, &signature.inputs)?;
+                let argument_indices =
+                    self.match_arguments(table, pc, labels, &signature.inputs, |_| false)?;
 
                 let arguments = self.give_arguments(table, argument_places)?;
+                let arguments = order_arguments(arguments, &argument_indices, |_| unreachable!());
 
                 let expected_return_ty = self.check_signature(&arguments, signature)?;
 
@@ -68,6 +89,7 @@ impl Stepper<'_> {
                 } else {
                     // This is not an async function, so push it onto the stack
                     // and begin execution immediately.
+                    self.check_stack_depth(self.span_from_bir(pc.control_point))?;
                     let bir = function.brew(self.db);
                     self.machine
                         .push_frame(self.db, bir, arguments, expected_return_ty);
@@ -76,11 +98,40 @@ impl Stepper<'_> {
             }
             &ObjectData::Intrinsic(intrinsic) => {
                 let definition = IntrinsicDefinition::for_intrinsic(self.db, intrinsic);
-                self.match_labels(table, pc, labels, &definition.argument_names)?;
+                let argument_names = definition.argument_names_for(labels.len());
+                let argument_indices =
+                    self.match_arguments(table, pc, labels, argument_names, |_| false)?;
                 let arguments = self.give_arguments(table, argument_places)?;
+                let arguments = order_arguments(arguments, &argument_indices, |_| unreachable!());
                 let value = (definition.function)(self, arguments)?;
                 Ok(CallResult::Returned(value))
             }
+            &ObjectData::NativeFunction(name) => {
+                let db = self.db;
+                let span = self.span_from_bir(pc.control_point);
+                let arguments = self.give_arguments(table, argument_places)?;
+                let arguments: Vec<serde_json::Value> = arguments
+                    .into_iter()
+                    .map(|value| self.machine.value_to_json(db, value))
+                    .collect();
+                let result = self
+                    .kernel
+                    .as_mut()
+                    .unwrap()
+                    .call_native_function(db, name.as_str(db), &arguments)
+                    .with_context(|| {
+                        error!(span, "error calling native function `{}`", name.as_str(db)).eyre(db)
+                    })?;
+                match self.machine.json_to_value(pc, &result) {
+                    Some(value) => Ok(CallResult::Returned(value)),
+                    None => Err(error!(
+                        span,
+                        "native function `{}` returned a JSON object, which has no Dada equivalent",
+                        name.as_str(db)
+                    )
+                    .eyre(db)),
+                }
+            }
             data => {
                 let span = self.span_from_bir(callee);
                 Err(error!(
@@ -93,6 +144,16 @@ impl Stepper<'_> {
         }
     }
 
+    /// Checks that pushing one more frame would not exceed the kernel's
+    /// maximum stack depth, reporting a "stack overflow" at `span` if it would.
+    pub(super) fn check_stack_depth(&self, span: FileSpan) -> eyre::Result<()> {
+        let max_stack_depth = self.kernel.as_ref().unwrap().max_stack_depth();
+        if self.machine.frames().len() >= max_stack_depth {
+            return Err(error!(span, "stack overflow").eyre(self.db));
+        }
+        Ok(())
+    }
+
     fn give_arguments(
         &mut self,
         table: &bir::Tables,
@@ -104,43 +165,149 @@ impl Stepper<'_> {
             .collect()
     }
 
-    fn match_labels(
+    /// Creates the value for a class field that was omitted from the
+    /// constructor call, using the field's declared default.
+    fn default_value(&mut self, pc: ProgramCounter, default: DefaultValue) -> Value {
+        let data = match default {
+            DefaultValue::Bool(v) => ObjectData::Bool(v),
+            DefaultValue::Int(v) => ObjectData::Int(v),
+            DefaultValue::UnsignedInt(v) => ObjectData::UnsignedInt(v),
+            DefaultValue::SignedInt(v) => ObjectData::SignedInt(v),
+            DefaultValue::Float(v) => ObjectData::Float(v.0),
+        };
+        self.machine.our_value(pc, data)
+    }
+
+    /// Matches the arguments of a call (with the given labels, in the order
+    /// they were written) to the callee's parameters. Labeled arguments go to
+    /// the parameter with that name, in any order; unlabeled arguments fill the
+    /// remaining parameters from left to right. A parameter may only be left
+    /// without an argument if `optional` returns true for its index.
+    ///
+    /// Returns, for each parameter, the index of the argument that supplies it.
+    fn match_arguments(
         &self,
         table: &bir::Tables,
         pc: ProgramCounter,
         actual_labels: &[Option<bir::Name>],
         expected_names: &[impl ExpectedName],
-    ) -> eyre::Result<()> {
+        optional: impl Fn(usize) -> bool,
+    ) -> eyre::Result<Vec<Option<usize>>> {
         let db = self.db;
+        let expected_names: Vec<Word> = expected_names.iter().map(|n| n.as_word(db)).collect();
 
-        for (actual_label, expected_name) in actual_labels.iter().zip(expected_names) {
-            let expected_name = expected_name.as_word(db);
-            if let &Some(actual_label) = actual_label {
-                let actual_word = table[actual_label].word;
-                if expected_name != actual_word {
-                    return Err(error!(
-                        self.span_from_bir_name(actual_label),
-                        "expected to find an argument named `{}`, but found the name `{}`",
-                        expected_name.as_str(db),
-                        actual_word.as_str(db),
-                    )
-                    .eyre(db));
+        if actual_labels.len() > expected_names.len() {
+            return Err(self.wrong_number_of_arguments(
+                pc,
+                expected_names.len(),
+                actual_labels.len(),
+            ));
+        }
+
+        // First, assign the labeled arguments to the parameters they name.
+        let mut argument_indices: Vec<Option<usize>> = vec![None; expected_names.len()];
+        let mut unknown_labels = vec![];
+        for (argument_index, actual_label) in actual_labels.iter().enumerate() {
+            let &Some(actual_label) = actual_label else {
+                continue;
+            };
+            let actual_word = table[actual_label].word;
+            match expected_names.iter().position(|&name| name == actual_word) {
+                Some(parameter_index) => {
+                    if argument_indices[parameter_index].is_some() {
+                        return Err(error!(
+                            self.span_from_bir_name(actual_label),
+                            "the argument `{}` was given more than once",
+                            actual_word.as_str(db),
+                        )
+                        .eyre(db));
+                    }
+                    argument_indices[parameter_index] = Some(argument_index);
                 }
+                None => unknown_labels.push(actual_label),
             }
         }
 
-        if actual_labels.len() != expected_names.len() {
+        if let Some(&actual_label) = unknown_labels.first() {
+            // Suggest the first parameter that no other label claimed.
+            let expected_name = argument_indices
+                .iter()
+                .position(|i| i.is_none())
+                .map(|parameter_index| expected_names[parameter_index])
+                .unwrap();
+            return Err(error!(
+                self.span_from_bir_name(actual_label),
+                "expected to find an argument named `{}`, but found the name `{}`",
+                expected_name.as_str(db),
+                table[actual_label].word.as_str(db),
+            )
+            .eyre(db));
+        }
+
+        // Then fill in the remaining parameters with the unlabeled arguments.
+        let mut unlabeled = actual_labels
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| label.is_none())
+            .map(|(argument_index, _)| argument_index);
+        for slot in argument_indices.iter_mut().filter(|slot| slot.is_none()) {
+            *slot = unlabeled.next();
+        }
+
+        // Finally, check that every required parameter was given.
+        let missing = argument_indices
+            .iter()
+            .enumerate()
+            .find(|&(parameter_index, slot)| slot.is_none() && !optional(parameter_index));
+        if let Some((parameter_index, _)) = missing {
+            if actual_labels.iter().all(|label| label.is_none()) {
+                return Err(self.wrong_number_of_arguments(
+                    pc,
+                    expected_names.len(),
+                    actual_labels.len(),
+                ));
+            }
             return Err(error!(
                 self.span_from_bir(pc.control_point),
-                "expected to find {} arguments, but found {}",
-                expected_names.len(),
-                actual_labels.len(),
+                "missing an argument for the parameter `{}`",
+                expected_names[parameter_index].as_str(db),
             )
             .eyre(db));
         }
 
-        Ok(())
+        Ok(argument_indices)
     }
+
+    fn wrong_number_of_arguments(
+        &self,
+        pc: ProgramCounter,
+        expected: usize,
+        actual: usize,
+    ) -> eyre::Report {
+        error!(
+            self.span_from_bir(pc.control_point),
+            "expected to find {} arguments, but found {}", expected, actual,
+        )
+        .eyre(self.db)
+    }
+}
+
+/// Puts the given arguments (in the order they were written) into parameter
+/// order, as computed by `match_arguments`; parameters without an argument get
+/// their value from `omitted`.
+fn order_arguments(
+    arguments: Vec<Value>,
+    argument_indices: &[Option<usize>],
+    mut omitted: impl FnMut(usize) -> Value,
+) -> Vec<Value> {
+    argument_indices
+        .iter()
+        .enumerate()
+        .map(|(parameter_index, argument_index)| match argument_index {
+            Some(argument_index) => arguments[*argument_index],
+            None => omitted(parameter_index),
+        })
+        .collect()
 }
 
 trait ExpectedName {
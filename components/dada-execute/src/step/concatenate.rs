@@ -1,4 +1,4 @@
-use dada_ir::code::bir;
+use dada_ir::{code::bir, format_string::FormatSpec};
 
 use crate::{
     machine::{stringify::DefaultStringify, ValidPermissionData},
@@ -11,12 +11,15 @@ impl Stepper<'_> {
     pub(super) fn concatenate(
         &mut self,
         table: &bir::Tables,
-        places: &[bir::Place],
+        places: &[(bir::Place, Option<FormatSpec>)],
     ) -> eyre::Result<Value> {
         let mut string = String::new();
-        for place in places {
-            let value = self.share_place(table, *place)?;
-            string.push_str(&self.machine.stringify_value(self.db, value));
+        for &(place, spec) in places {
+            let value = self.share_place(table, place)?;
+            match spec {
+                None => string.push_str(&self.machine.stringify_value(self.db, value)),
+                Some(spec) => string.push_str(&self.format_value(place, value, spec)?),
+            }
         }
 
         Ok(Value {
@@ -26,4 +29,28 @@ impl Stepper<'_> {
                 .new_permission(ValidPermissionData::our(self.machine.pc())),
         })
     }
+
+    /// Renders `value` (read from `place`) the way `print` would, then
+    /// applies `spec`. Integers lose the `_i`/`_u` suffix that `print` shows,
+    /// so that `{x:03}` pads the digits alone. A precision is only meaningful
+    /// for floats.
+    fn format_value(
+        &self,
+        place: bir::Place,
+        value: Value,
+        spec: FormatSpec,
+    ) -> eyre::Result<String> {
+        let data = &self.machine[value.object];
+        let text = match (data, spec.precision) {
+            (&ObjectData::Float(v), Some(precision)) => format!("{v:.precision$}"),
+            (data, Some(_)) => {
+                let span = self.span_from_bir(place);
+                return Err(Self::unexpected_kind(self.db, span, data, "a float"));
+            }
+            (&ObjectData::SignedInt(v), None) => v.to_string(),
+            (&ObjectData::UnsignedInt(v), None) => v.to_string(),
+            (_, None) => self.machine.stringify_value(self.db, value),
+        };
+        Ok(spec.pad(&text, data.is_number()))
+    }
 }
@@ -0,0 +1,35 @@
+use crate::machine::{Object, ObjectData, Value};
+
+use super::Stepper;
+
+impl Stepper<'_> {
+    /// True if `a` and `b` hold structurally equal data. Tuples and
+    /// lists are compared element by element; permissions are ignored.
+    pub(crate) fn values_equal(&self, a: Value, b: Value) -> bool {
+        self.objects_equal(a.object, b.object)
+    }
+
+    pub(super) fn objects_equal(&self, a: Object, b: Object) -> bool {
+        if a == b {
+            return true;
+        }
+
+        match (&self.machine[a], &self.machine[b]) {
+            (ObjectData::Tuple(a), ObjectData::Tuple(b)) => {
+                self.all_values_equal(&a.fields, &b.fields)
+            }
+            (ObjectData::List(a), ObjectData::List(b)) => {
+                self.all_values_equal(&a.elements, &b.elements)
+            }
+            (&ObjectData::Int(a), &ObjectData::UnsignedInt(b))
+            | (&ObjectData::UnsignedInt(a), &ObjectData::Int(b)) => a == b,
+            (&ObjectData::Int(a), &ObjectData::SignedInt(b))
+            | (&ObjectData::SignedInt(b), &ObjectData::Int(a)) => i64::try_from(a) == Ok(b),
+            (a, b) => a == b,
+        }
+    }
+
+    fn all_values_equal(&self, a: &[Value], b: &[Value]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(&a, &b)| self.values_equal(a, b))
+    }
+}
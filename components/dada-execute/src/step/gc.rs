@@ -3,13 +3,18 @@
 //! have a gc, but that it would be equivalent to the interpreter.
 //!
 //! The gc currently runs after every step, keeping things tidy.
+//!
+//! Collection is deterministic: the sets of marks are only used for
+//! membership tests, and the sweep visits permissions and objects in
+//! order of their id (see `Heap::all_objects` and `Heap::all_permissions`),
+//! so the same program always revokes and frees things in the same order.
 
-use dada_collections::Set;
-use dada_ir::storage::Leased;
+use dada_collections::{Map, Set};
+use dada_ir::{code::bir, storage::Leased};
 
 use crate::machine::{
-    op::MachineOp, ExpectedClassTy, ExpectedPermission, ExpectedTy, Object, ObjectData, Permission,
-    PermissionData, Value,
+    op::MachineOp, ExpectedClassTy, ExpectedPermission, ExpectedTy, FrameIndex, Object, ObjectData,
+    Permission, PermissionData, Value,
 };
 
 use super::Stepper;
@@ -30,6 +35,45 @@ impl Stepper<'_> {
     }
 }
 
+/// The root that keeps an object alive, as reported by
+/// [`Debugger::reachable_objects`](crate::Debugger::reachable_objects).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GcRoot {
+    /// A local variable of the given stack frame (`0` is the bottom-most frame).
+    Local {
+        frame: FrameIndex,
+        local: bir::LocalVariable,
+    },
+
+    /// The result of a thunk awaited by an `await all` in the given stack frame.
+    AwaitedValue { frame: FrameIndex, index: usize },
+
+    /// The in-flight value with the given index.
+    InFlight(usize),
+
+    /// The singleton unit object, which is always live.
+    Unit,
+}
+
+/// Returns the objects that the garbage collector would keep alive, given the
+/// in-flight values `in_flight_values`, without collecting anything. Each object
+/// is paired with the first root found to reach it (roots are searched from the
+/// bottom-most stack frame up, then the in-flight values), which helps to explain
+/// why an object is retained. Objects are returned in order of their id.
+pub(crate) fn reachable_from_roots(
+    machine: &dyn MachineOp,
+    in_flight_values: &[Value],
+) -> Vec<(Object, GcRoot)> {
+    let mut marks = Marks {
+        roots: Some(Map::default()),
+        ..Marks::default()
+    };
+    Marker::new(machine, &mut marks).mark(in_flight_values);
+    let mut objects: Vec<(Object, GcRoot)> = marks.roots.unwrap().into_iter().collect();
+    objects.sort_by_key(|&(object, _)| object);
+    objects
+}
+
 #[derive(Debug, Default)]
 struct Marks {
     /// Live objects: objects that had a live owning permission.
@@ -57,36 +101,61 @@ struct Marks {
     /// This function creates an Object and returns a leased copy,
     /// In the callee, the leased value will be live, but not the owner.
     live_permissions: Set<Permission>,
+
+    /// If present, records the root through which each live object was
+    /// first reached. Only tracked on request, as the gc runs after every step.
+    roots: Option<Map<Object, GcRoot>>,
 }
 
 struct Marker<'me> {
     machine: &'me dyn MachineOp,
     marks: &'me mut Marks,
+
+    /// The root currently being marked from.
+    root: GcRoot,
 }
 
 impl<'me> Marker<'me> {
     fn new(machine: &'me dyn MachineOp, marks: &'me mut Marks) -> Self {
-        Self { machine, marks }
+        Self {
+            machine,
+            marks,
+            root: GcRoot::Unit,
+        }
     }
 
     #[tracing::instrument(level = "Debug", skip(self))]
     fn mark(&mut self, in_flight_values: &[Value]) {
-        for frame in self.machine.frames() {
-            for local_value in &frame.locals {
+        for (frame_index, frame) in self.machine.frames().iter_enumerated() {
+            for (local, local_value) in frame.locals.iter_enumerated() {
+                self.root = GcRoot::Local {
+                    frame: frame_index,
+                    local,
+                };
                 self.mark_value(*local_value);
             }
 
+            for (index, awaited_value) in frame.awaited_values.iter().enumerate() {
+                self.root = GcRoot::AwaitedValue {
+                    frame: frame_index,
+                    index,
+                };
+                self.mark_value(*awaited_value);
+            }
+
             for ty in &frame.expected_return_ty {
                 self.mark_expected_ty(ty);
             }
         }
 
-        for in_flight_value in in_flight_values {
+        for (index, in_flight_value) in in_flight_values.iter().enumerate() {
+            self.root = GcRoot::InFlight(index);
             self.mark_value(*in_flight_value);
         }
 
         // the singleton unit object is always live :)
-        self.marks.live_objects.insert(self.machine.unit_object());
+        self.root = GcRoot::Unit;
+        self.mark_object(self.machine.unit_object());
     }
 
     #[tracing::instrument(level = "Debug", skip(self))]
@@ -152,22 +221,31 @@ impl<'me> Marker<'me> {
             return;
         }
 
+        if let Some(roots) = &mut self.marks.roots {
+            roots.insert(object, self.root);
+        }
+
         let object_data: &ObjectData = &self.machine[object];
         match object_data {
             ObjectData::Instance(i) => self.mark_values(&i.fields),
             ObjectData::ThunkFn(f) => self.mark_values(&f.arguments),
             ObjectData::ThunkRust(f) => self.mark_values(&f.arguments),
             ObjectData::Tuple(t) => self.mark_values(&t.fields),
+            ObjectData::List(l) => self.mark_values(&l.elements),
+            ObjectData::Result(r) => self.mark_values(&[r.value]),
 
             ObjectData::Class(_)
             | ObjectData::Function(_)
             | ObjectData::Intrinsic(_)
+            | ObjectData::NativeFunction(_)
             | ObjectData::Bool(_)
             | ObjectData::UnsignedInt(_)
             | ObjectData::SignedInt(_)
             | ObjectData::Int(_)
             | ObjectData::Float(_)
             | ObjectData::String(_)
+            | ObjectData::Char(_)
+            | ObjectData::Range(_)
             | ObjectData::Unit(_) => {
                 // no reachable data
             }
@@ -235,6 +313,9 @@ impl Stepper<'_> {
             tracing::debug!("freeing {:?}: {:?}", o, data);
         }
 
+        self.machine
+            .record_collection(dead_objects.len(), dead_permissions.len());
+
         Ok(())
     }
 }
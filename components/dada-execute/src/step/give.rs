@@ -27,7 +27,20 @@ impl Stepper<'_> {
         place: bir::Place,
     ) -> eyre::Result<Value> {
         let object_traversal = self.traverse_to_object(table, place)?;
-        self.give_traversal(table, object_traversal)
+        let value = self.give_traversal(table, object_traversal)?;
+
+        // If the value was moved out of a local variable, clear the variable
+        // (as `ActionData::Clear` would) so that it no longer refers to the
+        // object that it gave away. The expired permission is kept, as it
+        // records where the value was given away.
+        if let bir::PlaceData::LocalVariable(lv) = table[place] {
+            let permission = self.machine.local(lv).permission;
+            if self.machine[permission].valid().is_none() {
+                self.machine.local_mut(lv).object = self.machine.unit_object();
+            }
+        }
+
+        Ok(value)
     }
 
     #[tracing::instrument(level = "debug", skip(self))]
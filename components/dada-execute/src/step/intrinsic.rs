@@ -4,7 +4,7 @@ use eyre::Context;
 use crate::{
     error::DiagnosticBuilderExt,
     machine::stringify::DefaultStringify,
-    machine::{op::MachineOpExtMut, ProgramCounter, Value},
+    machine::{op::MachineOpExtMut, ObjectData, Outcome, ProgramCounter, Range, Value},
     thunk::RustThunk,
 };
 
@@ -14,6 +14,10 @@ pub(crate) type IntrinsicFn = fn(&mut Stepper<'_>, Vec<Value>) -> eyre::Result<V
 
 pub(crate) struct IntrinsicDefinition {
     pub(crate) argument_names: Vec<Word>,
+
+    /// Number of trailing arguments in `argument_names` that may be omitted.
+    pub(crate) optional_arguments: usize,
+
     pub(crate) function: IntrinsicFn,
 }
 
@@ -22,11 +26,143 @@ impl IntrinsicDefinition {
         match intrinsic {
             Intrinsic::Print => IntrinsicDefinition {
                 argument_names: vec![Word::intern(db, "message")],
+                optional_arguments: 0,
                 function: |s, v| s.intrinsic_print(v),
                 // FIXME: Stepper::intrinsic_write doesn't type check, why?
             },
+            Intrinsic::EPrint => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "message")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_eprint(v),
+            },
+            Intrinsic::Len => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "string")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_len(v),
+            },
+            Intrinsic::ReadLine => IntrinsicDefinition {
+                argument_names: vec![],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_read_line(v),
+            },
+            Intrinsic::Assert => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "condition"), Word::intern(db, "message")],
+                optional_arguments: 1,
+                function: |s, v| s.intrinsic_assert(v),
+            },
+            Intrinsic::TypeOf => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "value")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_type_of(v),
+            },
+            Intrinsic::Range => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "start"), Word::intern(db, "end")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_range(v, false),
+            },
+            Intrinsic::RangeInclusive => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "start"), Word::intern(db, "end")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_range(v, true),
+            },
+            Intrinsic::RangeStart => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "range")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_range_bound(v, |r| r.start),
+            },
+            Intrinsic::RangeEnd => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "range")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_range_bound(v, |r| r.end),
+            },
+            Intrinsic::Min => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "a"), Word::intern(db, "b")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_min_max(v, Intrinsic::Min),
+            },
+            Intrinsic::Max => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "a"), Word::intern(db, "b")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_min_max(v, Intrinsic::Max),
+            },
+            Intrinsic::Abs => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "value")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_abs(v),
+            },
+            Intrinsic::ToUpper => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "string")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_map_string(v, str::to_uppercase),
+            },
+            Intrinsic::ToLower => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "string")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_map_string(v, str::to_lowercase),
+            },
+            Intrinsic::Trim => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "string")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_map_string(v, |s| s.trim().to_string()),
+            },
+            Intrinsic::Substring => IntrinsicDefinition {
+                argument_names: vec![
+                    Word::intern(db, "string"),
+                    Word::intern(db, "start"),
+                    Word::intern(db, "end"),
+                ],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_substring(v),
+            },
+            Intrinsic::ParseInt => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "string")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_parse_int(v),
+            },
+            Intrinsic::ParseFloat => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "string")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_parse_float(v),
+            },
+            Intrinsic::ToString => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "value")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_to_string(v),
+            },
+            Intrinsic::Panic => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "message")],
+                optional_arguments: 1,
+                function: |s, v| s.intrinsic_panic(v),
+            },
+            Intrinsic::Ok => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "value")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_outcome(v, true),
+            },
+            Intrinsic::Err => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "value")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_outcome(v, false),
+            },
+            Intrinsic::IsOk => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "result")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_is_ok(v),
+            },
+            Intrinsic::Unwrap => IntrinsicDefinition {
+                argument_names: vec![Word::intern(db, "result")],
+                optional_arguments: 0,
+                function: |s, v| s.intrinsic_unwrap(v),
+            },
         }
     }
+
+    /// The argument names expected when the intrinsic is called with
+    /// `num_arguments` arguments (optional arguments may be left off the end).
+    pub(crate) fn argument_names_for(&self, num_arguments: usize) -> &[Word] {
+        let required = self.argument_names.len() - self.optional_arguments;
+        &self.argument_names[..num_arguments.clamp(required, self.argument_names.len())]
+    }
 }
 
 impl Stepper<'_> {
@@ -44,7 +180,74 @@ impl Stepper<'_> {
                 let await_pc = self.machine.pc();
                 self.intrinsic_print_async(await_pc, value).await
             }
+            Intrinsic::EPrint => {
+                let value = values.pop().unwrap();
+                let await_pc = self.machine.pc();
+                self.intrinsic_eprint_async(await_pc, value).await
+            }
+            Intrinsic::ReadLine => {
+                let await_pc = self.machine.pc();
+                self.intrinsic_read_line_async(await_pc).await
+            }
+            Intrinsic::Assert
+            | Intrinsic::Len
+            | Intrinsic::TypeOf
+            | Intrinsic::Range
+            | Intrinsic::RangeInclusive
+            | Intrinsic::RangeStart
+            | Intrinsic::RangeEnd
+            | Intrinsic::Min
+            | Intrinsic::Max
+            | Intrinsic::Abs
+            | Intrinsic::ToUpper
+            | Intrinsic::ToLower
+            | Intrinsic::Trim
+            | Intrinsic::Substring
+            | Intrinsic::ParseInt
+            | Intrinsic::ParseFloat
+            | Intrinsic::ToString
+            | Intrinsic::Panic
+            | Intrinsic::Ok
+            | Intrinsic::Err
+            | Intrinsic::IsOk
+            | Intrinsic::Unwrap => {
+                unreachable!("{intrinsic:?} does not yield a thunk")
+            }
+        }
+    }
+
+    fn intrinsic_assert(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let span = self.machine.pc().span(self.db);
+        let condition = match &self.machine[values[0].object] {
+            ObjectData::Bool(b) => *b,
+            data => return Err(Self::unexpected_kind(self.db, span, data, "a boolean")),
+        };
+
+        if !condition {
+            return Err(match values.get(1) {
+                Some(&message) => {
+                    let message =
+                        DefaultStringify::stringify_value(&*self.machine, self.db, message);
+                    error!(span, "assertion failed: {message}").eyre(self.db)
+                }
+                None => error!(span, "assertion failed").eyre(self.db),
+            });
         }
+
+        Ok(self.machine.our_value(self.machine.pc(), ()))
+    }
+
+    /// Only reached when `panic` is called indirectly (e.g., through a
+    /// variable); direct calls are brewed into a panic terminator.
+    fn intrinsic_panic(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let span = self.machine.pc().span(self.db);
+        Err(match values.first() {
+            Some(&message) => {
+                let message = DefaultStringify::stringify_value(&*self.machine, self.db, message);
+                error!(span, "panic: {message}").eyre(self.db)
+            }
+            None => error!(span, "panic! omg! 😱").eyre(self.db),
+        })
     }
 
     fn intrinsic_print(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
@@ -78,4 +281,339 @@ impl Stepper<'_> {
 
         Ok(self.machine.our_value(await_pc, ()))
     }
+
+    fn intrinsic_len(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let len = match &self.machine[values[0].object] {
+            ObjectData::String(s) => s.chars().count() as u64,
+            data => {
+                let span = self.machine.pc().span(self.db);
+                return Err(Self::unexpected_kind(self.db, span, data, "a string"));
+            }
+        };
+        Ok(self.machine.our_value(self.machine.pc(), len))
+    }
+
+    /// Implements intrinsics like `to_upper` that compute a new string from
+    /// a string. Like string literals, the result is shared (`our`).
+    fn intrinsic_map_string(
+        &mut self,
+        values: Vec<Value>,
+        f: fn(&str) -> String,
+    ) -> eyre::Result<Value> {
+        let string = match &self.machine[values[0].object] {
+            ObjectData::String(s) => f(s),
+            data => {
+                let span = self.machine.pc().span(self.db);
+                return Err(Self::unexpected_kind(self.db, span, data, "a string"));
+            }
+        };
+        Ok(self.machine.our_value(self.machine.pc(), string))
+    }
+
+    /// Returns the characters of a string from `start` up to (but not
+    /// including) `end`. Indices count characters, not bytes.
+    fn intrinsic_substring(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let span = self.machine.pc().span(self.db);
+        let start = self.index_argument(values[1])?;
+        let end = self.index_argument(values[2])?;
+        let substring = match &self.machine[values[0].object] {
+            ObjectData::String(s) => match (start, end) {
+                (Some(start), Some(end)) if start <= end && end <= s.chars().count() => {
+                    s.chars().skip(start).take(end - start).collect::<String>()
+                }
+                _ => return Err(error!(span, "substring out of bounds").eyre(self.db)),
+            },
+            data => return Err(Self::unexpected_kind(self.db, span, data, "a string")),
+        };
+        Ok(self.machine.our_value(self.machine.pc(), substring))
+    }
+
+    /// Parses a string (ignoring surrounding whitespace, such as the newline
+    /// left by `read_line`) as an integer. Like an integer literal, the result
+    /// is an unsuffixed integer unless it is negative.
+    ///
+    /// Malformed input is reported as an error rather than returned as a
+    /// value, like the other intrinsics; programs cannot recover from it.
+    fn intrinsic_parse_int(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let span = self.machine.pc().span(self.db);
+        let data = match &self.machine[values[0].object] {
+            ObjectData::String(s) => {
+                let s = s.trim();
+                if let Ok(v) = s.parse::<u64>() {
+                    ObjectData::Int(v)
+                } else if let Ok(v) = s.parse::<i64>() {
+                    ObjectData::SignedInt(v)
+                } else {
+                    return Err(error!(span, "cannot parse {s:?} as an integer").eyre(self.db));
+                }
+            }
+            data => return Err(Self::unexpected_kind(self.db, span, data, "a string")),
+        };
+        Ok(self.machine.our_value(self.machine.pc(), data))
+    }
+
+    /// Like `parse_int`, but parses a float.
+    fn intrinsic_parse_float(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let span = self.machine.pc().span(self.db);
+        let value = match &self.machine[values[0].object] {
+            ObjectData::String(s) => match s.trim().parse::<f64>() {
+                Ok(v) => v,
+                Err(_) => {
+                    return Err(error!(span, "cannot parse {:?} as a float", s.trim()).eyre(self.db))
+                }
+            },
+            data => return Err(Self::unexpected_kind(self.db, span, data, "a string")),
+        };
+        Ok(self.machine.our_value(self.machine.pc(), value))
+    }
+
+    /// Converts a primitive value (e.g., a number or a boolean) into a string,
+    /// rendered as `print` (and the data nodes of the heap-graph) would.
+    fn intrinsic_to_string(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let object = values[0].object;
+        match &self.machine[object] {
+            ObjectData::Bool(_)
+            | ObjectData::UnsignedInt(_)
+            | ObjectData::Int(_)
+            | ObjectData::SignedInt(_)
+            | ObjectData::Float(_)
+            | ObjectData::String(_)
+            | ObjectData::Char(_)
+            | ObjectData::Unit(_) => {}
+            data => {
+                let span = self.machine.pc().span(self.db);
+                return Err(Self::unexpected_kind(
+                    self.db,
+                    span,
+                    data,
+                    "a primitive value",
+                ));
+            }
+        }
+        let string = DefaultStringify::stringify_object(&*self.machine, self.db, object);
+        Ok(self.machine.our_value(self.machine.pc(), string))
+    }
+
+    /// Wraps the argument as `ok(value)` (if `ok` is true) or `err(value)`.
+    fn intrinsic_outcome(&mut self, values: Vec<Value>, ok: bool) -> eyre::Result<Value> {
+        let outcome = Outcome {
+            ok,
+            value: values[0],
+        };
+        Ok(self.machine.my_value(self.machine.pc(), outcome))
+    }
+
+    fn intrinsic_is_ok(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let outcome = self.outcome_argument(values[0])?;
+        Ok(self.machine.our_value(self.machine.pc(), outcome.ok))
+    }
+
+    /// Returns the value in an `ok` result, shared as if read from a field;
+    /// reports an error (showing the value) for an `err` result.
+    fn intrinsic_unwrap(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let outcome = self.outcome_argument(values[0])?;
+        let pc = self.machine.pc();
+        if !outcome.ok {
+            let span = pc.span(self.db);
+            let value = DefaultStringify::stringify_value(&*self.machine, self.db, outcome.value);
+            return Err(error!(span, "called `unwrap` on an error: {value}").eyre(self.db));
+        }
+        let traversal = self.traverse_to_field_value(pc.control_point, values[0], outcome.value)?;
+        self.shlease_traversal(traversal)
+    }
+
+    fn outcome_argument(&self, value: Value) -> eyre::Result<Outcome> {
+        match &self.machine[value.object] {
+            ObjectData::Result(outcome) => Ok(*outcome),
+            data => {
+                let span = self.machine.pc().span(self.db);
+                Err(Self::unexpected_kind(self.db, span, data, "a result"))
+            }
+        }
+    }
+
+    /// Converts an integer argument into an index, which is `None` if the
+    /// integer is negative (or too large to be an index).
+    fn index_argument(&self, value: Value) -> eyre::Result<Option<usize>> {
+        match self.machine[value.object] {
+            ObjectData::Int(v) | ObjectData::UnsignedInt(v) => Ok(usize::try_from(v).ok()),
+            ObjectData::SignedInt(v) => Ok(usize::try_from(v).ok()),
+            ref data => {
+                let span = self.machine.pc().span(self.db);
+                Err(Self::unexpected_kind(self.db, span, data, "an integer"))
+            }
+        }
+    }
+
+    fn intrinsic_type_of(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let type_name = self.machine[values[0].object].type_name(self.db);
+        Ok(self.machine.our_value(self.machine.pc(), type_name))
+    }
+
+    fn intrinsic_range(&mut self, values: Vec<Value>, inclusive: bool) -> eyre::Result<Value> {
+        let start = self.range_bound(values[0])?;
+        let end = self.range_bound(values[1])?;
+        let range = Range {
+            start,
+            end,
+            inclusive,
+        };
+        Ok(self
+            .machine
+            .our_value(self.machine.pc(), ObjectData::Range(range)))
+    }
+
+    /// Converts an argument to `range` into a bound of the range.
+    fn range_bound(&self, value: Value) -> eyre::Result<i64> {
+        let span = self.machine.pc().span(self.db);
+        match self.machine[value.object] {
+            ObjectData::Int(v) | ObjectData::UnsignedInt(v) => match i64::try_from(v) {
+                Ok(v) => Ok(v),
+                Err(_) => Err(error!(span, "range bound {v} is too large").eyre(self.db)),
+            },
+            ObjectData::SignedInt(v) => Ok(v),
+            ref data => Err(Self::unexpected_kind(self.db, span, data, "an integer")),
+        }
+    }
+
+    /// Returns one bound of a range. Like an integer literal, the result is
+    /// an unsuffixed integer unless it is negative.
+    fn intrinsic_range_bound(
+        &mut self,
+        values: Vec<Value>,
+        bound: fn(&Range) -> i64,
+    ) -> eyre::Result<Value> {
+        let value = match &self.machine[values[0].object] {
+            ObjectData::Range(range) => bound(range),
+            data => {
+                let span = self.machine.pc().span(self.db);
+                return Err(Self::unexpected_kind(self.db, span, data, "a range"));
+            }
+        };
+        let data = match u64::try_from(value) {
+            Ok(value) => ObjectData::Int(value),
+            Err(_) => ObjectData::SignedInt(value),
+        };
+        Ok(self.machine.our_value(self.machine.pc(), data))
+    }
+
+    /// Implements `min` and `max`. The arguments are first converted to a
+    /// common type: an unsuffixed integer adopts the type of the other
+    /// argument, and integers are converted to floats when mixed with them.
+    fn intrinsic_min_max(
+        &mut self,
+        values: Vec<Value>,
+        intrinsic: Intrinsic,
+    ) -> eyre::Result<Value> {
+        let span = self.machine.pc().span(self.db);
+        let (a, b) = (
+            &self.machine[values[0].object],
+            &self.machine[values[1].object],
+        );
+        for data in [a, b] {
+            if !data.is_number() {
+                return Err(Self::unexpected_kind(self.db, span, data, "a number"));
+            }
+        }
+
+        let max = intrinsic == Intrinsic::Max;
+        let pick = |a_is_less: bool| if a_is_less == max { b } else { a };
+        let data = match (a, b) {
+            (&ObjectData::Int(x), &ObjectData::Int(y))
+            | (&ObjectData::UnsignedInt(x), &ObjectData::UnsignedInt(y)) => pick(x < y).clone(),
+            (&ObjectData::UnsignedInt(x), &ObjectData::Int(y))
+            | (&ObjectData::Int(x), &ObjectData::UnsignedInt(y)) => {
+                ObjectData::UnsignedInt(if max { x.max(y) } else { x.min(y) })
+            }
+            (&ObjectData::SignedInt(x), &ObjectData::SignedInt(y)) => pick(x < y).clone(),
+            (&ObjectData::Int(x), &ObjectData::SignedInt(y))
+            | (&ObjectData::SignedInt(y), &ObjectData::Int(x)) => match i64::try_from(x) {
+                Ok(x) => ObjectData::SignedInt(if max { x.max(y) } else { x.min(y) }),
+                Err(_) => return Err(error!(span, "arithmetic overflow").eyre(self.db)),
+            },
+            _ => match (a.as_f64(), b.as_f64()) {
+                (Some(x), Some(y)) if a.is_float() || b.is_float() => {
+                    ObjectData::Float(if max { x.max(y) } else { x.min(y) })
+                }
+                _ => {
+                    return Err(error!(
+                        span,
+                        "cannot apply `{}` to {} and {}",
+                        intrinsic.as_str(self.db),
+                        a.kind_str(self.db),
+                        b.kind_str(self.db),
+                    )
+                    .eyre(self.db))
+                }
+            },
+        };
+        Ok(self.machine.our_value(self.machine.pc(), data))
+    }
+
+    fn intrinsic_abs(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        let span = self.machine.pc().span(self.db);
+        let data = match self.machine[values[0].object] {
+            ObjectData::Int(v) => ObjectData::Int(v),
+            ObjectData::UnsignedInt(v) => ObjectData::UnsignedInt(v),
+            ObjectData::SignedInt(v) => match v.checked_abs() {
+                Some(v) => ObjectData::SignedInt(v),
+                None => return Err(error!(span, "arithmetic overflow").eyre(self.db)),
+            },
+            ObjectData::Float(v) => ObjectData::Float(v.abs()),
+            ref data => return Err(Self::unexpected_kind(self.db, span, data, "a number")),
+        };
+        Ok(self.machine.our_value(self.machine.pc(), data))
+    }
+
+    fn intrinsic_eprint(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        Ok(self.machine.my_value(
+            self.machine.pc(),
+            RustThunk::new("eprint", values, Intrinsic::EPrint),
+        ))
+    }
+
+    #[tracing::instrument(level = "Debug", skip(self, await_pc))]
+    pub(super) async fn intrinsic_eprint_async(
+        &mut self,
+        await_pc: ProgramCounter,
+        value: Value,
+    ) -> eyre::Result<Value> {
+        let message_str = DefaultStringify::stringify_value(&*self.machine, self.db, value);
+
+        async {
+            let kernel = self.kernel.as_mut().unwrap();
+            kernel.print_err(await_pc, &message_str).await?;
+            kernel.print_err(await_pc, "\n").await
+        }
+        .await
+        .with_context(|| {
+            let span_now = self.machine.pc().span(self.db);
+            error!(span_now, "error printing `{:?}`", message_str).eyre(self.db)
+        })?;
+
+        Ok(self.machine.our_value(await_pc, ()))
+    }
+
+    fn intrinsic_read_line(&mut self, values: Vec<Value>) -> eyre::Result<Value> {
+        Ok(self.machine.my_value(
+            self.machine.pc(),
+            RustThunk::new("read_line", values, Intrinsic::ReadLine),
+        ))
+    }
+
+    #[tracing::instrument(level = "Debug", skip(self, await_pc))]
+    async fn intrinsic_read_line_async(&mut self, await_pc: ProgramCounter) -> eyre::Result<Value> {
+        let line = self
+            .kernel
+            .as_mut()
+            .unwrap()
+            .read_line(await_pc)
+            .await
+            .with_context(|| {
+                let span_now = self.machine.pc().span(self.db);
+                error!(span_now, "error reading a line of input").eyre(self.db)
+            })?;
+
+        Ok(self.machine.our_value(await_pc, line))
+    }
 }
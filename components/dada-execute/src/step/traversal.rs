@@ -132,6 +132,9 @@ impl Stepper<'_> {
             bir::PlaceData::Intrinsic(i) => {
                 Ok(self.traverse_to_constant(ObjectData::Intrinsic(*i)))
             }
+            bir::PlaceData::NativeFunction(name) => {
+                Ok(self.traverse_to_constant(ObjectData::NativeFunction(*name)))
+            }
             bir::PlaceData::Dot(owner_place, field_name) => {
                 let ObjectTraversal {
                     mut accumulated_permissions,
@@ -147,6 +150,19 @@ impl Stepper<'_> {
                     address: Address::Field(owner_object, field_index),
                 })
             }
+            bir::PlaceData::Index(owner_place, index_place) => {
+                let index = self.eval_place_to_int(table, *index_place)?;
+                let ObjectTraversal {
+                    accumulated_permissions,
+                    object: owner_object,
+                } = self.traverse_to_object(table, *owner_place)?;
+                let element_index = self.list_element(place, owner_object, index)?;
+
+                Ok(PlaceTraversal {
+                    accumulated_permissions,
+                    address: Address::Field(owner_object, element_index),
+                })
+            }
         }
     }
 
@@ -170,6 +186,10 @@ impl Stepper<'_> {
     /// If this returns `Ok`, the data in the object is at least potentially *accessible*,
     /// though some of the objects along the way may currently be leased. If the place
     /// tries to dereference an expired permission, returns `Err`.
+    ///
+    /// Traversals follow the structure of `bir_place` (one step per field
+    /// or index), not the heap, so they terminate even if the heap contains
+    /// a cycle.
     pub(super) fn traverse_to_object(
         &mut self,
         table: &bir::Tables,
@@ -180,6 +200,16 @@ impl Stepper<'_> {
             address,
         } = self.traverse_to_place(table, bir_place)?;
         let Value { permission, object } = self.peek(address);
+
+        // A local variable that has never been assigned (or that was cleared)
+        // holds an expired permission that was never granted.
+        if let (Address::Local(_), PermissionData::Expired(None)) =
+            (address, &self.machine[permission])
+        {
+            let span = self.span_from_bir(bir_place);
+            return Err(error!(span, "variable used before assignment").eyre(self.db));
+        }
+
         let permissions =
             self.accumulate_permission(bir_place, accumulated_permissions, permission)?;
 
@@ -244,15 +274,59 @@ impl Stepper<'_> {
                 }
                 Err(error!(place_span, "no field named `{}`", field_name_str).eyre(self.db))
             }
-            owner_data => Err(Self::unexpected_kind(
-                self.db,
+            owner_data => Err(error!(
                 place_span,
-                owner_data,
-                "something with fields",
+                "value of type {} has no fields",
+                owner_data.kind_str(self.db)
+            )
+            .eyre(self.db)),
+        }
+    }
+
+    /// Returns the index of the element of the list `owner_object` found at `index`
+    /// (which is `None` if the index was too large to represent).
+    fn list_element(
+        &mut self,
+        place: impl HasOriginIn<bir::Origins, Origin = syntax::Expr>,
+        owner_object: Object,
+        index: Option<i64>,
+    ) -> eyre::Result<usize> {
+        let place_span = self.span_from_bir(place);
+        match &self.machine[owner_object] {
+            ObjectData::List(list) => {
+                let len = list.elements.len();
+                match index.and_then(|i| usize::try_from(i).ok()) {
+                    Some(i) if i < len => Ok(i),
+                    _ => Err(
+                        error!(place_span, "index out of bounds for a list of length {len}")
+                            .eyre(self.db),
+                    ),
+                }
+            }
+            owner_data => Err(Self::unexpected_kind(
+                self.db, place_span, owner_data, "a list",
             )),
         }
     }
 
+    /// Returns a traversal that starts from `owner` (a value held by the
+    /// caller, such as an argument to an intrinsic) and follows `field`,
+    /// a value stored in `owner`'s object, to reach its object.
+    pub(super) fn traverse_to_field_value(
+        &mut self,
+        place: impl HasOriginIn<bir::Origins, Origin = syntax::Expr> + Copy,
+        owner: Value,
+        field: Value,
+    ) -> eyre::Result<ObjectTraversal> {
+        let permissions = AccumulatedPermissions::unique(Atomic::No);
+        let permissions = self.accumulate_permission(place, permissions, owner.permission)?;
+        let permissions = self.accumulate_permission(place, permissions, field.permission)?;
+        Ok(ObjectTraversal {
+            accumulated_permissions: permissions,
+            object: field.object,
+        })
+    }
+
     fn traverse_to_constant(&mut self, object_data: ObjectData) -> PlaceTraversal {
         let object = self.machine.our_value(self.machine.pc(), object_data);
         let permissions = AccumulatedPermissions {
@@ -269,7 +343,7 @@ impl Stepper<'_> {
 
     fn accumulate_permission(
         &mut self,
-        place: bir::Place,
+        place: impl HasOriginIn<bir::Origins, Origin = syntax::Expr>,
         accumulated_permissions: AccumulatedPermissions,
         permission: Permission,
     ) -> eyre::Result<AccumulatedPermissions> {
@@ -341,6 +415,8 @@ pub(super) fn report_traversing_expired_permission(
 
             let secondary_label = if expired_at.is_return(db) {
                 "lease was cancelled when this function returned"
+            } else if expired_at.is_give(db) {
+                "value was given away here"
             } else {
                 "lease was cancelled here"
             };
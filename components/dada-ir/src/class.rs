@@ -1,7 +1,8 @@
 use crate::{
     code::syntax,
     input_file::InputFile,
-    span::{Anchored, Span},
+    span::{Anchored, FileSpan, Span},
+    storage::Atomic,
     word::Word,
 };
 
@@ -24,6 +25,37 @@ impl Class {
         let signature = self.signature_syntax(db);
         signature.spans[signature.name]
     }
+
+    /// Returns the fields of this class, in declaration order, as
+    /// declared in its signature.
+    pub fn field_infos(self, db: &dyn crate::Db) -> Vec<FieldInfo> {
+        let signature = self.signature_syntax(db);
+        signature
+            .parameters
+            .iter()
+            .map(|&parameter| {
+                let data = &signature.tables[parameter];
+                FieldInfo {
+                    name: signature.tables[data.name].word,
+                    span: signature.spans[parameter].anchor_to(db, &self),
+                    atomic: Atomic::from(data.atomic),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Describes a field of a class; see [`Class::field_infos`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// Name of the field.
+    pub name: Word,
+
+    /// Span of the field's declaration (e.g., `atomic x: my String`).
+    pub span: FileSpan,
+
+    /// Was the field declared as atomic?
+    pub atomic: Atomic,
 }
 
 impl Anchored for Class {
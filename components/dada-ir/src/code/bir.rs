@@ -5,8 +5,11 @@ use std::collections::BTreeSet;
 
 use crate::{
     class::Class,
-    code::validated::op::Op,
+    code::validated::op::{Op, UnaryOp},
+    diagnostic::Diagnostic,
+    format_string::FormatSpec,
     function::Function,
+    graph::{DirectedGraph, Dominators, Successors},
     in_ir_db::InIrDb,
     input_file::InputFile,
     intrinsic::Intrinsic,
@@ -16,11 +19,16 @@ use crate::{
     storage::Atomic,
     word::Word,
 };
+use dada_collections::Map;
 use dada_id::{id, prelude::*, tables};
 use salsa::DebugWithDb;
 
 use super::{syntax, validated};
 
+mod dead_assignments;
+mod fold;
+mod pseudocode;
+
 #[salsa::tracked]
 pub struct Bir {
     /// Name of file containing the code from which this Bir was created.
@@ -74,6 +82,90 @@ impl Bir {
         let syntax_tree = self.syntax_tree(db);
         syntax_tree.spans(db)[syntax_node].anchor_to(db, self)
     }
+
+    /// Returns the span of the source code from which `node` (e.g., an
+    /// [`Expr`], [`Place`], or [`ControlPoint`] of this BIR) was created,
+    /// by way of the syntax node recorded in its [`Origins`].
+    /// Like [`Self::span_of`], this is to be avoided except for diagnostics
+    /// and tooling, as it depends on the precise span of the source code.
+    pub fn origin_span<N>(self, db: &dyn crate::Db, node: N) -> FileSpan
+    where
+        N: HasOriginIn<Origins>,
+        N::Origin: HasOriginIn<syntax::Spans, Origin = Span>,
+    {
+        let syntax_node = self.origins(db).get(node);
+        self.span_of(db, syntax_node)
+    }
+
+    /// Returns the span of source code from which the control point `cp`
+    /// was created, or `None` if `cp` does not belong to this BIR.
+    /// Like [`Self::span_of`], this should only be used for diagnostics,
+    /// tracing, and other tooling.
+    pub fn point_span(self, db: &dyn crate::Db, cp: ControlPoint) -> Option<FileSpan> {
+        if cp >= ControlPoint::max_key(&self.data(db).tables) {
+            return None;
+        }
+        Some(self.origin_span(db, cp))
+    }
+
+    /// Returns a warning for each piece of source code whose control points
+    /// are all unreachable from the start point (see [`BirData::unreachable_points`]).
+    /// This is not part of the normal checks, as brewing routinely creates some
+    /// unreachable control points, but it is useful for finding dead code
+    /// and bugs in brewing.
+    pub fn unreachable_point_warnings(self, db: &dyn crate::Db) -> Vec<Diagnostic> {
+        let reachable_spans: Vec<FileSpan> = reachable_control_points(db, self)
+            .iter()
+            .filter_map(|&cp| self.point_span(db, cp))
+            .collect();
+        let mut spans: Vec<FileSpan> = vec![];
+        for cp in self.data(db).unreachable_points() {
+            let Some(span) = self.point_span(db, cp) else {
+                continue;
+            };
+            if !spans.contains(&span) && !reachable_spans.contains(&span) {
+                spans.push(span);
+            }
+        }
+        spans
+            .into_iter()
+            .map(|span| crate::warning!(span, "unreachable code").finish())
+            .collect()
+    }
+
+    /// Renders the control-flow graph of this BIR in graphviz (DOT) format,
+    /// with one node per reachable control point. Useful for debugging brewing.
+    pub fn graphviz_cfg(self, db: &dyn crate::Db) -> String {
+        let in_ir_db = &self.in_ir_db(db.as_dyn_ir_db());
+        let bir_data = self.data(db);
+
+        let mut output = String::new();
+        output.push_str("digraph {\n");
+        output.push_str("  node[shape = \"box\"];\n");
+        for (cp, successors) in bir_data.control_flow_graph() {
+            let index = u32::from(cp);
+            let label = match cp.data(&bir_data.tables) {
+                ControlPointData::Statement(s) => format!("{:?}", s.action.debug(in_ir_db)),
+                ControlPointData::Terminator(t) => format!("{:?}", t.debug(in_ir_db)),
+            };
+            let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+            output.push_str(&format!("  cp{index} [label = \"{index}: {label}\"];\n"));
+            for successor in successors {
+                let successor = u32::from(successor);
+                output.push_str(&format!("  cp{index} -> cp{successor};\n"));
+            }
+        }
+        output.push_str("}\n");
+        output
+    }
+}
+
+/// The control points of `bir` that are reachable from its start point
+/// (see [`BirData::control_points`]). Unlike calling that method directly,
+/// this is memoized, so repeated passes over the BIR don't re-walk the graph.
+#[salsa::tracked(return_ref)]
+pub fn reachable_control_points(db: &dyn crate::Db, bir: Bir) -> BTreeSet<ControlPoint> {
+    bir.data(db).control_points()
 }
 
 /// Stores the ast for a function.
@@ -127,6 +219,14 @@ impl BirData {
         LocalVariable::max_key(&self.tables)
     }
 
+    /// Returns the local variables that have a user-facing name (including
+    /// parameters), skipping temporaries, in the order they were declared.
+    pub fn named_locals(&self) -> impl Iterator<Item = (LocalVariable, Word)> + '_ {
+        self.max_local_variable()
+            .iter()
+            .filter_map(|lv| Some((lv, self.tables[lv].name?)))
+    }
+
     pub fn control_points(&self) -> BTreeSet<ControlPoint> {
         let mut points = BTreeSet::new();
         let mut stack = vec![self.start_point];
@@ -139,6 +239,51 @@ impl BirData {
 
         points
     }
+
+    /// Returns the control points that were allocated but are not reachable
+    /// from the start point (i.e., are not in [`Self::control_points`]),
+    /// in the order they were allocated.
+    pub fn unreachable_points(&self) -> Vec<ControlPoint> {
+        let reachable = self.control_points();
+        ControlPoint::range(0, ControlPoint::max_key(&self.tables).into())
+            .filter(|cp| !reachable.contains(cp))
+            .collect()
+    }
+
+    /// Returns each reachable control point (in the same order as
+    /// [`Self::control_points`]) paired with its successors.
+    pub fn control_flow_graph(&self) -> Vec<(ControlPoint, Vec<ControlPoint>)> {
+        self.control_points()
+            .into_iter()
+            .map(|cp| (cp, cp.successors(self)))
+            .collect()
+    }
+
+    /// Maps each reachable control point to the reachable control points
+    /// that can jump to it (in ascending order).
+    pub fn predecessors(&self) -> Map<ControlPoint, Vec<ControlPoint>> {
+        crate::graph::predecessor_map(self)
+    }
+
+    /// Computes the dominator tree of the reachable control points, rooted
+    /// at the start point.
+    pub fn dominators(&self) -> Dominators<ControlPoint> {
+        crate::graph::dominators(self)
+    }
+}
+
+impl DirectedGraph for BirData {
+    type Node = ControlPoint;
+
+    fn start_node(&self) -> ControlPoint {
+        self.start_point
+    }
+}
+
+impl Successors for BirData {
+    fn successors(&self, node: ControlPoint) -> Vec<ControlPoint> {
+        node.successors(self)
+    }
 }
 
 tables! {
@@ -329,12 +474,19 @@ impl DebugWithDb<InIrDb<'_, Bir>> for ActionData {
 pub enum TerminatorData {
     Goto(ControlPoint),
     If(Place, ControlPoint, ControlPoint),
+
+    /// Multi-way branch on an integer: jumps to the control point of the
+    /// first arm whose value equals the place, or to the default otherwise.
+    Switch(Place, Vec<(i64, ControlPoint)>, ControlPoint),
+
     StartAtomic(ControlPoint),
     EndAtomic(ControlPoint),
     Return(Place),
     Assign(TargetPlace, TerminatorExpr, ControlPoint),
     Error,
-    Panic,
+
+    /// Aborts execution, reporting the message stored in the place (if any).
+    Panic(Option<Place>),
 }
 
 impl TerminatorData {
@@ -342,12 +494,15 @@ impl TerminatorData {
         match *self {
             TerminatorData::Goto(c) => vec![c],
             TerminatorData::If(_, a, b) => vec![a, b],
+            TerminatorData::Switch(_, ref arms, default) => {
+                arms.iter().map(|&(_, c)| c).chain(Some(default)).collect()
+            }
             TerminatorData::StartAtomic(a) => vec![a],
             TerminatorData::EndAtomic(a) => vec![a],
             TerminatorData::Return(_) => vec![],
             TerminatorData::Assign(_, _, a) => vec![a],
             TerminatorData::Error => vec![],
-            TerminatorData::Panic => vec![],
+            TerminatorData::Panic(_) => vec![],
         }
     }
 }
@@ -362,6 +517,12 @@ impl DebugWithDb<InIrDb<'_, Bir>> for TerminatorData {
                 .field(&if_true.debug(db))
                 .field(&if_false.debug(db))
                 .finish(),
+            TerminatorData::Switch(scrutinee, arms, default) => f
+                .debug_tuple("Switch")
+                .field(&scrutinee.debug(db))
+                .field(arms)
+                .field(&default.debug(db))
+                .finish(),
             TerminatorData::StartAtomic(block) => {
                 f.debug_tuple("StartAomic").field(&block.debug(db)).finish()
             }
@@ -378,7 +539,10 @@ impl DebugWithDb<InIrDb<'_, Bir>> for TerminatorData {
                 .field(&next.debug(db))
                 .finish(),
             TerminatorData::Error => f.debug_tuple("Error").finish(),
-            TerminatorData::Panic => f.debug_tuple("Panic").finish(),
+            TerminatorData::Panic(message) => f
+                .debug_tuple("Panic")
+                .field(&message.map(|m| m.debug(db)))
+                .finish(),
         }
     }
 }
@@ -387,6 +551,10 @@ impl DebugWithDb<InIrDb<'_, Bir>> for TerminatorData {
 pub enum TerminatorExpr {
     Await(Place),
 
+    /// Await each of the thunks in order, storing a tuple
+    /// of their results.
+    AwaitAll(Vec<Place>),
+
     /// Call `function(arguments...)`. The `labels` for each
     /// argument are present as well.
     Call {
@@ -400,6 +568,9 @@ impl DebugWithDb<InIrDb<'_, Bir>> for TerminatorExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>, db: &InIrDb<'_, Bir>) -> std::fmt::Result {
         match self {
             TerminatorExpr::Await(place) => f.debug_tuple("Await").field(&place.debug(db)).finish(),
+            TerminatorExpr::AwaitAll(places) => {
+                f.debug_tuple("AwaitAll").field(&places.debug(db)).finish()
+            }
             TerminatorExpr::Call {
                 function,
                 arguments,
@@ -441,6 +612,9 @@ pub enum ExprData {
     /// `"foo"` with no format strings
     StringLiteral(Word),
 
+    /// `'a'`
+    CharLiteral(char),
+
     /// `<value>.share`
     IntoShared(Place),
 
@@ -459,15 +633,18 @@ pub enum ExprData {
     /// `(a, b, ...)` (i.e., at least 2)
     Tuple(Vec<Place>),
 
+    /// `[a, b, ...]`
+    List(Vec<Place>),
+
     /// Concatenates a bunch of strings together from a format literal like
-    /// `foo{bar}baz`
-    Concatenate(Vec<Place>),
+    /// `foo{bar}baz`, formatting each place according to its specifier (if any)
+    Concatenate(Vec<(Place, Option<FormatSpec>)>),
 
     /// `a + b`
     Op(Place, Op, Place),
 
     /// `- 1`
-    Unary(Op, Place),
+    Unary(UnaryOp, Place),
 
     /// parse or other error
     Error,
@@ -481,6 +658,7 @@ impl DebugWithDb<InIrDb<'_, Bir>> for ExprData {
             ExprData::UnsignedIntegerLiteral(w) => write!(f, "{w}"),
             ExprData::SignedIntegerLiteral(w) => write!(f, "{w}"),
             ExprData::StringLiteral(w) => write!(f, "{:?}", w.as_str(db.db())),
+            ExprData::CharLiteral(c) => write!(f, "{c:?}"),
             ExprData::FloatLiteral(w) => write!(f, "{w}"),
             ExprData::IntoShared(e) => write!(f, "{:?}.share", e.debug(db)),
             ExprData::Share(p) => write!(f, "{:?}.share", p.debug(db)),
@@ -488,9 +666,28 @@ impl DebugWithDb<InIrDb<'_, Bir>> for ExprData {
             ExprData::Give(p) => write!(f, "{:?}.give", p.debug(db)),
             ExprData::Unit => write!(f, "()"),
             ExprData::Tuple(vars) => write_parenthesized_places(f, vars, db),
+            ExprData::List(vars) => {
+                write!(f, "[")?;
+                for (v, i) in vars.iter().zip(0..) {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", v.debug(db))?;
+                }
+                write!(f, "]")
+            }
             ExprData::Concatenate(vars) => {
-                write!(f, "Concatenate")?;
-                write_parenthesized_places(f, vars, db)
+                write!(f, "Concatenate(")?;
+                for ((v, spec), i) in vars.iter().zip(0..) {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", v.debug(db))?;
+                    if let Some(spec) = spec {
+                        write!(f, ":{spec}")?;
+                    }
+                }
+                write!(f, ")")
             }
             ExprData::Op(lhs, op, rhs) => {
                 write!(f, "{:?} {} {:?}", lhs.debug(db), op.str(), rhs.debug(db))
@@ -533,7 +730,13 @@ pub enum PlaceData {
     Function(Function),
     Class(Class),
     Intrinsic(Intrinsic),
+
+    /// A function provided by the host; see `InputFile::native_functions`.
+    NativeFunction(Word),
     Dot(Place, Word),
+
+    /// `list[index]`, where `index` is a place holding an integer.
+    Index(Place, Place),
 }
 
 impl DebugWithDb<InIrDb<'_, Bir>> for PlaceData {
@@ -543,7 +746,9 @@ impl DebugWithDb<InIrDb<'_, Bir>> for PlaceData {
             PlaceData::Function(func) => write!(f, "{:?}", func.debug(db.db())),
             PlaceData::Class(class) => write!(f, "{:?}", class.debug(db.db())),
             PlaceData::Intrinsic(intrinsic) => write!(f, "{intrinsic:?}"),
+            PlaceData::NativeFunction(name) => write!(f, "{}", name.as_str(db.db())),
             PlaceData::Dot(p, id) => write!(f, "{:?}.{}", p.debug(db), id.as_str(db.db())),
+            PlaceData::Index(p, i) => write!(f, "{:?}[{:?}]", p.debug(db), i.debug(db)),
         }
     }
 }
@@ -583,3 +788,142 @@ impl DebugWithDb<InIrDb<'_, Bir>> for Name {
 pub struct NameData {
     pub word: Word,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_flow_graph_if() {
+        let mut tables = Tables::default();
+        let condition = tables.add(LocalVariableData {
+            name: None,
+            atomic: Atomic::No,
+        });
+        let condition = tables.add(PlaceData::LocalVariable(condition));
+        let if_true = tables.add(ControlPointData::Terminator(TerminatorData::Return(
+            condition,
+        )));
+        let if_false = tables.add(ControlPointData::Terminator(TerminatorData::Return(
+            condition,
+        )));
+        let start = tables.add(ControlPointData::Terminator(TerminatorData::If(
+            condition, if_true, if_false,
+        )));
+        let bir_data = BirData::new(tables, 0, start);
+
+        assert_eq!(
+            bir_data.control_flow_graph(),
+            vec![
+                (if_true, vec![]),
+                (if_false, vec![]),
+                (start, vec![if_true, if_false]),
+            ]
+        );
+    }
+
+    #[test]
+    fn switch_successors() {
+        let mut tables = Tables::default();
+        let scrutinee = tables.add(LocalVariableData {
+            name: None,
+            atomic: Atomic::No,
+        });
+        let scrutinee = tables.add(PlaceData::LocalVariable(scrutinee));
+        let targets: Vec<ControlPoint> = (0..4)
+            .map(|_| {
+                tables.add(ControlPointData::Terminator(TerminatorData::Return(
+                    scrutinee,
+                )))
+            })
+            .collect();
+        let switch = TerminatorData::Switch(
+            scrutinee,
+            vec![(0, targets[0]), (1, targets[1]), (-1, targets[2])],
+            targets[3],
+        );
+
+        assert_eq!(switch.successors(), targets);
+    }
+
+    #[test]
+    fn unreachable_points_orphaned_statement() {
+        let mut tables = Tables::default();
+        let variable = tables.add(LocalVariableData {
+            name: None,
+            atomic: Atomic::No,
+        });
+        let place = tables.add(PlaceData::LocalVariable(variable));
+        let start = tables.add(ControlPointData::Terminator(TerminatorData::Return(place)));
+        let orphan = tables.add(ControlPointData::Statement(StatementData {
+            action: ActionData::Clear(variable),
+            next: start,
+        }));
+        let bir_data = BirData::new(tables, 0, start);
+
+        assert_eq!(bir_data.unreachable_points(), vec![orphan]);
+    }
+
+    #[test]
+    fn predecessors_if() {
+        let mut tables = Tables::default();
+        let condition = tables.add(LocalVariableData {
+            name: None,
+            atomic: Atomic::No,
+        });
+        let condition = tables.add(PlaceData::LocalVariable(condition));
+        let join = tables.add(ControlPointData::Terminator(TerminatorData::Return(
+            condition,
+        )));
+        let if_true = tables.add(ControlPointData::Terminator(TerminatorData::Goto(join)));
+        let if_false = tables.add(ControlPointData::Terminator(TerminatorData::Goto(join)));
+        let start = tables.add(ControlPointData::Terminator(TerminatorData::If(
+            condition, if_true, if_false,
+        )));
+        let bir_data = BirData::new(tables, 0, start);
+
+        let predecessors = bir_data.predecessors();
+        assert!(predecessors[&start].is_empty());
+        assert_eq!(predecessors[&if_true], vec![start]);
+        assert_eq!(predecessors[&if_false], vec![start]);
+        assert_eq!(predecessors[&join], vec![if_true, if_false]);
+
+        let graph = crate::graph::WithPredecessors::new(&bir_data);
+        assert_eq!(
+            crate::graph::Predecessors::predecessors(&graph, join),
+            vec![if_true, if_false]
+        );
+    }
+
+    #[test]
+    fn dominators_diamond() {
+        let mut tables = Tables::default();
+        let condition = tables.add(LocalVariableData {
+            name: None,
+            atomic: Atomic::No,
+        });
+        let condition = tables.add(PlaceData::LocalVariable(condition));
+        let join = tables.add(ControlPointData::Terminator(TerminatorData::Return(
+            condition,
+        )));
+        let if_true = tables.add(ControlPointData::Terminator(TerminatorData::Goto(join)));
+        let if_false = tables.add(ControlPointData::Terminator(TerminatorData::Goto(join)));
+        let start = tables.add(ControlPointData::Terminator(TerminatorData::If(
+            condition, if_true, if_false,
+        )));
+        let orphan = tables.add(ControlPointData::Terminator(TerminatorData::Goto(join)));
+        let bir_data = BirData::new(tables, 0, start);
+
+        let dominators = bir_data.dominators();
+        assert_eq!(dominators.idom(start), None);
+        assert_eq!(dominators.idom(if_true), Some(start));
+        assert_eq!(dominators.idom(if_false), Some(start));
+        assert_eq!(dominators.idom(join), Some(start));
+        assert_eq!(dominators.idom(orphan), None);
+
+        assert!(dominators.dominates(start, join));
+        assert!(dominators.dominates(join, join));
+        assert!(!dominators.dominates(if_true, join));
+        assert!(!dominators.dominates(start, orphan));
+    }
+}
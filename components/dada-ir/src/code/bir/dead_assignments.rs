@@ -0,0 +1,207 @@
+//! Dead-assignment elimination: removes assignments to local variables whose
+//! values are never read.
+
+use dada_collections::{Map, Set};
+
+use super::{
+    ActionData, BirData, ControlPoint, ControlPointData, Expr, ExprData, LocalVariable, Place,
+    PlaceData, TargetPlace, TargetPlaceData, TerminatorData, TerminatorExpr,
+};
+
+impl BirData {
+    /// Replaces with `Noop` each statement that assigns a local variable
+    /// which is not read again before being reassigned, cleared, or going
+    /// out of scope at a return.
+    ///
+    /// Only assignments of literals are removed: evaluating any other
+    /// expression can fail, or changes the permissions of the places it
+    /// reads (e.g., `x.give`), and calls are terminators, so they are always
+    /// preserved. Breakpoints count as reading every variable, since the
+    /// debugger shows them all in the heap-graph.
+    pub fn eliminate_dead_assignments(&mut self) {
+        // Literals read no variables, so removing one of these assignments
+        // never makes another one dead: a single pass suffices.
+        let live_out = self.live_variables();
+        let dead: Vec<ControlPoint> = live_out
+            .iter()
+            .filter(|&(&cp, live)| self.is_dead_assignment(cp, live))
+            .map(|(&cp, _)| cp)
+            .collect();
+        for cp in dead {
+            if let ControlPointData::Statement(statement) = &mut self.tables[cp] {
+                statement.action = ActionData::Noop;
+            }
+        }
+    }
+
+    fn is_dead_assignment(&self, cp: ControlPoint, live_out: &Set<LocalVariable>) -> bool {
+        let ControlPointData::Statement(statement) = &self.tables[cp] else {
+            return false;
+        };
+        let ActionData::AssignExpr(target, expr) = statement.action else {
+            return false;
+        };
+        let TargetPlaceData::LocalVariable(lv) = self.tables[target] else {
+            return false;
+        };
+        !live_out.contains(&lv)
+            && matches!(
+                self.tables[expr],
+                ExprData::BooleanLiteral(_)
+                    | ExprData::SignedIntegerLiteral(_)
+                    | ExprData::UnsignedIntegerLiteral(_)
+                    | ExprData::IntegerLiteral(_)
+                    | ExprData::FloatLiteral(_)
+                    | ExprData::StringLiteral(_)
+                    | ExprData::CharLiteral(_)
+                    | ExprData::Unit
+            )
+    }
+
+    /// Maps each reachable control point to the local variables that are
+    /// live just after it executes (i.e., may be read before they are next
+    /// assigned or cleared).
+    fn live_variables(&self) -> Map<ControlPoint, Set<LocalVariable>> {
+        let predecessors = self.predecessors();
+        let control_points = self.control_points();
+
+        let mut live_in: Map<ControlPoint, Set<LocalVariable>> = Map::default();
+        let mut worklist: Vec<ControlPoint> = control_points.iter().copied().collect();
+        while let Some(cp) = worklist.pop() {
+            let live = self.live_before(cp, self.live_after(cp, &live_in));
+            if live_in.get(&cp) != Some(&live) {
+                live_in.insert(cp, live);
+                worklist.extend(&predecessors[&cp]);
+            }
+        }
+
+        control_points
+            .into_iter()
+            .map(|cp| (cp, self.live_after(cp, &live_in)))
+            .collect()
+    }
+
+    fn live_after(
+        &self,
+        cp: ControlPoint,
+        live_in: &Map<ControlPoint, Set<LocalVariable>>,
+    ) -> Set<LocalVariable> {
+        cp.successors(self)
+            .into_iter()
+            .filter_map(|successor| live_in.get(&successor))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Given the variables live after `cp`, returns those live before it.
+    fn live_before(&self, cp: ControlPoint, mut live: Set<LocalVariable>) -> Set<LocalVariable> {
+        match &self.tables[cp] {
+            ControlPointData::Statement(statement) => match statement.action {
+                ActionData::Noop => {}
+                ActionData::AssignExpr(target, expr) => {
+                    self.kill_target(target, &mut live);
+                    self.expr_reads(expr, &mut live);
+                }
+                ActionData::Clear(lv) => {
+                    live.remove(&lv);
+                }
+                ActionData::BreakpointStart(..) | ActionData::BreakpointEnd(..) => {
+                    live.extend(self.max_local_variable().iter());
+                }
+            },
+            ControlPointData::Terminator(terminator) => match terminator {
+                TerminatorData::Goto(_)
+                | TerminatorData::StartAtomic(_)
+                | TerminatorData::EndAtomic(_)
+                | TerminatorData::Error
+                | TerminatorData::Panic(None) => {}
+                TerminatorData::If(place, ..)
+                | TerminatorData::Panic(Some(place))
+                | TerminatorData::Switch(place, ..)
+                | TerminatorData::Return(place) => self.place_reads(*place, &mut live),
+                TerminatorData::Assign(target, terminator_expr, _) => {
+                    self.kill_target(*target, &mut live);
+                    match terminator_expr {
+                        TerminatorExpr::Await(place) => self.place_reads(*place, &mut live),
+                        TerminatorExpr::AwaitAll(places) => {
+                            self.places_read(places, &mut live);
+                        }
+                        TerminatorExpr::Call {
+                            function,
+                            arguments,
+                            labels: _,
+                        } => {
+                            self.place_reads(*function, &mut live);
+                            self.places_read(arguments, &mut live);
+                        }
+                    }
+                }
+            },
+        }
+        live
+    }
+
+    /// Updates `live` for an assignment to `target`: assigning a variable
+    /// kills it, whereas assigning a field reads the owning place.
+    fn kill_target(&self, target: TargetPlace, live: &mut Set<LocalVariable>) {
+        match self.tables[target] {
+            TargetPlaceData::LocalVariable(lv) => {
+                live.remove(&lv);
+            }
+            TargetPlaceData::Dot(place, _) => self.place_reads(place, live),
+        }
+    }
+
+    fn expr_reads(&self, expr: Expr, live: &mut Set<LocalVariable>) {
+        match &self.tables[expr] {
+            ExprData::BooleanLiteral(_)
+            | ExprData::SignedIntegerLiteral(_)
+            | ExprData::UnsignedIntegerLiteral(_)
+            | ExprData::IntegerLiteral(_)
+            | ExprData::FloatLiteral(_)
+            | ExprData::StringLiteral(_)
+            | ExprData::CharLiteral(_)
+            | ExprData::Unit
+            | ExprData::Error => {}
+            ExprData::IntoShared(place)
+            | ExprData::Share(place)
+            | ExprData::Lease(place)
+            | ExprData::Give(place)
+            | ExprData::Unary(_, place) => self.place_reads(*place, live),
+            ExprData::Tuple(places) | ExprData::List(places) => self.places_read(places, live),
+            ExprData::Concatenate(places) => {
+                for &(place, _) in places {
+                    self.place_reads(place, live)
+                }
+            }
+            ExprData::Op(lhs, _, rhs) => {
+                self.place_reads(*lhs, live);
+                self.place_reads(*rhs, live);
+            }
+        }
+    }
+
+    fn places_read(&self, places: &[Place], live: &mut Set<LocalVariable>) {
+        for &place in places {
+            self.place_reads(place, live);
+        }
+    }
+
+    fn place_reads(&self, place: Place, live: &mut Set<LocalVariable>) {
+        match self.tables[place] {
+            PlaceData::LocalVariable(lv) => {
+                live.insert(lv);
+            }
+            PlaceData::Dot(owner, _) => self.place_reads(owner, live),
+            PlaceData::Index(list, index) => {
+                self.place_reads(list, live);
+                self.place_reads(index, live);
+            }
+            PlaceData::Function(_)
+            | PlaceData::Class(_)
+            | PlaceData::Intrinsic(_)
+            | PlaceData::NativeFunction(_) => {}
+        }
+    }
+}
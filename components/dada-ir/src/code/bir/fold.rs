@@ -0,0 +1,197 @@
+//! Constant folding: evaluates operators whose operands are known literals
+//! when the BIR is built, rather than each time it executes.
+
+use dada_collections::Map;
+
+use crate::code::validated::op::Op;
+
+use super::{
+    ActionData, BirData, ControlPoint, ControlPointData, ExprData, LocalVariable, Place, PlaceData,
+    TargetPlaceData, TerminatorData,
+};
+
+impl BirData {
+    /// Rewrites operator expressions like `temp{1} + temp{2}`, where both
+    /// operands are temporaries holding literals, into the literal they
+    /// evaluate to. The rewritten expressions keep their ids, so their
+    /// origins (and hence the spans used in diagnostics) are unchanged.
+    ///
+    /// Only temporaries introduced by the compiler that are assigned exactly
+    /// once, at a point dominating the use, are considered. Anything whose
+    /// result depends on the runtime (overflow, division by zero, operands of
+    /// different kinds) is left alone, so that it reports the same error it
+    /// always has.
+    pub fn fold_constants(&mut self) {
+        while self.fold_constants_once() {}
+    }
+
+    /// Folds every operator whose operands are currently known literals;
+    /// returns true if anything changed. Folding one operator may produce
+    /// a new known literal, so the caller iterates to a fixed point.
+    fn fold_constants_once(&mut self) -> bool {
+        let literals = self.literal_temporaries();
+        let dominators = self.dominators();
+
+        let mut folded = vec![];
+        for cp in self.control_points() {
+            let ControlPointData::Statement(statement) = &self.tables[cp] else {
+                continue;
+            };
+            let ActionData::AssignExpr(_, expr) = statement.action else {
+                continue;
+            };
+            let ExprData::Op(lhs, op, rhs) = self.tables[expr] else {
+                continue;
+            };
+            let operand = |place: Place| {
+                let PlaceData::LocalVariable(lv) = self.tables[place] else {
+                    return None;
+                };
+                let (defined_at, literal) = literals.get(&lv)?;
+                (*defined_at != cp && dominators.dominates(*defined_at, cp)).then_some(literal)
+            };
+            let (Some(lhs), Some(rhs)) = (operand(lhs), operand(rhs)) else {
+                continue;
+            };
+            if let Some(value) = fold_op(lhs, op, rhs) {
+                folded.push((expr, value));
+            }
+        }
+
+        let changed = !folded.is_empty();
+        for (expr, value) in folded {
+            self.tables[expr] = value;
+        }
+        changed
+    }
+
+    /// Maps each temporary that is assigned exactly once, from a literal,
+    /// to the control point that assigns it and the literal.
+    fn literal_temporaries(&self) -> Map<LocalVariable, (ControlPoint, ExprData)> {
+        let mut assignments: Map<LocalVariable, Vec<(ControlPoint, Option<ExprData>)>> =
+            Map::default();
+        for cp in self.control_points() {
+            let (target, expr) = match &self.tables[cp] {
+                ControlPointData::Statement(statement) => match statement.action {
+                    ActionData::AssignExpr(target, expr) => (target, Some(expr)),
+                    _ => continue,
+                },
+                ControlPointData::Terminator(TerminatorData::Assign(target, ..)) => (*target, None),
+                ControlPointData::Terminator(_) => continue,
+            };
+            let TargetPlaceData::LocalVariable(lv) = self.tables[target] else {
+                continue;
+            };
+            let literal = expr
+                .map(|expr| self.tables[expr].clone())
+                .filter(is_foldable_literal);
+            assignments.entry(lv).or_default().push((cp, literal));
+        }
+
+        assignments
+            .into_iter()
+            .filter(|&(lv, _)| self.tables[lv].name.is_none())
+            .filter(|&(lv, _)| usize::from(lv) >= self.num_parameters)
+            .filter_map(|(lv, mut assignments)| match assignments.pop() {
+                Some((cp, Some(literal))) if assignments.is_empty() => Some((lv, (cp, literal))),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn is_foldable_literal(expr: &ExprData) -> bool {
+    matches!(
+        expr,
+        ExprData::BooleanLiteral(_)
+            | ExprData::IntegerLiteral(_)
+            | ExprData::UnsignedIntegerLiteral(_)
+            | ExprData::SignedIntegerLiteral(_)
+    )
+}
+
+/// Evaluates `lhs op rhs` the way the interpreter would, returning `None`
+/// if it would report an error (or if it is an operator we don't fold).
+fn fold_op(lhs: &ExprData, op: Op, rhs: &ExprData) -> Option<ExprData> {
+    match (lhs, rhs) {
+        (&ExprData::BooleanLiteral(lhs), &ExprData::BooleanLiteral(rhs)) => match op {
+            Op::EqualEqual => Some(ExprData::BooleanLiteral(lhs == rhs)),
+            Op::NotEqual => Some(ExprData::BooleanLiteral(lhs != rhs)),
+            Op::GreaterEqual => Some(ExprData::BooleanLiteral(lhs >= rhs)),
+            Op::LessEqual => Some(ExprData::BooleanLiteral(lhs <= rhs)),
+            Op::BitAnd => Some(ExprData::BooleanLiteral(lhs & rhs)),
+            Op::BitOr => Some(ExprData::BooleanLiteral(lhs | rhs)),
+            Op::BitXor => Some(ExprData::BooleanLiteral(lhs ^ rhs)),
+            _ => None,
+        },
+        (&ExprData::IntegerLiteral(lhs), &ExprData::IntegerLiteral(rhs)) => {
+            fold_int(op, lhs, rhs, ExprData::IntegerLiteral)
+        }
+        (&ExprData::UnsignedIntegerLiteral(lhs), &ExprData::UnsignedIntegerLiteral(rhs))
+        | (&ExprData::UnsignedIntegerLiteral(lhs), &ExprData::IntegerLiteral(rhs))
+        | (&ExprData::IntegerLiteral(lhs), &ExprData::UnsignedIntegerLiteral(rhs)) => {
+            fold_int(op, lhs, rhs, ExprData::UnsignedIntegerLiteral)
+        }
+        (&ExprData::SignedIntegerLiteral(lhs), &ExprData::SignedIntegerLiteral(rhs)) => {
+            fold_int(op, lhs, rhs, ExprData::SignedIntegerLiteral)
+        }
+        _ => None,
+    }
+}
+
+/// Folds an arithmetic or comparison operator on two integers of the same
+/// type, using `literal` to build the result of arithmetic. Overflow and
+/// division by zero are not folded.
+fn fold_int<I>(op: Op, lhs: I, rhs: I, literal: fn(I) -> ExprData) -> Option<ExprData>
+where
+    I: CheckedArithmetic,
+{
+    match op {
+        Op::EqualEqual => Some(ExprData::BooleanLiteral(lhs == rhs)),
+        Op::NotEqual => Some(ExprData::BooleanLiteral(lhs != rhs)),
+        Op::GreaterEqual => Some(ExprData::BooleanLiteral(lhs >= rhs)),
+        Op::LessEqual => Some(ExprData::BooleanLiteral(lhs <= rhs)),
+        Op::LessThan => Some(ExprData::BooleanLiteral(lhs < rhs)),
+        Op::GreaterThan => Some(ExprData::BooleanLiteral(lhs > rhs)),
+        Op::Plus => lhs.checked_add(rhs).map(literal),
+        Op::Minus => lhs.checked_sub(rhs).map(literal),
+        Op::Times => lhs.checked_mul(rhs).map(literal),
+        Op::DividedBy => lhs.checked_div(rhs).map(literal),
+        Op::Modulo => lhs.checked_rem(rhs).map(literal),
+        _ => None,
+    }
+}
+
+trait CheckedArithmetic: Copy + Ord + Sized {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! checked_arithmetic {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedArithmetic for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_div(self, rhs)
+                }
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_rem(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+checked_arithmetic!(u64, i64);
@@ -0,0 +1,168 @@
+//! Renders BIR as readable, source-like pseudocode.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use dada_collections::Map;
+use salsa::DebugWithDb;
+
+use crate::{in_ir_db::InIrDb, prelude::InIrDbExt};
+
+use super::{
+    ActionData, Bir, BirData, ControlPoint, ControlPointData, TerminatorData, TerminatorExpr,
+};
+
+impl Bir {
+    /// Renders this BIR as pseudocode, for those learning how Dada code is
+    /// lowered. The control points are grouped into basic blocks (`block0:`,
+    /// `block1:`, ...), each of which ends in a jump such as `goto block1`.
+    /// Assignments are rendered like `x{0} := y{1}.give`, where the number
+    /// in braces distinguishes variables with the same name (temporaries are
+    /// called `temp`).
+    ///
+    /// Only control points reachable from the start point are included.
+    pub fn to_pseudocode(self, db: &dyn crate::Db) -> String {
+        let in_ir_db = &self.in_ir_db(db.as_dyn_ir_db());
+        let bir_data = self.data(db);
+        let blocks = basic_blocks(bir_data);
+
+        let mut output = String::new();
+        for (&leader, &index) in &blocks {
+            writeln!(output, "block{index}:").unwrap();
+            let mut cp = leader;
+            loop {
+                match cp.data(&bir_data.tables) {
+                    ControlPointData::Statement(statement) => {
+                        if let Some(line) = action_line(&statement.action, in_ir_db) {
+                            writeln!(output, "    {line}").unwrap();
+                        }
+                        if let Some(next_index) = blocks.get(&statement.next) {
+                            writeln!(output, "    goto block{next_index}").unwrap();
+                            break;
+                        }
+                        cp = statement.next;
+                    }
+                    ControlPointData::Terminator(terminator) => {
+                        let line = terminator_line(terminator, &blocks, in_ir_db);
+                        writeln!(output, "    {line}").unwrap();
+                        break;
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Returns the first control point of each basic block (in order of
+/// allocation, which is roughly source order) mapped to the block's index.
+/// A block begins at the start point, at the target of a terminator, and at
+/// any point with more than one predecessor.
+fn basic_blocks(bir_data: &BirData) -> BTreeMap<ControlPoint, usize> {
+    let control_points = bir_data.control_points();
+
+    let mut num_predecessors: Map<ControlPoint, usize> = Map::default();
+    let mut leaders = vec![bir_data.start_point];
+    for &cp in &control_points {
+        let successors = cp.successors(bir_data);
+        if let ControlPointData::Terminator(_) = cp.data(&bir_data.tables) {
+            leaders.extend(&successors);
+        }
+        for successor in successors {
+            *num_predecessors.entry(successor).or_default() += 1;
+        }
+    }
+    leaders.extend(
+        control_points
+            .iter()
+            .filter(|cp| num_predecessors.get(cp).copied().unwrap_or(0) > 1),
+    );
+
+    leaders.sort();
+    leaders.dedup();
+    leaders
+        .into_iter()
+        .enumerate()
+        .map(|(index, cp)| (cp, index))
+        .collect()
+}
+
+fn action_line(action: &ActionData, db: &InIrDb<'_, Bir>) -> Option<String> {
+    match action {
+        ActionData::Noop => None,
+        ActionData::AssignExpr(target, expr) => {
+            Some(format!("{:?} := {:?}", target.debug(db), expr.debug(db)))
+        }
+        ActionData::Clear(lv) => Some(format!("clear {:?}", lv.debug(db))),
+        ActionData::BreakpointStart(_, index) => Some(format!("breakpoint_start({index})")),
+        ActionData::BreakpointEnd(_, index, _, _) => Some(format!("breakpoint_end({index})")),
+    }
+}
+
+fn terminator_line(
+    terminator: &TerminatorData,
+    blocks: &BTreeMap<ControlPoint, usize>,
+    db: &InIrDb<'_, Bir>,
+) -> String {
+    let block = |cp: &ControlPoint| format!("block{}", blocks[cp]);
+    match terminator {
+        TerminatorData::Goto(target) => format!("goto {}", block(target)),
+        TerminatorData::If(condition, if_true, if_false) => format!(
+            "if {:?} goto {} else {}",
+            condition.debug(db),
+            block(if_true),
+            block(if_false)
+        ),
+        TerminatorData::Switch(scrutinee, arms, default) => {
+            let mut line = format!("switch {:?} {{ ", scrutinee.debug(db));
+            for (value, target) in arms {
+                write!(line, "{value} => {}, ", block(target)).unwrap();
+            }
+            write!(line, "_ => {} }}", block(default)).unwrap();
+            line
+        }
+        TerminatorData::StartAtomic(next) => format!("start_atomic; goto {}", block(next)),
+        TerminatorData::EndAtomic(next) => format!("end_atomic; goto {}", block(next)),
+        TerminatorData::Return(value) => format!("return {:?}", value.debug(db)),
+        TerminatorData::Assign(target, expr, next) => format!(
+            "{:?} := {}; goto {}",
+            target.debug(db),
+            terminator_expr(expr, db),
+            block(next)
+        ),
+        TerminatorData::Error => "error".to_string(),
+        TerminatorData::Panic(None) => "panic".to_string(),
+        TerminatorData::Panic(Some(message)) => format!("panic({:?})", message.debug(db)),
+    }
+}
+
+fn terminator_expr(expr: &TerminatorExpr, db: &InIrDb<'_, Bir>) -> String {
+    match expr {
+        TerminatorExpr::Await(thunk) => format!("{:?}.await", thunk.debug(db)),
+        TerminatorExpr::AwaitAll(thunks) => {
+            let thunks: Vec<String> = thunks
+                .iter()
+                .map(|thunk| format!("{:?}", thunk.debug(db)))
+                .collect();
+            format!("await all [{}]", thunks.join(", "))
+        }
+        TerminatorExpr::Call {
+            function,
+            arguments,
+            labels,
+        } => {
+            let arguments: Vec<String> = arguments
+                .iter()
+                .zip(labels)
+                .map(|(argument, label)| match label {
+                    Some(label) => format!(
+                        "{}: {:?}",
+                        label.data(db.tables()).word.as_str(db.db()),
+                        argument.debug(db)
+                    ),
+                    None => format!("{:?}", argument.debug(db)),
+                })
+                .collect();
+            format!("{:?}({})", function.debug(db), arguments.join(", "))
+        }
+    }
+}
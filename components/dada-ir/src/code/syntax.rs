@@ -1,4 +1,7 @@
-use crate::{code::syntax::op::Op, in_ir_db::InIrDb, in_ir_db::InIrDbExt, span::Span, word::Word};
+use crate::{
+    code::syntax::op::Op, format_string::FormatSpec, in_ir_db::InIrDb, in_ir_db::InIrDbExt,
+    span::Span, word::Word,
+};
 use dada_id::{id, prelude::*, tables};
 use derive_new::new;
 use salsa::DebugWithDb;
@@ -161,9 +164,13 @@ pub enum ExprData {
     /// that has parsed expressions.
     StringLiteral(Word),
 
+    /// `'a'`; the word is the text between the quotes (escapes are not yet processed)
+    CharLiteral(Word),
+
     /// Generated by a format string like `"foo{x}bar"`, which would
-    /// yield `Concatenate(StringLiteral("foo"), x, StringLiteral("bar"))`
-    Concatenate(Vec<Expr>),
+    /// yield `Concatenate(StringLiteral("foo"), x, StringLiteral("bar"))`.
+    /// Each expression carries its format specifier (e.g., `{x:02}`), if any.
+    Concatenate(Vec<(Expr, Option<FormatSpec>)>),
 
     /// `expr.ident`
     Dot(Expr, Word),
@@ -171,9 +178,15 @@ pub enum ExprData {
     /// `expr.await`
     Await(Expr),
 
+    /// `await all [expr, ...]`
+    AwaitAll(Vec<Expr>),
+
     /// `expr(id: expr, ...)`
     Call(Expr, Vec<NamedExpr>),
 
+    /// `expr[expr]`
+    Index(Expr, Expr),
+
     /// `expr.share`
     Share(Expr),
 
@@ -192,6 +205,9 @@ pub enum ExprData {
     /// `()` or `(a, b, ...)` (i.e., expr seq cannot have length 1)
     Tuple(Vec<Expr>),
 
+    /// `[a, b, ...]`
+    List(Vec<Expr>),
+
     /// `if condition { block } [else { block }]`
     If(Expr, Expr, Option<Expr>),
 
@@ -245,21 +261,34 @@ impl DebugWithDb<InIrDb<'_, Tree>> for ExprData {
                 .field(&d.debug(db.db()))
                 .finish(),
             ExprData::StringLiteral(v) => f.debug_tuple("String").field(&v.debug(db.db())).finish(),
-            ExprData::Concatenate(exprs) => f
-                .debug_tuple("Concatenate")
-                .field(&exprs.debug(db))
-                .finish(),
+            ExprData::CharLiteral(v) => f.debug_tuple("Char").field(&v.debug(db.db())).finish(),
+            ExprData::Concatenate(exprs) => {
+                let mut f = f.debug_tuple("Concatenate");
+                for (expr, spec) in exprs {
+                    f.field(&expr.debug(db));
+                    if let Some(spec) = spec {
+                        f.field(&format_args!(":{spec}"));
+                    }
+                }
+                f.finish()
+            }
             ExprData::Dot(lhs, rhs) => f
                 .debug_tuple("Dot")
                 .field(&lhs.debug(db))
                 .field(&rhs.debug(db.db()))
                 .finish(),
             ExprData::Await(e) => f.debug_tuple("Await").field(&e.debug(db)).finish(),
+            ExprData::AwaitAll(e) => f.debug_tuple("AwaitAll").field(&e.debug(db)).finish(),
             ExprData::Call(func, args) => f
                 .debug_tuple("Call")
                 .field(&func.debug(db))
                 .field(&args.debug(db))
                 .finish(),
+            ExprData::Index(list, index) => f
+                .debug_tuple("Index")
+                .field(&list.debug(db))
+                .field(&index.debug(db))
+                .finish(),
             ExprData::Share(e) => f.debug_tuple("Share").field(&e.debug(db)).finish(),
             ExprData::Lease(e) => f.debug_tuple("Lease").field(&e.debug(db)).finish(),
             ExprData::Give(e) => f.debug_tuple("Give").field(&e.debug(db)).finish(),
@@ -272,6 +301,7 @@ impl DebugWithDb<InIrDb<'_, Tree>> for ExprData {
                 f.debug_tuple("Parenthesized").field(&e.debug(db)).finish()
             }
             ExprData::Tuple(e) => f.debug_tuple("Tuple").field(&e.debug(db)).finish(),
+            ExprData::List(e) => f.debug_tuple("List").field(&e.debug(db)).finish(),
             ExprData::If(c, t, e) => f
                 .debug_tuple("If")
                 .field(&c.debug(db))
@@ -329,6 +359,10 @@ pub struct LocalVariableDeclData {
     pub atomic: Option<AtomicKeyword>,
     pub name: Name,
     pub ty: Option<Ty>,
+
+    /// Default value given in a parameter list (e.g., `class Point(x, y = 0)`).
+    /// Always `None` for `let` declarations.
+    pub default: Option<Expr>,
 }
 
 impl DebugWithDb<InIrDb<'_, Tree>> for LocalVariableDeclData {
@@ -337,6 +371,7 @@ impl DebugWithDb<InIrDb<'_, Tree>> for LocalVariableDeclData {
             .field("atomic", &self.atomic)
             .field("name", &self.name.debug(db))
             .field("ty", &self.ty.debug(db))
+            .field("default", &self.default.debug(db))
             .finish()
     }
 }
@@ -42,8 +42,13 @@ define_operators! {
     TimesEqual => "*=",
     DividedByEqual => "/=",
     EqualEqual => "==",
+    NotEqual => "!=",
     GreaterEqual => ">=",
     LessEqual => "<=",
+    ShiftLeft => "<<",
+    ShiftRight => ">>",
+    AndAnd => "&&",
+    OrOr => "||",
     RightArrow => "->",
 
     // 1-character ops
@@ -51,6 +56,10 @@ define_operators! {
     Minus => "-",
     Times => "*",
     DividedBy => "/",
+    Modulo => "%",
+    BitAnd => "&",
+    BitOr => "|",
+    BitXor => "^",
     Colon => ":",
     SemiColon => ";",
     Equal => "=",
@@ -59,6 +68,7 @@ define_operators! {
     LeftAngle => "<",
     RightAngle => ">",
     Dot => ".",
+    Not => "!",
 }
 
 impl Op {
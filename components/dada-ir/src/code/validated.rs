@@ -3,8 +3,15 @@
 //! desugared and easy to work with.
 
 use crate::{
-    class::Class, code::validated::op::Op, function::Function, in_ir_db::InIrDb,
-    intrinsic::Intrinsic, prelude::InIrDbExt, storage::Atomic, word::Word,
+    class::Class,
+    code::validated::op::{Op, UnaryOp},
+    format_string::FormatSpec,
+    function::Function,
+    in_ir_db::InIrDb,
+    intrinsic::Intrinsic,
+    prelude::InIrDbExt,
+    storage::Atomic,
+    word::Word,
 };
 use dada_id::{id, prelude::*, tables};
 use salsa::DebugWithDb;
@@ -243,12 +250,20 @@ pub enum ExprData {
     /// `"foo"` with no format strings
     StringLiteral(Word),
 
-    /// Concatenates a bunch of strings from a string literal like `"foo{bar}baz"`
-    Concatenate(Vec<Expr>),
+    /// `'a'`
+    CharLiteral(char),
+
+    /// Concatenates a bunch of strings from a string literal like `"foo{bar}baz"`,
+    /// formatting each according to its specifier (if any)
+    Concatenate(Vec<(Expr, Option<FormatSpec>)>),
 
     /// `expr.await`
     Await(Expr),
 
+    /// `await all [expr, ...]`: awaits each of the thunks in turn,
+    /// producing a tuple of their results.
+    AwaitAll(Vec<Expr>),
+
     /// `expr(id: expr, ...)`
     Call(Expr, Vec<NamedExpr>),
 
@@ -267,6 +282,9 @@ pub enum ExprData {
     /// `()` or `(a, b, ...)` (i.e., expr seq cannot have length 1)
     Tuple(Vec<Expr>),
 
+    /// `[a, b, ...]`
+    List(Vec<Expr>),
+
     /// `if condition { block } [else { block }]`
     If(Expr, Expr, Expr),
 
@@ -297,7 +315,7 @@ pub enum ExprData {
     Op(Expr, Op, Expr),
 
     /// `<op> x`
-    Unary(Op, Expr),
+    Unary(UnaryOp, Expr),
 
     /// `a = b` or `a := b`
     Assign(TargetPlace, Expr),
@@ -330,7 +348,15 @@ impl ExprData {
             ExprData::SignedIntegerLiteral(v) => write!(f, "{v}"),
             ExprData::FloatLiteral(v) => write!(f, "{v}"),
             ExprData::StringLiteral(v) => std::fmt::Debug::fmt(&v.as_str(db.db()), f),
+            ExprData::CharLiteral(v) => std::fmt::Debug::fmt(v, f),
             ExprData::Await(expr) => f.debug_tuple("Await").field(&expr.debug(db)).finish(),
+            ExprData::AwaitAll(exprs) => {
+                let mut f = f.debug_tuple("AwaitAll");
+                for expr in exprs {
+                    f.field(&expr.debug(db));
+                }
+                f.finish()
+            }
             ExprData::Call(expr, args) => f
                 .debug_tuple("Call")
                 .field(&expr.debug(db))
@@ -347,10 +373,20 @@ impl ExprData {
                 }
                 f.finish()
             }
+            ExprData::List(exprs) => {
+                let mut f = f.debug_tuple("List");
+                for expr in exprs {
+                    f.field(&expr.debug(db));
+                }
+                f.finish()
+            }
             ExprData::Concatenate(exprs) => {
                 let mut f = f.debug_tuple("Concatenate");
-                for expr in exprs {
+                for (expr, spec) in exprs {
                     f.field(&expr.debug(db));
+                    if let Some(spec) = spec {
+                        f.field(&format_args!(":{spec}"));
+                    }
                 }
                 f.finish()
             }
@@ -423,8 +459,14 @@ pub enum PlaceData {
     LocalVariable(LocalVariable),
     Function(Function),
     Intrinsic(Intrinsic),
+
+    /// A function provided by the host; see `InputFile::native_functions`.
+    NativeFunction(Word),
     Class(Class),
     Dot(Place, Word),
+
+    /// `list[index]`, where `index` is a place holding an integer.
+    Index(Place, Place),
 }
 
 impl DebugWithDb<InIrDb<'_, Tree>> for PlaceData {
@@ -433,12 +475,21 @@ impl DebugWithDb<InIrDb<'_, Tree>> for PlaceData {
             PlaceData::LocalVariable(lv) => DebugWithDb::fmt(lv, f, db),
             PlaceData::Function(function) => DebugWithDb::fmt(function, f, db.db()),
             PlaceData::Intrinsic(intrinsic) => std::fmt::Debug::fmt(intrinsic, f),
+            PlaceData::NativeFunction(name) => f
+                .debug_tuple("NativeFunction")
+                .field(&name.debug(db.db()))
+                .finish(),
             PlaceData::Class(class) => DebugWithDb::fmt(class, f, db.db()),
             PlaceData::Dot(place, field) => f
                 .debug_tuple("Dot")
                 .field(&place.debug(db))
                 .field(&field.debug(db.db()))
                 .finish(),
+            PlaceData::Index(place, index) => f
+                .debug_tuple("Index")
+                .field(&place.debug(db))
+                .field(&index.debug(db))
+                .finish(),
         }
     }
 }
@@ -37,12 +37,41 @@ impl std::fmt::Display for Op {
 
 define_operators! {
     EqualEqual => "==",
+    NotEqual => "!=",
     GreaterEqual => ">=",
     LessEqual => "<=",
     Plus => "+",
     Minus => "-",
     Times => "*",
     DividedBy => "/",
+    Modulo => "%",
+    BitAnd => "&",
+    BitOr => "|",
+    BitXor => "^",
+    ShiftLeft => "<<",
+    ShiftRight => ">>",
     LessThan => "<",
     GreaterThan => ">",
 }
+
+/// Operators that take a single operand, like `-x` or `!x`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum UnaryOp {
+    Minus,
+    Not,
+}
+
+impl UnaryOp {
+    pub fn str(self) -> &'static str {
+        match self {
+            UnaryOp::Minus => "-",
+            UnaryOp::Not => "!",
+        }
+    }
+}
+
+impl std::fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.str())
+    }
+}
@@ -16,6 +16,10 @@ pub struct Diagnostic {
     pub severity: Severity,
     pub span: FileSpan,
     pub message: String,
+
+    /// A stable code (like `E0001`) that identifies this kind of diagnostic,
+    /// so that users can look up an explanation.
+    pub code: Option<&'static str>,
     pub labels: Vec<Label>,
     pub children: Vec<Diagnostic>,
 }
@@ -35,9 +39,50 @@ pub struct Label {
     pub message: String,
 }
 
+impl Severity {
+    /// Errors are fatal; everything else (warnings, notes, help) is informational
+    /// and does not prevent the program from running.
+    pub fn is_fatal(self) -> bool {
+        self == Severity::Error
+    }
+}
+
 #[salsa::accumulator]
 pub struct Diagnostics(Diagnostic);
 
+/// A set of diagnostics, split into the fatal ones (errors) and the rest.
+#[derive(Clone, Default, Debug)]
+pub struct DiagnosticsBySeverity {
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl DiagnosticsBySeverity {
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+impl Extend<Diagnostic> for DiagnosticsBySeverity {
+    fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, diagnostics: T) {
+        for diagnostic in diagnostics {
+            if diagnostic.severity.is_fatal() {
+                self.errors.push(diagnostic);
+            } else {
+                self.warnings.push(diagnostic);
+            }
+        }
+    }
+}
+
+impl FromIterator<Diagnostic> for DiagnosticsBySeverity {
+    fn from_iter<T: IntoIterator<Item = Diagnostic>>(diagnostics: T) -> Self {
+        let mut result = Self::default();
+        result.extend(diagnostics);
+        result
+    }
+}
+
 /// Convenience macro for avoiding `format!`
 #[macro_export]
 macro_rules! diag {
@@ -110,6 +155,7 @@ pub struct DiagnosticBuilder {
     severity: Severity,
     span: FileSpan,
     message: String,
+    code: Option<&'static str>,
 
     /// All labels added by user so far (primary or secondary).
     labels: Vec<Label>,
@@ -122,11 +168,16 @@ pub struct DiagnosticBuilder {
 }
 
 impl DiagnosticBuilder {
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
     fn new(severity: Severity, span: FileSpan, message: impl ToString) -> Self {
         Self {
             severity,
             span,
             message: message.to_string(),
+            code: None,
             labels: vec![],
             children: vec![],
             add_primary_label: true,
@@ -145,6 +196,13 @@ impl DiagnosticBuilder {
         self.skip_primary_label()
     }
 
+    /// Sets the stable code (like `E0001`) for this diagnostic.
+    #[must_use = "you have not emitted the diagnostic"]
+    pub fn code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
     /// Avoids adding any primary label at all.
     #[must_use = "you have not emitted the diagnostic"]
     pub fn skip_primary_label(mut self) -> Self {
@@ -189,6 +247,7 @@ impl DiagnosticBuilder {
             severity: self.severity,
             span: self.span,
             message: self.message,
+            code: self.code,
             labels: self.labels,
             children: self.children,
         }
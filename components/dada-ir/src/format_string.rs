@@ -37,8 +37,9 @@ pub enum FormatStringSectionData {
     /// Plain text to be emitted directly.
     Text(Word),
 
-    /// A token tree for an expression.
-    TokenTree(TokenTree),
+    /// A token tree for an expression, along with its format specifier
+    /// (e.g., the `:02` in `"{x:02}"`), if any.
+    TokenTree(TokenTree, Option<FormatSpec>),
 }
 
 impl FormatStringSection {
@@ -46,7 +47,7 @@ impl FormatStringSection {
     pub fn len(&self, db: &dyn crate::Db) -> u32 {
         match self.data(db) {
             FormatStringSectionData::Text(w) => w.len(db),
-            FormatStringSectionData::TokenTree(tree) => tree.len(db),
+            FormatStringSectionData::TokenTree(tree, _) => tree.len(db),
         }
     }
 }
@@ -63,9 +64,200 @@ impl salsa::DebugWithDb<dyn crate::Db + '_> for FormatStringSectionData {
             FormatStringSectionData::Text(word) => {
                 f.debug_tuple("Text").field(&word.debug(db)).finish()
             }
-            FormatStringSectionData::TokenTree(tree) => {
-                f.debug_tuple("TokenTree").field(&tree.debug(db)).finish()
+            FormatStringSectionData::TokenTree(tree, spec) => f
+                .debug_tuple("TokenTree")
+                .field(&tree.debug(db))
+                .field(spec)
+                .finish(),
+        }
+    }
+}
+
+/// Controls how an interpolated value is rendered, like the `08.2` in
+/// `"{x:08.2}"`. The syntax is a subset of Rust's:
+/// `[[fill]align][0][width][.precision]`, where `align` is one of `<`, `^`, or `>`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatSpec {
+    /// Character used to pad the value out to `width`.
+    pub fill: char,
+
+    /// Where the value goes if it is narrower than `width`. If `None`,
+    /// numbers are aligned to the right and everything else to the left.
+    pub align: Option<FormatAlign>,
+
+    /// Pad numbers with zeros after their sign (the `0` flag).
+    pub zero_pad: bool,
+
+    /// Minimum number of characters to produce.
+    pub width: Option<usize>,
+
+    /// Number of digits to show after the decimal point of a float.
+    pub precision: Option<usize>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FormatAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl FormatAlign {
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '<' => Some(FormatAlign::Left),
+            '^' => Some(FormatAlign::Center),
+            '>' => Some(FormatAlign::Right),
+            _ => None,
+        }
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            FormatAlign::Left => '<',
+            FormatAlign::Center => '^',
+            FormatAlign::Right => '>',
+        }
+    }
+}
+
+impl FormatSpec {
+    /// Parses the text following the `:`, returning `None` if it is malformed.
+    pub fn parse(text: &str) -> Option<FormatSpec> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut rest = &chars[..];
+
+        let (fill, align) = match rest {
+            [fill, align, ..] if FormatAlign::from_char(*align).is_some() => {
+                rest = &rest[2..];
+                (*fill, FormatAlign::from_char(*align))
             }
+            [align, ..] if FormatAlign::from_char(*align).is_some() => {
+                rest = &rest[1..];
+                (' ', FormatAlign::from_char(*align))
+            }
+            _ => (' ', None),
+        };
+
+        let zero_pad = rest.first() == Some(&'0');
+        if zero_pad {
+            rest = &rest[1..];
         }
+
+        let width = parse_digits(&mut rest);
+
+        let precision = match rest {
+            ['.', tail @ ..] => {
+                rest = tail;
+                Some(parse_digits(&mut rest)?)
+            }
+            _ => None,
+        };
+
+        if !rest.is_empty() {
+            return None;
+        }
+
+        Some(FormatSpec {
+            fill,
+            align,
+            zero_pad,
+            width,
+            precision,
+        })
+    }
+
+    /// Pads `text` (the rendered value, which is a number if `is_number`)
+    /// out to the width of this spec.
+    pub fn pad(&self, text: &str, is_number: bool) -> String {
+        let len = text.chars().count();
+        let padding = match self.width {
+            Some(width) if width > len => width - len,
+            _ => return text.to_string(),
+        };
+
+        if self.zero_pad && is_number {
+            let (sign, digits) = match text.strip_prefix('-') {
+                Some(digits) => ("-", digits),
+                None => ("", text),
+            };
+            return format!("{sign}{}{digits}", "0".repeat(padding));
+        }
+
+        let default_align = if is_number {
+            FormatAlign::Right
+        } else {
+            FormatAlign::Left
+        };
+        let (before, after) = match self.align.unwrap_or(default_align) {
+            FormatAlign::Left => (0, padding),
+            FormatAlign::Center => (padding / 2, padding - padding / 2),
+            FormatAlign::Right => (padding, 0),
+        };
+        let fill = |n: usize| self.fill.to_string().repeat(n);
+        format!("{}{text}{}", fill(before), fill(after))
+    }
+}
+
+impl std::fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(align) = self.align {
+            write!(f, "{}{}", self.fill, align.as_char())?;
+        }
+        if self.zero_pad {
+            write!(f, "0")?;
+        }
+        if let Some(width) = self.width {
+            write!(f, "{width}")?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{precision}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Consumes the decimal digits at the start of `rest`, if any.
+fn parse_digits(rest: &mut &[char]) -> Option<usize> {
+    let len = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+    let (digits, tail) = rest.split_at(len);
+    *rest = tail;
+    digits.iter().collect::<String>().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatAlign, FormatSpec};
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        for text in ["", "02", ".2", "08.3", " >5", "*^7", "-<3.1"] {
+            let spec = FormatSpec::parse(text).unwrap();
+            assert_eq!(spec.to_string(), text);
+        }
+        let spec = FormatSpec::parse("*^7").unwrap();
+        assert_eq!(spec.fill, '*');
+        assert_eq!(spec.align, Some(FormatAlign::Center));
+        assert_eq!(spec.width, Some(7));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_specs() {
+        for text in ["x", "2x", ".", "5.", "<<<"] {
+            assert_eq!(FormatSpec::parse(text), None, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn pad() {
+        let pad = |spec: &str, text: &str, is_number: bool| {
+            FormatSpec::parse(spec).unwrap().pad(text, is_number)
+        };
+        assert_eq!(pad("03", "7", true), "007");
+        assert_eq!(pad("05", "-7", true), "-0007");
+        assert_eq!(pad("4", "7", true), "   7");
+        assert_eq!(pad("4", "ab", false), "ab  ");
+        assert_eq!(pad("*^6", "ab", false), "**ab**");
+        assert_eq!(pad("2", "abc", false), "abc");
     }
 }
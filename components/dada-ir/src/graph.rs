@@ -0,0 +1,204 @@
+//! Small traits describing directed graphs (like the control-flow graph of
+//! the BIR), so that graph algorithms can be written once, generically.
+
+use std::{collections::BTreeSet, hash::Hash};
+
+use dada_collections::Map;
+
+/// A directed graph with a distinguished start node.
+pub trait DirectedGraph {
+    type Node: Copy + Eq + Ord + Hash;
+
+    /// The node where traversals of the graph begin.
+    fn start_node(&self) -> Self::Node;
+}
+
+/// A graph that can enumerate the edges leaving a node.
+pub trait Successors: DirectedGraph {
+    fn successors(&self, node: Self::Node) -> Vec<Self::Node>;
+}
+
+/// A graph that can enumerate the edges entering a node.
+pub trait Predecessors: DirectedGraph {
+    fn predecessors(&self, node: Self::Node) -> Vec<Self::Node>;
+}
+
+/// Returns the nodes reachable from the start node of `graph`.
+pub fn reachable_nodes<G: Successors>(graph: &G) -> BTreeSet<G::Node> {
+    let mut nodes = BTreeSet::new();
+    let mut stack = vec![graph.start_node()];
+    while let Some(node) = stack.pop() {
+        if nodes.insert(node) {
+            stack.extend(graph.successors(node));
+        }
+    }
+    nodes
+}
+
+/// Maps each node reachable from the start node of `graph` to its
+/// predecessors (in ascending order). Unreachable predecessors are omitted.
+pub fn predecessor_map<G: Successors>(graph: &G) -> Map<G::Node, Vec<G::Node>> {
+    let nodes = reachable_nodes(graph);
+    let mut map: Map<G::Node, Vec<G::Node>> = nodes.iter().map(|&n| (n, vec![])).collect();
+    for &node in &nodes {
+        for successor in graph.successors(node) {
+            let predecessors = map.get_mut(&successor).unwrap();
+            if !predecessors.contains(&node) {
+                predecessors.push(node);
+            }
+        }
+    }
+    map
+}
+
+/// Wraps a graph together with its (precomputed) predecessor map, so that
+/// it implements [`Predecessors`] as well as [`Successors`].
+pub struct WithPredecessors<'g, G: Successors> {
+    graph: &'g G,
+    predecessors: Map<G::Node, Vec<G::Node>>,
+}
+
+impl<'g, G: Successors> WithPredecessors<'g, G> {
+    pub fn new(graph: &'g G) -> Self {
+        Self {
+            graph,
+            predecessors: predecessor_map(graph),
+        }
+    }
+}
+
+impl<G: Successors> DirectedGraph for WithPredecessors<'_, G> {
+    type Node = G::Node;
+
+    fn start_node(&self) -> Self::Node {
+        self.graph.start_node()
+    }
+}
+
+impl<G: Successors> Successors for WithPredecessors<'_, G> {
+    fn successors(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.graph.successors(node)
+    }
+}
+
+impl<G: Successors> Predecessors for WithPredecessors<'_, G> {
+    fn predecessors(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.predecessors.get(&node).cloned().unwrap_or_default()
+    }
+}
+
+/// The dominator tree of a graph, as computed by [`dominators`].
+///
+/// Node `a` dominates node `b` if every path from the start node to `b`
+/// passes through `a`. The immediate dominator of `b` is the dominator of
+/// `b` (other than `b` itself) that is dominated by all of `b`'s other
+/// dominators.
+#[derive(Clone, Debug)]
+pub struct Dominators<N> {
+    start_node: N,
+    immediate_dominators: Map<N, N>,
+}
+
+impl<N: Copy + Eq + Hash> Dominators<N> {
+    /// Returns the immediate dominator of `node`, or `None` if `node` is the
+    /// start node or is not reachable.
+    pub fn idom(&self, node: N) -> Option<N> {
+        if node == self.start_node {
+            return None;
+        }
+        self.immediate_dominators.get(&node).copied()
+    }
+
+    /// True if `a` dominates `b` (every node dominates itself).
+    /// Unreachable nodes are dominated by nothing.
+    pub fn dominates(&self, a: N, b: N) -> bool {
+        if !self.is_reachable(b) {
+            return false;
+        }
+        let mut node = b;
+        loop {
+            if node == a {
+                return true;
+            }
+            match self.idom(node) {
+                Some(idom) => node = idom,
+                None => return false,
+            }
+        }
+    }
+
+    /// True if `node` is reachable from the start node.
+    pub fn is_reachable(&self, node: N) -> bool {
+        node == self.start_node || self.immediate_dominators.contains_key(&node)
+    }
+}
+
+/// Computes the dominator tree of the nodes reachable from the start node of
+/// `graph`, using the algorithm of Cooper, Harvey, and Kennedy ("A Simple, Fast
+/// Dominance Algorithm").
+pub fn dominators<G: Successors>(graph: &G) -> Dominators<G::Node> {
+    let start_node = graph.start_node();
+
+    // Number the nodes in reverse postorder, so that (ignoring back edges)
+    // each node comes after all of its predecessors.
+    let mut postorder = vec![];
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![(start_node, graph.successors(start_node), 0)];
+    visited.insert(start_node);
+    while let Some((node, successors, index)) = stack.last_mut() {
+        if let Some(&successor) = successors.get(*index) {
+            *index += 1;
+            if visited.insert(successor) {
+                stack.push((successor, graph.successors(successor), 0));
+            }
+        } else {
+            postorder.push(*node);
+            stack.pop();
+        }
+    }
+    let rpo: Vec<G::Node> = postorder.into_iter().rev().collect();
+    let rpo_index: Map<G::Node, usize> = rpo.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let predecessors = predecessor_map(graph);
+
+    // `idoms[i]` is the (current guess at the) immediate dominator of `rpo[i]`,
+    // as an index into `rpo`.
+    let mut idoms: Vec<Option<usize>> = vec![None; rpo.len()];
+    idoms[0] = Some(0);
+    let intersect = |idoms: &[Option<usize>], mut a: usize, mut b: usize| {
+        while a != b {
+            while a > b {
+                a = idoms[a].unwrap();
+            }
+            while b > a {
+                b = idoms[b].unwrap();
+            }
+        }
+        a
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, node) in rpo.iter().enumerate().skip(1) {
+            let new_idom = predecessors[node]
+                .iter()
+                .map(|p| rpo_index[p])
+                .filter(|&p| idoms[p].is_some())
+                .reduce(|a, b| intersect(&idoms, a, b));
+            if new_idom != idoms[index] {
+                idoms[index] = new_idom;
+                changed = true;
+            }
+        }
+    }
+
+    let immediate_dominators = rpo
+        .iter()
+        .zip(&idoms)
+        .skip(1)
+        .map(|(&node, idom)| (node, rpo[idom.unwrap()]))
+        .collect();
+    Dominators {
+        start_node,
+        immediate_dominators,
+    }
+}
@@ -16,6 +16,17 @@ pub struct InputFile {
     /// The locations of any breakpoints set in this file.
     #[return_ref]
     breakpoint_locations: Vec<LineColumn>,
+
+    /// Names of functions provided by the host (rather than written in Dada)
+    /// that code in this file can call. When called, the interpreter invokes
+    /// the kernel's `call_native_function`.
+    #[return_ref]
+    native_functions: Vec<Word>,
+
+    /// Other files whose functions and classes code in this file can refer
+    /// to by name. Items defined in this file take precedence.
+    #[return_ref]
+    imported_files: Vec<InputFile>,
 }
 
 impl InputFile {
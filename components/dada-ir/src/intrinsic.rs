@@ -30,4 +30,28 @@ macro_rules! intrinsic {
 
 intrinsic! {
     Print => "print",
+    Assert => "assert",
+    EPrint => "eprint",
+    Len => "len",
+    ReadLine => "read_line",
+    TypeOf => "type_of",
+    Range => "range",
+    RangeInclusive => "range_inclusive",
+    RangeStart => "range_start",
+    RangeEnd => "range_end",
+    Min => "min",
+    Max => "max",
+    Abs => "abs",
+    ToUpper => "to_upper",
+    ToLower => "to_lower",
+    Trim => "trim",
+    Substring => "substring",
+    ParseInt => "parse_int",
+    ParseFloat => "parse_float",
+    ToString => "to_string",
+    Panic => "panic",
+    Ok => "ok",
+    Err => "err",
+    IsOk => "is_ok",
+    Unwrap => "unwrap",
 }
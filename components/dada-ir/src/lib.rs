@@ -7,6 +7,7 @@ pub mod diagnostic;
 pub mod effect;
 pub mod format_string;
 pub mod function;
+pub mod graph;
 pub mod in_ir_db;
 pub mod input_file;
 pub mod intrinsic;
@@ -25,6 +26,7 @@ pub mod word;
 #[salsa::jar(db = Db)]
 pub struct Jar(
     code::bir::Bir,
+    code::bir::reachable_control_points,
     code::syntax::Tree,
     code::validated::Tree,
     class::Class,
@@ -52,6 +52,20 @@ pub struct Field {
 
     /// Was the field declared as atomic?
     pub atomic: Atomic,
+
+    /// Value used for this field if the constructor call omits it.
+    pub default: Option<DefaultValue>,
+}
+
+/// The default value of a class field (e.g., the `0` in `class Point(x, y = 0)`).
+/// Only literals are supported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DefaultValue {
+    Bool(bool),
+    Int(u64),
+    UnsignedInt(u64),
+    SignedInt(i64),
+    Float(eq_float::F64),
 }
 
 /// Represents the signature in a callable thing (e.g., a class, function, etc)
@@ -31,6 +31,10 @@ pub enum Token {
     /// A string literal like `"foo"` or `"foo {bar}"`
     FormatString(FormatString),
 
+    /// A character literal like `'a'` or `'\n'`; the word is the
+    /// source text, including the quotes.
+    CharLiteral(Word),
+
     /// Some whitespace (` `, `\n`, etc)
     Whitespace(char),
 
@@ -47,9 +51,10 @@ impl Token {
     pub fn span_len(self, db: &dyn Db) -> u32 {
         match self {
             Token::Tree(tree) => tree.span(db).len(),
-            Token::Alphabetic(word) | Token::Number(word) | Token::Prefix(word) => {
-                word.as_str(db).len().try_into().unwrap()
-            }
+            Token::Alphabetic(word)
+            | Token::Number(word)
+            | Token::Prefix(word)
+            | Token::CharLiteral(word) => word.as_str(db).len().try_into().unwrap(),
             Token::FormatString(f) => f.len(db),
             Token::Delimiter(ch) | Token::Op(ch) | Token::Whitespace(ch) | Token::Unknown(ch) => {
                 ch.len_utf8().try_into().unwrap()
@@ -85,6 +90,9 @@ impl salsa::DebugWithDb<dyn crate::Db + '_> for Token {
             Token::Alphabetic(word) => f.debug_tuple("Alphabetic").field(&word.debug(db)).finish(),
             Token::Number(word) => f.debug_tuple("Number").field(&word.debug(db)).finish(),
             Token::Prefix(word) => f.debug_tuple("Prefix").field(&word.debug(db)).finish(),
+            Token::CharLiteral(word) => {
+                f.debug_tuple("CharLiteral").field(&word.debug(db)).finish()
+            }
             Token::Tree(tree) => f.debug_tuple("Tree").field(&tree.debug(db)).finish(),
             Token::FormatString(format_string) => f
                 .debug_tuple("FormatString")
@@ -19,6 +19,10 @@ pub struct Options {
     /// Log the BIR
     #[structopt(long)]
     log_bir: bool,
+
+    /// Warn about code that can never be executed
+    #[structopt(long)]
+    warn_unreachable: bool,
 }
 
 impl Options {
@@ -31,6 +35,12 @@ impl Options {
             let input_file = db.new_input_file(path, contents);
             all_diagnostics.extend(db.diagnostics(input_file));
 
+            if self.warn_unreachable {
+                for item in db.items(input_file) {
+                    all_diagnostics.extend(db.unreachable_code_warnings(item));
+                }
+            }
+
             if self.log_syntax_tree {
                 for item in db.items(input_file) {
                     if let Some(tree) = db.debug_syntax_tree(item) {
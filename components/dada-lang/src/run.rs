@@ -29,8 +29,11 @@ impl Options {
             .with_context(|| format!("reading `{}`", self.path.display()))?;
         let input_file = db.new_input_file(&self.path, contents);
 
-        for diagnostic in db.diagnostics(input_file) {
-            dada_error_format::print_diagnostic(&db, &diagnostic)?;
+        // Errors do not stop us from executing: the program fails only if
+        // it reaches the erroneous code. Warnings never affect execution.
+        let diagnostics = db.diagnostics_by_severity(input_file);
+        for diagnostic in diagnostics.warnings.iter().chain(&diagnostics.errors) {
+            dada_error_format::print_diagnostic(&db, diagnostic)?;
         }
 
         let mut should_execute = true;
@@ -98,11 +101,43 @@ impl dada_execute::kernel::Kernel for Kernel {
         return Ok(());
     }
 
+    async fn print_err(&mut self, _await_pc: ProgramCounter, text: &str) -> eyre::Result<()> {
+        let mut stderr = tokio::io::stderr();
+        let mut text = text.as_bytes();
+        while !text.is_empty() {
+            let written = stderr.write(text).await?;
+            text = &text[written..];
+        }
+        return Ok(());
+    }
+
+    async fn flush(&mut self) -> eyre::Result<()> {
+        tokio::io::stdout().flush().await?;
+        tokio::io::stderr().flush().await?;
+        Ok(())
+    }
+
+    async fn read_line(&mut self, _await_pc: ProgramCounter) -> eyre::Result<String> {
+        let mut line = tokio::task::spawn_blocking(|| {
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line).map(|_| line)
+        })
+        .await??;
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(line)
+    }
+
     fn breakpoint_start(
         &mut self,
         _db: &dyn dada_execute::Db,
         _breakpoint_input_file: dada_ir::input_file::InputFile,
         _breakpoint_index: usize,
+        _hit_count: usize,
         _generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
     ) -> eyre::Result<()> {
         panic!("no breakpoints set")
@@ -113,6 +148,7 @@ impl dada_execute::kernel::Kernel for Kernel {
         _db: &dyn dada_execute::Db,
         _breakpoint_input_file: dada_ir::input_file::InputFile,
         _breakpoint_index: usize,
+        _hit_count: usize,
         _breakpoint_span: FileSpan,
         _generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
     ) -> eyre::Result<()> {
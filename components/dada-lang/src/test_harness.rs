@@ -13,6 +13,10 @@ use regex::Regex;
 mod heap_graph_query;
 mod lsp_client;
 
+/// Step budget when interpreting tests, so that a test that loops
+/// forever reports an error instead of hanging the test run.
+const MAX_STEPS: u64 = 100_000;
+
 #[derive(structopt::StructOpt)]
 pub struct Options {
     /// Paths to directories and/or `.dada` files to test
@@ -206,6 +210,7 @@ impl Options {
             &path.join("stdout.ref"),
             &expected_diagnostics.runtime,
             &expected_diagnostics.output,
+            &expected_diagnostics.input,
             &mut errors,
         )
         .await?;
@@ -378,12 +383,16 @@ impl Options {
         ref_path: &Path,
         expected_diagnostics: &[ExpectedDiagnostic],
         expected_outputs: &Option<Vec<ExpectedOutput>>,
+        input: &str,
         errors: &mut Errors,
     ) -> eyre::Result<()> {
         let mut diagnostics = vec![];
         let actual_output = match db.main_function(input_file) {
             Some(bir) => {
-                let mut kernel = BufferKernel::new().track_output_ranges(true);
+                let mut kernel = BufferKernel::new()
+                    .track_output_ranges(true)
+                    .max_steps(Some(MAX_STEPS))
+                    .input(input);
                 let res = kernel.interpret(db, bir, vec![]).await;
                 if let Err(err) = res {
                     match err.downcast_ref::<dada_execute::DiagnosticError>() {
@@ -712,6 +721,9 @@ struct ExpectedDiagnostics {
 
     // Any `#! FIXME` annotations found
     fixmes: Vec<String>,
+
+    // Text given by `#! INPUT` lines, which is fed to `read_line`.
+    input: String,
 }
 
 /// Returns the diagnostics that we expect to see in the file, sorted by line number.
@@ -732,6 +744,8 @@ fn expected_diagnostics(path: &Path) -> eyre::Result<ExpectedDiagnostics> {
 
     let any_output_marker = regex::Regex::new(r"^(?P<prefix>[^#]*)#!\s*OUTPUT ANY").unwrap();
 
+    let input_marker = regex::Regex::new(r"^[^#]*#!\s*INPUT(\s(?P<text>.*))?$").unwrap();
+
     let any_marker = regex::Regex::new(r"^[^#]*#!").unwrap();
 
     fn compute_line_number(
@@ -770,6 +784,7 @@ fn expected_diagnostics(path: &Path) -> eyre::Result<ExpectedDiagnostics> {
     let mut output = vec![];
     let mut fixmes = vec![];
     let mut any_output_marker_seen = None;
+    let mut input = String::new();
     for (line, line_number) in file_contents.lines().zip(1..) {
         if let Some(c) = diagnostic_marker.captures(line) {
             let start_line = compute_line_number(&c["prefix"], None, last_code_line, line_number);
@@ -798,6 +813,9 @@ fn expected_diagnostics(path: &Path) -> eyre::Result<ExpectedDiagnostics> {
                     eyre::bail!("unexpected diagnostic type {} in {:?}", wrong, path);
                 }
             }
+        } else if let Some(c) = input_marker.captures(line) {
+            input.push_str(c.name("text").map_or("", |m| m.as_str()));
+            input.push('\n');
         } else if any_output_marker.is_match(line) {
             any_output_marker_seen = Some(line_number);
         } else if let Some(c) = output_marker.captures(line) {
@@ -838,6 +856,7 @@ fn expected_diagnostics(path: &Path) -> eyre::Result<ExpectedDiagnostics> {
             Some(output)
         },
         fixmes,
+        input,
     })
 }
 
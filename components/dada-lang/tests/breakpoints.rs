@@ -0,0 +1,514 @@
+mod common;
+
+use common::db_with_file;
+use dada_execute::{
+    heap_graph::{EdgeSource, GraphvizOptions, PermissionNodeLabel, RankDir},
+    kernel::{BreakpointRecord, BufferKernel},
+};
+use dada_ir::{input_file::InputFile, span::LineColumn};
+use serde_json::json;
+
+const SOURCE: &str = "let x = 22\nlet y = x + 1\nprint(y).await\n";
+
+const CLASS_SOURCE: &str = "class Point(x, y)\nlet p = Point(22, 44)\nlet q = p\n";
+
+const LEASED_POINT_SOURCE: &str = "\
+class Point(x, y)
+
+async fn main() {
+    let p = Point(22, 44)
+    let q = Point(p.lease, 66)
+}
+";
+
+const LEASED_VARIABLE_SOURCE: &str = "\
+class Point(x, y)
+
+async fn main() {
+    let p = Point(22, 44)
+    let q = p.lease
+    q.x
+}
+";
+
+const LOOP_SOURCE: &str = "let n = 0\nwhile n < 3 {\n    n += 1\n}\nprint(n).await\n";
+
+/// Runs `source` with a single breakpoint at `location`, checks that it
+/// printed `expected_output`, and returns the database along with the
+/// breakpoints that fired.
+async fn run_with_breakpoint(
+    source: &str,
+    location: LineColumn,
+    kernel: BufferKernel,
+    expected_output: &str,
+) -> eyre::Result<(dada_db::Db, Vec<BreakpointRecord>)> {
+    let (mut db, input_file) = db_with_file("breakpoints.dada", source);
+    db.set_breakpoints(input_file, vec![location]);
+    let bir = db.main_function(input_file).expect("no main function");
+
+    let mut kernel = kernel;
+    kernel.interpret(&db, bir, vec![]).await?;
+    assert_eq!(kernel.buffer(), expected_output);
+    Ok((db, kernel.take_recorded_breakpoints()))
+}
+
+/// Runs `SOURCE` with a breakpoint on the `x` in `x + 1`, using the kernel that
+/// `kernel` creates for the input file, and returns the number of times the
+/// breakpoint fired.
+async fn breakpoint_hits(kernel: impl FnOnce(InputFile) -> BufferKernel) -> eyre::Result<usize> {
+    let (mut db, input_file) = db_with_file("breakpoints.dada", SOURCE);
+    db.set_breakpoints(input_file, vec![LineColumn::new1(2, 9)]);
+    let bir = db.main_function(input_file).expect("no main function");
+
+    let mut kernel = kernel(input_file);
+    kernel.interpret(&db, bir, vec![]).await?;
+    assert_eq!(kernel.buffer(), "23\n");
+    Ok(kernel.take_recorded_breakpoints().len())
+}
+
+#[tokio::test]
+async fn unconditional_breakpoint_fires() -> eyre::Result<()> {
+    assert_eq!(breakpoint_hits(|_| BufferKernel::new()).await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn true_condition_fires() -> eyre::Result<()> {
+    let kernel = |input_file| {
+        BufferKernel::new().breakpoint_condition(input_file, 0, |_db, _heap_graph| true)
+    };
+    assert_eq!(breakpoint_hits(kernel).await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn false_condition_does_not_fire() -> eyre::Result<()> {
+    let kernel = |input_file| {
+        BufferKernel::new().breakpoint_condition(input_file, 0, |_db, _heap_graph| false)
+    };
+    assert_eq!(breakpoint_hits(kernel).await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn condition_only_applies_to_its_input_file() -> eyre::Result<()> {
+    // Both files have a breakpoint with index 0; only the one in the
+    // library has a (false) condition.
+    let mut db = dada_db::Db::default();
+    let main_file = db.new_input_file(
+        "main.dada",
+        "async fn main() {\n    let x = double(22)\n    print(x).await\n}\n".to_string(),
+    );
+    let library_file = db.new_input_file(
+        "library.dada",
+        "fn double(a) -> {\n    a + a\n}\n".to_string(),
+    );
+    db.set_imported_files(main_file, vec![library_file]);
+    db.set_breakpoints(main_file, vec![LineColumn::new1(2, 20)]);
+    db.set_breakpoints(library_file, vec![LineColumn::new1(2, 5)]);
+    let bir = db.main_function(main_file).expect("no main function");
+
+    let mut kernel =
+        BufferKernel::new().breakpoint_condition(library_file, 0, |_db, _heap_graph| false);
+    kernel.interpret(&db, bir, vec![]).await?;
+    assert_eq!(kernel.buffer(), "44\n");
+    let records = kernel.take_recorded_breakpoints();
+    let files: Vec<_> = records.iter().map(|r| r.breakpoint_input_file).collect();
+    assert_eq!(files, vec![main_file]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn hit_count_increments_across_loop_iterations() -> eyre::Result<()> {
+    for _ in 0..2 {
+        // Each execution starts counting from 1 again.
+        let (_, records) = run_with_breakpoint(
+            LOOP_SOURCE,
+            LineColumn::new1(3, 5),
+            BufferKernel::new(),
+            "3\n",
+        )
+        .await?;
+        let hit_counts: Vec<usize> = records.iter().map(|r| r.hit_count).collect();
+        assert_eq!(hit_counts, vec![1, 2, 3]);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn breakpoints_are_listed_in_index_order() -> eyre::Result<()> {
+    let source = "\
+async fn helper() {
+    let a = 22
+}
+
+async fn main() {
+    let b = 44
+    helper().await
+}
+";
+    let (mut db, input_file) = db_with_file("breakpoints.dada", source);
+    db.set_breakpoints(
+        input_file,
+        vec![LineColumn::new1(2, 13), LineColumn::new1(6, 13)],
+    );
+
+    let spans = dada_breakpoint::locations::breakpoints_in(&db, input_file);
+    let texts: Vec<&str> = spans
+        .iter()
+        .map(|span| &source[usize::from(span.start)..usize::from(span.end)])
+        .collect();
+    assert_eq!(texts, vec!["22", "44"]);
+
+    // The indices reported while running refer to the same list, even
+    // though the breakpoints are in different functions.
+    let bir = db.main_function(input_file).expect("no main function");
+    let mut kernel = BufferKernel::new();
+    kernel.interpret(&db, bir, vec![]).await?;
+    let records = kernel.take_recorded_breakpoints();
+    let indices: Vec<usize> = records.iter().map(|r| r.breakpoint_index).collect();
+    assert_eq!(indices, vec![1, 0]);
+    for record in &records {
+        assert_eq!(record.breakpoint_span, spans[record.breakpoint_index]);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn in_flight_value_is_highlighted() -> eyre::Result<()> {
+    // Breakpoint on the literal `22`, whose value is in-flight at the end.
+    let (db, records) =
+        run_with_breakpoint(SOURCE, LineColumn::new1(1, 9), BufferKernel::new(), "23\n").await?;
+    let heap_graph = &records[0].heap_at_end;
+
+    // Not highlighted by default...
+    let graphviz = heap_graph.graphviz_alone(&db, false, None);
+    assert!(graphviz.contains("(in-flight)"));
+    assert!(!graphviz.contains("bgcolor"));
+
+    // ...but only when a color is given.
+    let options = GraphvizOptions {
+        in_flight_bgcolor: Some("lightyellow".to_string()),
+        ..GraphvizOptions::default()
+    };
+    let graphviz = heap_graph.graphviz_alone_with_options(&db, false, None, &options);
+    let in_flight_row = graphviz
+        .lines()
+        .find(|line| line.contains("(in-flight)"))
+        .expect("no in-flight row");
+    assert!(in_flight_row.contains(r#"bgcolor="lightyellow""#));
+    Ok(())
+}
+
+#[tokio::test]
+async fn permission_ids_are_optional() -> eyre::Result<()> {
+    // Breakpoint on the `p` in `q = p`, where `p` owns a `Point`.
+    let (db, records) = run_with_breakpoint(
+        CLASS_SOURCE,
+        LineColumn::new1(3, 9),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let heap_graph = &records[0].heap_at_start;
+
+    let default_graphviz = heap_graph.graphviz_alone(&db, false, None);
+    let graphviz =
+        heap_graph.graphviz_alone_with_options(&db, false, None, &GraphvizOptions::default());
+    assert_eq!(graphviz, default_graphviz);
+    assert!(graphviz.contains(r#"[label="my","#));
+    assert!(!graphviz.contains(" #"));
+
+    let options = GraphvizOptions {
+        include_permission_ids: true,
+        ..GraphvizOptions::default()
+    };
+    let graphviz = heap_graph.graphviz_alone_with_options(&db, false, None, &options);
+    assert!(graphviz.contains(r#"[label="my #"#));
+    Ok(())
+}
+
+#[tokio::test]
+async fn layout_is_configurable() -> eyre::Result<()> {
+    let (db, records) = run_with_breakpoint(
+        CLASS_SOURCE,
+        LineColumn::new1(3, 9),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let heap_graph = &records[0].heap_at_start;
+
+    let graphviz = heap_graph.graphviz_alone(&db, false, None);
+    assert!(graphviz.contains(r#"rankdir = "LR";"#));
+    assert!(graphviz.contains(r#"node[shape = "note"];"#));
+
+    let options = GraphvizOptions {
+        rankdir: RankDir::TopToBottom,
+        node_shape: "box".to_string(),
+        ..GraphvizOptions::default()
+    };
+    let graphviz = heap_graph.graphviz_alone_with_options(&db, false, None, &options);
+    assert!(graphviz.contains(r#"rankdir = "TB";"#));
+    assert!(!graphviz.contains(r#"rankdir = "LR";"#));
+    assert!(graphviz.contains(r#"node[shape = "box"];"#));
+    Ok(())
+}
+
+#[tokio::test]
+async fn long_strings_are_truncated() -> eyre::Result<()> {
+    let long = "abcdefghij".repeat(6);
+    let (db, records) = run_with_breakpoint(
+        &format!("let s = \"{long}\"\nlet t = s\n"),
+        LineColumn::new1(2, 9),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let heap_graph = &records[0].heap_at_start;
+
+    let graphviz = heap_graph.graphviz_alone(&db, false, None);
+    assert!(!graphviz.contains(&long), "{graphviz}");
+    assert!(graphviz.contains("[...]"), "{graphviz}");
+
+    let options = GraphvizOptions {
+        truncate_data_at: 100,
+        ..GraphvizOptions::default()
+    };
+    let graphviz = heap_graph.graphviz_alone_with_options(&db, false, None, &options);
+    assert!(graphviz.contains(&long), "{graphviz}");
+    assert!(!graphviz.contains("[...]"), "{graphviz}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn strings_are_truncated_from_40_characters() -> eyre::Result<()> {
+    // The rendering includes the quotes, so these render as 39 and 40 characters.
+    for (len, truncated) in [(37, false), (38, true)] {
+        let (db, records) = run_with_breakpoint(
+            &format!("let s = \"{}\"\nlet t = s\n", "a".repeat(len)),
+            LineColumn::new1(2, 9),
+            BufferKernel::new(),
+            "",
+        )
+        .await?;
+        let graphviz = records[0].heap_at_start.graphviz_alone(&db, false, None);
+        assert_eq!(graphviz.contains("[...]"), truncated, "{graphviz}");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn text_dump() -> eyre::Result<()> {
+    // Breakpoint on `p.lease`, whose value is in-flight at the end.
+    let (db, records) = run_with_breakpoint(
+        LEASED_POINT_SOURCE,
+        LineColumn::new1(5, 23),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let text = records[0].heap_at_end.to_text(&db, false);
+    assert_eq!(
+        text,
+        "\
+main
+  p: my Point@0
+    x: \"22\"
+    y: \"44\"
+  q: (expired)
+  (in-flight): leased Point@0 (see above)
+"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn json_dump() -> eyre::Result<()> {
+    // Breakpoint on `p.lease`, whose value is in-flight at the end.
+    let (db, records) = run_with_breakpoint(
+        LEASED_POINT_SOURCE,
+        LineColumn::new1(5, 23),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let json = records[0].heap_at_end.to_json(&db);
+
+    // Permissions are numbered in the order they are reached: `p`, `q`, the
+    // in-flight lease of `p`, then the fields of the point. The lease is a
+    // tenant of `p`'s permission, which is its lessor.
+    assert_eq!(
+        json,
+        json!({
+            "stack": [{
+                "function": "main",
+                "variables": [
+                    { "name": "p", "value": { "permission": 0, "target": { "node": "node0" } } },
+                    { "name": "q", "value": { "permission": 1, "target": "expired" } },
+                ],
+                "in_flight": { "permission": 2, "target": { "node": "node0" } },
+            }],
+            "nodes": [{
+                "id": "node0",
+                "kind": "object",
+                "name": "Point",
+                "fields": [
+                    { "name": "x", "value": { "permission": 3, "target": { "data": "22" } } },
+                    { "name": "y", "value": { "permission": 4, "target": { "data": "44" } } },
+                ],
+            }],
+            "permissions": [
+                { "id": 0, "label": "my", "tenants": [2], "lessor": null },
+                { "id": 1, "label": "expired", "tenants": [], "lessor": null },
+                { "id": 2, "label": "leased", "tenants": [], "lessor": 0 },
+                { "id": 3, "label": "my", "tenants": [], "lessor": null },
+                { "id": 4, "label": "my", "tenants": [], "lessor": null },
+            ],
+        })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn mermaid_dump() -> eyre::Result<()> {
+    // Breakpoint on the `q` in `q.x`, by which time `q` leases `p`.
+    let (db, records) = run_with_breakpoint(
+        LEASED_VARIABLE_SOURCE,
+        LineColumn::new1(6, 5),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let mermaid = records[0].heap_at_start.mermaid(&db, false);
+
+    // The edge from `p` is dotted because its permission has a tenant (`q`).
+    assert_eq!(
+        mermaid,
+        r#"graph LR
+  subgraph frame0 ["main"]
+    frame0_0["p"]
+    frame0_1["q"]
+  end
+  node0["<b>Point</b><br/>x: #quot;22#quot;<br/>y: #quot;44#quot;"]
+  frame0_0 -.->|"my"| node0
+  frame0_1 -->|"leased"| node0
+"#
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn text_dump_shows_data_in_full() -> eyre::Result<()> {
+    let long = format!("<{}>", "abcdefghij".repeat(6));
+    let (db, records) = run_with_breakpoint(
+        &format!("let s = \"{long}\"\nlet t = s\n"),
+        LineColumn::new1(2, 9),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let text = records[0].heap_at_start.to_text(&db, false);
+    assert_eq!(
+        text,
+        format!("builtin@main\n  s: {long:?}\n  t: (expired)\n")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn given_variable_has_no_edge() -> eyre::Result<()> {
+    // Breakpoint on `a.give`, after which the point is in-flight.
+    let (db, records) = run_with_breakpoint(
+        "class Point(x, y)\nlet a = Point(22, 44)\nlet b = a.give\n",
+        LineColumn::new1(3, 9),
+        BufferKernel::new(),
+        "",
+    )
+    .await?;
+    let heap_graph = &records[0].heap_at_end;
+
+    let text = heap_graph.to_text(&db, false);
+    assert!(text.contains("\n  a: (expired)\n"), "{text}");
+    assert!(text.contains("(in-flight): my Point"), "{text}");
+
+    // `a` is the first local variable, so it would be port 0.
+    let graphviz = heap_graph.graphviz_alone(&db, false, None);
+    assert!(!graphviz.contains(":0 ->"), "{graphviz}");
+    assert!(graphviz.contains(" -> "), "{graphviz}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn diff_reports_assigned_variable() -> eyre::Result<()> {
+    // Breakpoints on the `22` in `Point(22, 44)` and on the `p` in `q = p`,
+    // so that the assignment to `p` happens between the two snapshots.
+    let (mut db, input_file) = db_with_file("breakpoints.dada", CLASS_SOURCE);
+    db.set_breakpoints(
+        input_file,
+        vec![LineColumn::new1(2, 15), LineColumn::new1(3, 9)],
+    );
+    let bir = db.main_function(input_file).expect("no main function");
+
+    let mut kernel = BufferKernel::new();
+    kernel.interpret(&db, bir, vec![]).await?;
+    let records = kernel.take_recorded_breakpoints();
+    assert_eq!(records.len(), 2);
+
+    let diff = records[0].heap_at_start.diff(&records[1].heap_at_start);
+    assert!(!diff.objects_created.is_empty(), "{diff:?}");
+    assert!(
+        diff.changed_edges
+            .iter()
+            .any(|change| matches!(change.source, EdgeSource::Field { .. })
+                && change.before.is_none())
+    );
+
+    let (p, _) = bir
+        .data(&db)
+        .named_locals()
+        .find(|(_, name)| name.as_str(&db) == "p")
+        .expect("no local variable `p`");
+    let change = diff
+        .changed_edges
+        .iter()
+        .find(|change| matches!(change.source, EdgeSource::Local { local, .. } if local == p))
+        .expect("no change to `p`");
+    assert_eq!(change.before, Some(PermissionNodeLabel::Expired));
+    assert_eq!(change.after, Some(PermissionNodeLabel::My));
+
+    // Comparing a snapshot against itself finds no differences.
+    let diff = records[1].heap_at_start.diff(&records[1].heap_at_start);
+    assert_eq!(diff, Default::default());
+    Ok(())
+}
+
+#[tokio::test]
+async fn graphviz_is_identical_across_runs() -> eyre::Result<()> {
+    // Each iteration drops the previous point and revokes the lease on it,
+    // so the gc has work to do between the breakpoints.
+    let source = "\
+class Point(x, y)
+
+async fn main() {
+    let p = Point(0, 0)
+    let q = p.lease
+    let i = 0
+    while i < 3 {
+        p = Point(i, q.x)
+        q = p.lease
+        i += 1
+    }
+}
+";
+    let mut graphviz_runs = vec![];
+    for _ in 0..2 {
+        let (db, records) =
+            run_with_breakpoint(source, LineColumn::new1(9, 13), BufferKernel::new(), "").await?;
+        assert_eq!(records.len(), 3);
+        let graphviz: Vec<_> = records.iter().map(|r| r.to_graphviz(&db)).collect();
+        graphviz_runs.push(graphviz);
+    }
+    assert_eq!(graphviz_runs[0], graphviz_runs[1]);
+    Ok(())
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::{db_with_file, function_named};
+use dada_execute::kernel::BufferKernel;
+use serde_json::json;
+
+const SOURCE: &str = "\
+fn add(a, b) -> {
+    a + b
+}
+
+async fn pair(a, b) -> {
+    print(a).await
+    (a, b)
+}
+";
+
+#[tokio::test]
+async fn call_two_argument_function() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("call_function.dada", SOURCE);
+    assert!(db.diagnostics(input_file).is_empty());
+    let add = function_named(&db, input_file, "add");
+
+    let mut kernel = BufferKernel::new();
+    let result =
+        dada_execute::call_function(add, &db, &mut kernel, &[json!(20), json!(22)]).await?;
+    assert_eq!(result, json!(42));
+    assert_eq!(kernel.buffer(), "");
+    Ok(())
+}
+
+#[tokio::test]
+async fn call_async_function() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("call_function.dada", SOURCE);
+    let pair = function_named(&db, input_file, "pair");
+
+    let mut kernel = BufferKernel::new();
+    let result =
+        dada_execute::call_function(pair, &db, &mut kernel, &[json!("hi"), json!([1, 2])]).await?;
+    assert_eq!(result, json!(["hi", [1, 2]]));
+    assert_eq!(kernel.buffer(), "hi\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn wrong_number_of_arguments() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("call_function.dada", SOURCE);
+    let add = function_named(&db, input_file, "add");
+
+    let mut kernel = BufferKernel::new();
+    assert!(
+        dada_execute::call_function(add, &db, &mut kernel, &[json!(20)])
+            .await
+            .is_err()
+    );
+    Ok(())
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::db_with_file;
+use dada_ir::{item::Item, span::LineColumn, storage::Atomic};
+
+#[test]
+fn field_infos_lists_fields_in_order() {
+    let (db, input_file) = db_with_file("class_fields.dada", "class Pair(first, atomic second)\n");
+    let class = match db.items(input_file).as_slice() {
+        &[Item::Class(class)] => class,
+        items => panic!("expected a single class, found {items:?}"),
+    };
+
+    let fields: Vec<(String, Atomic, LineColumn, LineColumn)> = class
+        .field_infos(&db)
+        .into_iter()
+        .map(|field| {
+            let (_, start, end) = db.line_columns(field.span);
+            (field.name.as_str(&db).to_string(), field.atomic, start, end)
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            (
+                "first".to_string(),
+                Atomic::No,
+                LineColumn::new1(1, 12),
+                LineColumn::new1(1, 17),
+            ),
+            (
+                "second".to_string(),
+                Atomic::Yes,
+                LineColumn::new1(1, 19),
+                LineColumn::new1(1, 32),
+            ),
+        ]
+    );
+}
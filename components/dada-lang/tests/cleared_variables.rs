@@ -0,0 +1,31 @@
+mod common;
+
+use common::{db_with_file, runtime_error};
+use dada_execute::kernel::BufferKernel;
+
+/// `x` is cleared at the end of each iteration, so the second iteration
+/// reads it after it has been cleared.
+const SOURCE: &str = "\
+async fn main() {
+    let i = 0
+    while i < 2 {
+        let x = if i == 0 { 22 } else { x }
+        i += 1
+    }
+}
+";
+
+#[tokio::test]
+async fn reading_a_cleared_variable_is_an_error() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("cleared_variables.dada", SOURCE);
+    let bir = db.main_function(input_file).expect("no main function");
+    let diagnostic = runtime_error(&db, bir, &mut BufferKernel::new()).await;
+    assert_eq!(diagnostic.message, "variable used before assignment");
+
+    // The error points at the read of `x` in the `else` branch.
+    let start = usize::from(diagnostic.span.start);
+    let end = usize::from(diagnostic.span.end);
+    assert_eq!(&SOURCE[start..end], "x");
+    assert_eq!(SOURCE[..start].lines().count(), 4);
+    Ok(())
+}
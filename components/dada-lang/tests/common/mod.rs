@@ -0,0 +1,86 @@
+//! Setup shared by the integration tests in this directory. Tests that only
+//! check what a program prints belong in `dada_tests` instead.
+
+// Each test crate uses a different subset of these helpers.
+#![allow(dead_code)]
+
+use dada_execute::{kernel::BufferKernel, DebugStep, Debugger, DiagnosticError};
+use dada_ir::{
+    code::bir::{ActionData, Bir, BirData, ControlPointData, ExprData},
+    diagnostic::Diagnostic,
+    function::Function,
+    input_file::InputFile,
+    item::Item,
+};
+
+/// Creates a database holding `source` as the input file `name`.
+pub fn db_with_file(name: &str, source: &str) -> (dada_db::Db, InputFile) {
+    let mut db = dada_db::Db::default();
+    let input_file = db.new_input_file(name, source.to_string());
+    (db, input_file)
+}
+
+/// Like [`db_with_file`], but also checks that `source` compiles without
+/// diagnostics and returns the BIR of its `main` function.
+pub fn compile_main(name: &str, source: &str) -> (dada_db::Db, InputFile, Bir) {
+    let (db, input_file) = db_with_file(name, source);
+    let diagnostics = db.diagnostics(input_file);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    let bir = db.main_function(input_file).expect("no main function");
+    (db, input_file, bir)
+}
+
+/// Finds the function called `name` in `input_file`.
+pub fn function_named(db: &dada_db::Db, input_file: InputFile, name: &str) -> Function {
+    db.items(input_file)
+        .into_iter()
+        .find_map(|item| match item {
+            Item::Function(function) if function.name(db).as_str(db) == name => Some(function),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no function named `{name}`"))
+}
+
+/// Returns the expressions assigned by the reachable statements of `bir_data`.
+pub fn assigned_exprs(bir_data: &BirData) -> Vec<&ExprData> {
+    bir_data
+        .control_points()
+        .into_iter()
+        .filter_map(|cp| match &bir_data.tables[cp] {
+            ControlPointData::Statement(statement) => match statement.action {
+                ActionData::AssignExpr(_, expr) => Some(&bir_data.tables[expr]),
+                _ => None,
+            },
+            ControlPointData::Terminator(_) => None,
+        })
+        .collect()
+}
+
+/// Steps `debugger` until peeking at the variable `name` gives a value
+/// containing `text`.
+pub async fn step_until_peek_contains(
+    debugger: &mut Debugger<'_>,
+    name: &str,
+    text: &str,
+) -> eyre::Result<()> {
+    while !debugger
+        .peek(name)
+        .is_some_and(|value| value.contains(text))
+    {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+    }
+    Ok(())
+}
+
+/// Runs `bir` with `kernel`, expecting it to stop with an error, and returns
+/// the diagnostic for that error.
+pub async fn runtime_error(db: &dada_db::Db, bir: Bir, kernel: &mut BufferKernel) -> Diagnostic {
+    let err = kernel
+        .interpret(db, bir, vec![])
+        .await
+        .expect_err("expected a runtime error");
+    err.downcast_ref::<DiagnosticError>()
+        .expect("expected a diagnostic")
+        .diagnostic()
+        .clone()
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{assigned_exprs, compile_main, db_with_file};
+use dada_ir::code::bir::ExprData;
+
+const SOURCE: &str = "\
+async fn main() {
+    let a = 1
+    print(2 + 3).await
+    print(a + 3).await
+}
+";
+
+fn op_count(exprs: &[&ExprData]) -> usize {
+    exprs
+        .iter()
+        .filter(|expr| matches!(expr, ExprData::Op(..)))
+        .count()
+}
+
+#[test]
+fn literal_operands_are_folded() {
+    let (db, _, bir) = compile_main("constant_folding.dada", SOURCE);
+
+    let original = bir.data(&db);
+    assert_eq!(op_count(&assigned_exprs(original)), 2);
+
+    let mut folded = original.clone();
+    folded.fold_constants();
+    let exprs = assigned_exprs(&folded);
+
+    // `2 + 3` becomes `5`...
+    assert!(exprs.contains(&&ExprData::IntegerLiteral(5)));
+
+    // ...whereas `a + 3` is left alone, as `a` is a variable.
+    assert_eq!(op_count(&exprs), 1);
+}
+
+#[test]
+fn overflow_is_not_folded() {
+    let (db, input_file) = db_with_file(
+        "constant_folding.dada",
+        "print(18446744073709551615 + 1).await\nprint(1 / 0).await\n",
+    );
+    let bir = db.main_function(input_file).expect("no main function");
+
+    let mut folded = bir.data(&db).clone();
+    folded.fold_constants();
+    assert_eq!(&folded, bir.data(&db));
+}
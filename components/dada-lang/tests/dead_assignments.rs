@@ -0,0 +1,68 @@
+mod common;
+
+use common::{assigned_exprs, compile_main};
+use dada_ir::code::bir::{BirData, ControlPointData, ExprData, TerminatorData, TerminatorExpr};
+
+const SOURCE: &str = "\
+fn one() -> {
+    1
+}
+
+async fn main() {
+    let x = 22
+    x = 44
+    print(x).await
+    let y = one()
+    y = 66
+    print(y).await
+}
+";
+
+fn call_count(bir_data: &BirData) -> usize {
+    bir_data
+        .control_points()
+        .into_iter()
+        .filter(|&cp| {
+            matches!(
+                bir_data.tables[cp],
+                ControlPointData::Terminator(TerminatorData::Assign(
+                    _,
+                    TerminatorExpr::Call { .. },
+                    _
+                ))
+            )
+        })
+        .count()
+}
+
+#[test]
+fn overwritten_literal_is_removed() {
+    let (db, _, bir) = compile_main("dead_assignments.dada", SOURCE);
+
+    let original = bir.data(&db);
+    assert!(assigned_exprs(original).contains(&&ExprData::IntegerLiteral(22)));
+
+    let mut optimized = original.clone();
+    optimized.eliminate_dead_assignments();
+    let exprs = assigned_exprs(&optimized);
+
+    // `x = 22` is overwritten before `x` is read...
+    assert!(!exprs.contains(&&ExprData::IntegerLiteral(22)));
+
+    // ...but the values that are printed are still assigned.
+    assert!(exprs.contains(&&ExprData::IntegerLiteral(44)));
+    assert!(exprs.contains(&&ExprData::IntegerLiteral(66)));
+}
+
+#[test]
+fn overwritten_call_is_preserved() {
+    let (db, _, bir) = compile_main("dead_assignments.dada", SOURCE);
+
+    let original = bir.data(&db);
+    let mut optimized = original.clone();
+    optimized.eliminate_dead_assignments();
+
+    // The result of `one()` is never read, but the call must still happen.
+    assert!(call_count(original) > 0);
+    assert_eq!(call_count(&optimized), call_count(original));
+}
@@ -0,0 +1,516 @@
+mod common;
+
+use common::{compile_main, step_until_peek_contains};
+use dada_execute::{
+    heap_graph::GraphvizOptions, kernel::BufferKernel,
+    machine::assert_invariants::InvariantViolation, DebugStep, Debugger, GcRoot, PcInfo,
+};
+
+/// Two locals holding distinct objects; see `step_with_corrupted_machine`.
+const TWO_LOCALS_SOURCE: &str = "\
+async fn main() {
+    let a = 1
+    let b = 2
+    print(a).await
+}
+";
+
+#[tokio::test]
+async fn step_program_to_completion() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main(
+        "debugger.dada",
+        "let x = 22\nlet y = x + 1\nprint(y).await\n",
+    );
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+
+    let mut steps = 0;
+    loop {
+        assert!(debugger.current_span().is_some());
+        steps += 1;
+        match debugger.step_once().await? {
+            DebugStep::Running => {
+                let _ = debugger.heap_graph();
+            }
+            DebugStep::Done => break,
+        }
+    }
+
+    assert!(steps > 1);
+    assert!(debugger.is_done());
+    assert!(debugger.current_span().is_none());
+    assert!(debugger.step_once().await.is_err());
+    drop(debugger);
+
+    assert_eq!(kernel.buffer(), "23\n");
+    Ok(())
+}
+
+/// Runs `TWO_LOCALS_SOURCE` until `b` is assigned, then corrupts the
+/// machine so that `a` and `b` share one owned permission (violating
+/// invariant I0) and takes one more step.
+async fn step_with_corrupted_machine(check_invariants: bool) -> eyre::Result<DebugStep> {
+    let (db, _, bir) = compile_main("invariants.dada", TWO_LOCALS_SOURCE);
+    let named_locals: Vec<_> = bir.data(&db).named_locals().collect();
+    let local = |name: &str| {
+        named_locals
+            .iter()
+            .find(|(_, word)| word.as_str(&db) == name)
+            .map(|&(local_variable, _)| local_variable)
+            .unwrap()
+    };
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger =
+        Debugger::new(&db, bir, &mut kernel, vec![]).with_invariant_checks(check_invariants);
+    step_until_peek_contains(&mut debugger, "b", "2").await?;
+
+    let mut machine = debugger.snapshot();
+    let frame = machine.stack.frames.iter_mut().last().unwrap();
+    frame.locals[local("b")].permission = frame.locals[local("a")].permission;
+    debugger.restore(machine);
+
+    debugger.step_once().await
+}
+
+#[tokio::test]
+async fn invariant_checks_can_be_disabled() -> eyre::Result<()> {
+    let error = step_with_corrupted_machine(true)
+        .await
+        .expect_err("expected an invariant violation");
+    let violation = error
+        .downcast_ref::<InvariantViolation>()
+        .expect("expected an invariant violation");
+    assert_eq!(violation.invariant, "I0");
+
+    assert_eq!(
+        step_with_corrupted_machine(false).await?,
+        DebugStep::Running
+    );
+    Ok(())
+}
+
+const LEASE_SOURCE: &str = "\
+class Point(x, y)
+
+async fn main() {
+    let p = Point(22, 44)
+    let q = p.lease
+    let x = p.x
+}
+";
+
+#[tokio::test]
+async fn peek_does_not_revoke_leases() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main("peek.dada", LEASE_SOURCE);
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    assert_eq!(debugger.peek("no_such_variable"), None);
+
+    // Run until `q` holds its lease on `p`.
+    let leased = loop {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+        match debugger.peek("q") {
+            Some(q) if q != "(expired)" => break q,
+            _ => (),
+        }
+    };
+
+    // Peeking at the lessor leaves the lease intact...
+    let before = debugger.heap_graph().to_text(&db, false);
+    assert!(debugger.peek("p").is_some());
+    assert_eq!(debugger.peek("q").as_deref(), Some(leased.as_str()));
+    assert_eq!(debugger.heap_graph().to_text(&db, false), before);
+
+    // ...whereas the program reading `p.x` revokes it.
+    loop {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+        if debugger.peek("q").as_deref() == Some("(expired)") {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn peek_json_extracts_instance_fields() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main("peek_json.dada", LEASE_SOURCE);
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    assert_eq!(debugger.peek_json("p"), Some(serde_json::Value::Null));
+
+    let point = loop {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+        match debugger.peek_json("p") {
+            Some(serde_json::Value::Null) => (),
+            Some(point) => break point,
+            None => panic!("no variable `p`"),
+        }
+    };
+    assert_eq!(point, serde_json::json!({ "x": 22, "y": 44 }));
+    Ok(())
+}
+
+const DROP_SOURCE: &str = "\
+class Point(x, y)
+
+async fn main() {
+    let p = Point(22, 44)
+    p = 0
+    print(p).await
+}
+";
+
+#[tokio::test]
+async fn dropped_object_becomes_unreachable() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main("drop.dada", DROP_SOURCE);
+    let (p, _) = bir
+        .data(&db)
+        .named_locals()
+        .find(|(_, word)| word.as_str(&db) == "p")
+        .expect("no local `p`");
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+
+    // Run until `p` owns the point, which it alone keeps alive.
+    step_until_peek_contains(&mut debugger, "p", "Point").await?;
+    let retained_by_p: Vec<_> = debugger
+        .reachable_objects()
+        .into_iter()
+        .filter(|(_, root)| matches!(root, GcRoot::Local { local, .. } if *local == p))
+        .map(|(object, _)| object)
+        .collect();
+    assert!(!retained_by_p.is_empty());
+
+    // Once `p` is overwritten, nothing refers to the point (or its fields) anymore.
+    while debugger.peek("p").as_deref() != Some("0") {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+    }
+    assert!(debugger
+        .reachable_objects()
+        .iter()
+        .all(|(object, _)| !retained_by_p.contains(object)));
+    Ok(())
+}
+
+const SCOPED_SOURCE: &str = "\
+async fn main() {
+    if true {
+        let x = 22
+    }
+    print(\"done\").await
+}
+";
+
+#[tokio::test]
+async fn variables_are_cleared_at_end_of_scope() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main("scoped.dada", SCOPED_SOURCE);
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    while debugger.peek("x").as_deref() != Some("22") {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+    }
+    let live_objects = debugger.reachable_objects().len();
+
+    // Leaving the block clears `x` rather than leaving its stale value behind,
+    // and the value it held is collected.
+    while debugger.peek("x").as_deref() != Some("(expired)") {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+    }
+    assert!(debugger.reachable_objects().len() < live_objects);
+    while debugger.step_once().await? == DebugStep::Running {}
+    drop(debugger);
+
+    assert_eq!(kernel.buffer(), "done\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn peek_display_shows_tuple_elements() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main(
+        "peek_display.dada",
+        "async fn main() {\n    let t = (22, 44)\n    print(t).await\n}\n",
+    );
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    let displayed = loop {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+        let displayed = format!("{:?}", debugger.peek_display("t").unwrap());
+        if !displayed.starts_with("(expired)") {
+            break displayed;
+        }
+    };
+    assert!(displayed.contains("22"), "{displayed}");
+    assert!(displayed.contains("44"), "{displayed}");
+    assert!(displayed.contains("a tuple"), "{displayed}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn current_pc_advances_across_statements() -> eyre::Result<()> {
+    let source = "async fn main() {\n    let x = 22\n    let y = x + 1\n    print(y).await\n}\n";
+    let (db, input_file, bir) = compile_main("current_pc.dada", source);
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    let mut pcs = vec![];
+    while !debugger.is_done() {
+        pcs.push(debugger.current_pc().unwrap());
+        debugger.step_once().await?;
+    }
+    assert!(debugger.current_pc().is_none());
+    drop(debugger);
+
+    for pc in &pcs {
+        assert_eq!(pc.function_name.as_str(&db), "main");
+        assert_eq!(pc.span.input_file, input_file);
+    }
+    assert!(pcs
+        .windows(2)
+        .any(|w| w[0].control_point != w[1].control_point));
+
+    // Each statement is reached, in order.
+    let statement_starts: Vec<usize> = ["let x", "let y", "print"]
+        .iter()
+        .map(|s| source.find(s).unwrap())
+        .collect();
+    let statement_of = |pc: &PcInfo| {
+        statement_starts
+            .iter()
+            .filter(|&&start| usize::from(pc.span.start) >= start)
+            .count()
+    };
+    let first_pc_in = |statement: usize| {
+        pcs.iter()
+            .position(|pc| statement_of(pc) == statement)
+            .unwrap_or_else(|| panic!("statement {statement} never reached"))
+    };
+    assert!(first_pc_in(1) < first_pc_in(2));
+    assert!(first_pc_in(2) < first_pc_in(3));
+    Ok(())
+}
+
+#[tokio::test]
+async fn evaluate_watch_against_current_frame() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main(
+        "watch.dada",
+        "\
+async fn main() {
+    let a = 22
+    let b = 44
+    print(a).await
+}
+",
+    );
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+
+    // `b` is not assigned yet.
+    assert!(debugger.evaluate("a + b").await.is_err());
+
+    while debugger.peek("b").unwrap() == "(expired)" {
+        assert_eq!(debugger.step_once().await?, DebugStep::Running);
+    }
+    assert_eq!(debugger.evaluate("a + b").await?, "66");
+
+    // Errors in the expression itself are reported, not run.
+    assert!(debugger.evaluate("a +").await.is_err());
+    assert!(debugger.evaluate("c").await.is_err());
+
+    // Output from the expression does not end up in the program's output.
+    assert_eq!(debugger.evaluate("print(a).await\na * 2").await?, "44");
+
+    // Evaluating did not disturb the program.
+    assert_eq!(debugger.peek("a").unwrap(), "22");
+    assert_eq!(debugger.peek("b").unwrap(), "44");
+    while debugger.step_once().await? == DebugStep::Running {}
+    drop(debugger);
+
+    assert_eq!(kernel.buffer(), "22\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn object_origin_is_construction_site() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main(
+        "origin.dada",
+        "class Point(x, y)\nlet p = Point(22, 44)\nprint(p).await\n",
+    );
+
+    for track_object_origins in [false, true] {
+        let mut kernel = BufferKernel::new();
+        let mut debugger =
+            Debugger::new(&db, bir, &mut kernel, vec![]).with_object_origins(track_object_origins);
+        step_until_peek_contains(&mut debugger, "p", "Point").await?;
+
+        let origins: Vec<_> = debugger
+            .reachable_objects()
+            .into_iter()
+            .filter_map(|(object, _)| debugger.object_origin(object))
+            .collect();
+        if !track_object_origins {
+            assert!(origins.is_empty());
+            continue;
+        }
+        assert!(origins
+            .iter()
+            .any(|span| span.snippet(&db).contains("Point(22, 44)")));
+
+        let options = GraphvizOptions {
+            include_object_origins: true,
+            ..GraphvizOptions::default()
+        };
+        let graphviz = debugger
+            .heap_graph()
+            .graphviz_alone_with_options(&db, false, None, &options);
+        assert!(graphviz.contains("tooltip = \"allocated at origin.dada:2:"));
+    }
+    Ok(())
+}
+
+/// Renders the heap as soon as `p` holds the `Pair` built by `make`.
+async fn graphviz_of_pair(make: &str, options: &GraphvizOptions) -> eyre::Result<String> {
+    let (db, _, bir) = compile_main(
+        "pair.dada",
+        &format!(
+            "\
+class Point(x, y)
+class Pair(a, b)
+
+{make}
+
+async fn main() {{
+    let p = make()
+    print(p).await
+}}
+"
+        ),
+    );
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    step_until_peek_contains(&mut debugger, "p", "Pair").await?;
+    Ok(debugger
+        .heap_graph()
+        .graphviz_alone_with_options(&db, false, None, options))
+}
+
+#[tokio::test]
+async fn reordering_independent_allocations_keeps_graph_labels() -> eyre::Result<()> {
+    let a_first = "\
+fn make() -> {
+    let a = Point(1, 2)
+    let b = Point(3, 4)
+    Pair(a.give, b.give)
+}";
+    let b_first = "\
+fn make() -> {
+    let b = Point(3, 4)
+    let a = Point(1, 2)
+    Pair(a.give, b.give)
+}";
+
+    for breadth_first in [false, true] {
+        let options = GraphvizOptions {
+            breadth_first,
+            ..GraphvizOptions::default()
+        };
+        let graphviz = graphviz_of_pair(a_first, &options).await?;
+        assert!(graphviz.contains("node2"));
+        assert_eq!(graphviz, graphviz_of_pair(b_first, &options).await?);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_depth_uses_shortest_path() -> eyre::Result<()> {
+    // The point is two edges away from the stack through `a`, but three
+    // through `b`, which is walked first.
+    let (db, _, bir) = compile_main(
+        "diamond.dada",
+        "\
+class Point(x, y)
+class Box(value)
+
+async fn main() {
+    let a = Box(Point(22, 44))
+    let b = Box(Box(a.value.share))
+    print(b).await
+}
+",
+    );
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    step_until_peek_contains(&mut debugger, "b", "Box").await?;
+
+    let graphviz_at_depth = |max_depth| {
+        let options = GraphvizOptions {
+            max_depth: Some(max_depth),
+            ..GraphvizOptions::default()
+        };
+        debugger
+            .heap_graph()
+            .graphviz_alone_with_options(&db, false, None, &options)
+    };
+    let truncated = r#"[label = "..."];"#;
+    assert!(!graphviz_at_depth(2).contains(truncated));
+    assert!(graphviz_at_depth(1).contains(truncated));
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_rewinds_to_snapshot() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main(
+        "restore.dada",
+        "\
+class Point(x, y)
+
+async fn main() {
+    let p = Point(22, 44)
+    let q = p.lease
+    q.x += 1
+    let r = Point(q.x, p.y)
+    print(r).await
+}
+",
+    );
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    step_until_peek_contains(&mut debugger, "p", "Point").await?;
+    let snapshot = debugger.snapshot();
+    let graphviz = debugger.heap_graph().graphviz_alone(&db, false, None);
+    let pc = debugger.current_pc();
+
+    while debugger.step_once().await? == DebugStep::Running {}
+    assert!(debugger.is_done());
+    assert_ne!(
+        debugger.heap_graph().graphviz_alone(&db, false, None),
+        graphviz
+    );
+
+    debugger.restore(snapshot);
+    assert!(!debugger.is_done());
+    assert_eq!(debugger.current_pc(), pc);
+    assert_eq!(
+        debugger.heap_graph().graphviz_alone(&db, false, None),
+        graphviz
+    );
+
+    // Execution resumes from the snapshot.
+    while debugger.step_once().await? == DebugStep::Running {}
+    drop(debugger);
+    assert_eq!(
+        kernel.buffer(),
+        "Point(x: 23, y: 44)\nPoint(x: 23, y: 44)\n"
+    );
+    Ok(())
+}
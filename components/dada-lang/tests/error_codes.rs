@@ -0,0 +1,40 @@
+mod common;
+
+use common::{db_with_file, runtime_error};
+use dada_error_format::FormatOptions;
+use dada_execute::kernel::BufferKernel;
+use dada_ir::diagnostic::Diagnostic;
+
+/// Runs `source` and returns the database along with the diagnostic for
+/// the runtime error it reports.
+async fn run_to_error(source: &str) -> (dada_db::Db, Diagnostic) {
+    let (db, input_file) = db_with_file("error_codes.dada", source);
+    let bir = db.main_function(input_file).expect("no main function");
+    let diagnostic = runtime_error(&db, bir, &mut BufferKernel::new()).await;
+    (db, diagnostic)
+}
+
+fn render(db: &dada_db::Db, diagnostic: Diagnostic) -> eyre::Result<String> {
+    dada_error_format::format_diagnostics_with_options(db, &[diagnostic], FormatOptions::no_color())
+}
+
+#[tokio::test]
+async fn no_such_field_has_a_code() -> eyre::Result<()> {
+    let (db, diagnostic) =
+        run_to_error("class Point(x, y)\nlet p = Point(22, 44)\nprint(p.z).await\n").await;
+    assert_eq!(diagnostic.code, Some("E0001"));
+
+    let rendered = render(&db, diagnostic)?;
+    assert!(rendered.starts_with("[E0001] Error: the class `Point` has no field named `z`"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn diagnostics_without_codes_render_unchanged() -> eyre::Result<()> {
+    let (db, diagnostic) = run_to_error("async fn main() {\n    break\n}\n").await;
+    assert_eq!(diagnostic.code, None);
+
+    let rendered = render(&db, diagnostic)?;
+    assert!(rendered.starts_with("Error: compilation error encountered"));
+    Ok(())
+}
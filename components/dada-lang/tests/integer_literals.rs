@@ -0,0 +1,45 @@
+mod common;
+
+use common::db_with_file;
+
+/// Returns the diagnostics for `source`, each paired with the text its span covers.
+fn diagnostics(source: &str) -> Vec<(String, String)> {
+    let (db, input_file) = db_with_file("integer_literals.dada", source);
+    db.diagnostics(input_file)
+        .into_iter()
+        .map(|diagnostic| {
+            let span = diagnostic.span;
+            let text = &source[usize::from(span.start)..usize::from(span.end)];
+            (diagnostic.message, text.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn unsigned_literal_too_large() {
+    // One more than `u64::MAX`.
+    let diagnostics = diagnostics("let x = 18446744073709551616_u\n");
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let (message, text) = &diagnostics[0];
+    assert_eq!(message, "integer literal too large");
+    assert!(text.starts_with("18446744073709551616"), "{text:?}");
+}
+
+#[test]
+fn negative_literal_too_large() {
+    let diagnostics = diagnostics("let x = -99999999999999999999\n");
+    assert_eq!(
+        diagnostics,
+        vec![(
+            "integer literal too large".to_string(),
+            "99999999999999999999".to_string()
+        )]
+    );
+}
+
+#[test]
+fn largest_literals_are_accepted() {
+    let diagnostics =
+        diagnostics("let x = 18446744073709551615_u\nlet y = 9223372036854775807_i\n");
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{db_with_file, runtime_error};
+use dada_execute::kernel::BufferKernel;
+use serde_json::json;
+
+const SOURCE: &str = "\
+class Point(x, y)
+let p = Point(22, 44)
+print(p.z).await
+";
+
+#[tokio::test]
+async fn no_such_field_as_json() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("json_diagnostics.dada", SOURCE);
+    let bir = db.main_function(input_file).expect("no main function");
+    let diagnostic = runtime_error(&db, bir, &mut BufferKernel::new()).await;
+
+    let json = dada_error_format::format_diagnostics_json(&db, &[diagnostic]);
+    let span = |start: usize, end: usize, line: u32, start_column: u32, end_column: u32| {
+        json!({
+            "file": "json_diagnostics.dada",
+            "start": start,
+            "end": end,
+            "start_line": line,
+            "start_column": start_column,
+            "end_line": line,
+            "end_column": end_column,
+        })
+    };
+    assert_eq!(
+        json,
+        json!([{
+            "severity": "error",
+            "message": "the class `Point` has no field named `z`",
+            "code": "E0001",
+            "span": span(46, 49, 3, 7, 10),
+            "labels": [
+                {
+                    "span": span(6, 11, 1, 7, 12),
+                    "message": "the class `Point` is declared here",
+                    "primary": false,
+                },
+                {
+                    "span": span(46, 49, 3, 7, 10),
+                    "message": "here",
+                    "primary": true,
+                },
+            ],
+            "children": [],
+        }])
+    );
+    Ok(())
+}
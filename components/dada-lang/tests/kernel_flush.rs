@@ -0,0 +1,80 @@
+mod common;
+
+use common::compile_main;
+use dada_execute::{heap_graph::HeapGraph, kernel::Kernel, machine::ProgramCounter};
+use dada_ir::{input_file::InputFile, span::FileSpan};
+
+/// A kernel that holds printed text back until it is flushed.
+#[derive(Default)]
+struct BufferingKernel {
+    pending: String,
+    flushed: String,
+    flush_count: usize,
+}
+
+#[async_trait::async_trait]
+impl Kernel for BufferingKernel {
+    async fn print(&mut self, _await_pc: ProgramCounter, text: &str) -> eyre::Result<()> {
+        self.pending.push_str(text);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> eyre::Result<()> {
+        self.flushed.push_str(&std::mem::take(&mut self.pending));
+        self.flush_count += 1;
+        Ok(())
+    }
+
+    fn breakpoint_start(
+        &mut self,
+        _db: &dyn dada_execute::Db,
+        _breakpoint_input_file: InputFile,
+        _breakpoint_index: usize,
+        _hit_count: usize,
+        _generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
+    ) -> eyre::Result<()> {
+        panic!("no breakpoints set")
+    }
+
+    fn breakpoint_end(
+        &mut self,
+        _db: &dyn dada_execute::Db,
+        _breakpoint_input_file: InputFile,
+        _breakpoint_index: usize,
+        _hit_count: usize,
+        _breakpoint_span: FileSpan,
+        _generate_heap_graph: &mut dyn FnMut() -> HeapGraph,
+    ) -> eyre::Result<()> {
+        panic!("no breakpoints set")
+    }
+}
+
+#[tokio::test]
+async fn flush_is_called_once_at_end() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main(
+        "kernel_flush.dada",
+        "print(\"hello\").await\nprint(\"world\").await\n",
+    );
+
+    let mut kernel = BufferingKernel::default();
+    dada_execute::interpret(bir, &db, &mut kernel, vec![]).await?;
+    assert_eq!(kernel.flush_count, 1);
+    assert_eq!(kernel.flushed, "hello\nworld\n");
+    assert_eq!(kernel.pending, "");
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_line_is_not_supported_by_default() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main("kernel_read_line.dada", "let line = read_line().await\n");
+
+    let mut kernel = BufferingKernel::default();
+    let error = dada_execute::interpret(bir, &db, &mut kernel, vec![])
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{error:?}").contains("stdin not supported by this kernel"),
+        "{error:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::compile_main;
+use dada_execute::{kernel::BufferKernel, DebugStep, Debugger};
+
+/// Allocates a tuple on each of `n` iterations.
+fn tuple_loop_source(n: u64) -> String {
+    format!(
+        "\
+async fn main() {{
+    let n = 0
+    while n < {n} {{
+        let t = (n, n)
+        n += 1
+    }}
+}}
+"
+    )
+}
+
+/// Runs `tuple_loop_source(n)` to completion and returns the final statistics.
+async fn tuple_loop_stats(n: u64) -> eyre::Result<dada_execute::machine::MachineStats> {
+    let (db, _, bir) = compile_main("stats.dada", &tuple_loop_source(n));
+
+    let mut kernel = BufferKernel::new();
+    let mut debugger = Debugger::new(&db, bir, &mut kernel, vec![]);
+    while debugger.step_once().await? == DebugStep::Running {}
+    Ok(debugger.stats())
+}
+
+#[tokio::test]
+async fn tuple_loop_allocations() -> eyre::Result<()> {
+    let small = tuple_loop_stats(100).await?;
+    let large = tuple_loop_stats(200).await?;
+
+    // Each iteration allocates at least the tuple, and everything allocated
+    // in an iteration is garbage by the next one.
+    assert!(small.objects_allocated >= 100, "{small:?}");
+    assert!(small.objects_collected >= 100, "{small:?}");
+    assert!(
+        small.objects_allocated - small.objects_collected < 10,
+        "{small:?}"
+    );
+    assert!(small.permissions_collected <= small.permissions_allocated);
+
+    // The allocation count is proportional to the number of iterations.
+    let per_iteration = (large.objects_allocated - small.objects_allocated) / 100;
+    assert!(per_iteration >= 1, "{small:?} vs {large:?}");
+    assert_eq!(
+        small.objects_allocated - 100 * per_iteration,
+        large.objects_allocated - 200 * per_iteration,
+    );
+    Ok(())
+}
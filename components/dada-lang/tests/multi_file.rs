@@ -0,0 +1,56 @@
+mod common;
+
+use common::runtime_error;
+use dada_execute::kernel::BufferKernel;
+
+const MAIN_SOURCE: &str = "\
+async fn main() {
+    print(divide(44, 2)).await
+    print(divide(22, 0)).await
+}
+";
+
+const LIBRARY_SOURCE: &str = "\
+fn divide(a, b) -> {
+    a / b
+}
+";
+
+#[tokio::test]
+async fn call_function_in_other_file() -> eyre::Result<()> {
+    let mut db = dada_db::Db::default();
+    let main_file = db.new_input_file("main.dada", MAIN_SOURCE.to_string());
+    let library_file = db.new_input_file("library.dada", LIBRARY_SOURCE.to_string());
+    db.set_imported_files(main_file, vec![library_file]);
+    assert!(db.diagnostics(main_file).is_empty());
+    assert!(db.diagnostics(library_file).is_empty());
+    let bir = db.main_function(main_file).expect("no main function");
+
+    let mut kernel = BufferKernel::new();
+    let diagnostic = runtime_error(&db, bir, &mut kernel).await;
+    assert_eq!(kernel.buffer(), "22\n");
+    assert_eq!(diagnostic.message, "divide by zero");
+
+    // The error is in the library, at `a / b`...
+    assert_eq!(diagnostic.span.input_file, library_file);
+    let start = usize::from(diagnostic.span.start);
+    let end = usize::from(diagnostic.span.end);
+    assert_eq!(&LIBRARY_SOURCE[start..end], "a / b");
+
+    // ...and was called from `main`, in the main file.
+    let caller = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.message == "called from `main`")
+        .expect("no label for the caller");
+    assert_eq!(caller.span.input_file, main_file);
+    Ok(())
+}
+
+#[test]
+fn unimported_function_is_not_visible() {
+    let mut db = dada_db::Db::default();
+    let main_file = db.new_input_file("main.dada", MAIN_SOURCE.to_string());
+    db.new_input_file("library.dada", LIBRARY_SOURCE.to_string());
+    assert!(!db.diagnostics(main_file).is_empty());
+}
@@ -0,0 +1,18 @@
+mod common;
+
+use common::compile_main;
+
+#[test]
+fn named_locals_skip_temporaries() {
+    let (db, _, bir) = compile_main("named_locals.dada", "let a = 22\nlet b = a + 1\n");
+    let bir_data = bir.data(&db);
+
+    let names: Vec<&str> = bir_data
+        .named_locals()
+        .map(|(_, name)| name.as_str(&db))
+        .collect();
+    assert_eq!(names, vec!["a", "b"]);
+
+    // `a + 1` is stored in a temporary, which is skipped.
+    assert!(bir_data.max_local_variable().iter().count() > names.len());
+}
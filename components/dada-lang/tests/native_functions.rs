@@ -0,0 +1,55 @@
+mod common;
+
+use common::db_with_file;
+use dada_execute::kernel::BufferKernel;
+
+const SOURCE: &str = "\
+async fn main() {
+    print(host_add(20, 22)).await
+}
+";
+
+fn host_add_kernel() -> BufferKernel {
+    BufferKernel::new().native_function("host_add", |arguments| {
+        let [a, b] = arguments else {
+            eyre::bail!("expected 2 arguments, found {}", arguments.len());
+        };
+        match (a.as_u64(), b.as_u64()) {
+            (Some(a), Some(b)) => Ok(serde_json::json!(a + b)),
+            _ => eyre::bail!("expected integers"),
+        }
+    })
+}
+
+#[tokio::test]
+async fn call_native_function() -> eyre::Result<()> {
+    let (mut db, input_file) = db_with_file("native_functions.dada", SOURCE);
+    db.set_native_functions(input_file, &["host_add"]);
+    assert!(db.diagnostics(input_file).is_empty());
+    let bir = db.main_function(input_file).expect("no main function");
+
+    let mut kernel = host_add_kernel();
+    kernel.interpret(&db, bir, vec![]).await?;
+    assert_eq!(kernel.buffer(), "42\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn undeclared_native_function_is_an_error() -> eyre::Result<()> {
+    // The kernel provides `host_add`, but the file does not declare it,
+    // so Dada code cannot see it.
+    let (db, input_file) = db_with_file("native_functions.dada", SOURCE);
+    assert!(!db.diagnostics(input_file).is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn unregistered_native_function_fails_at_runtime() -> eyre::Result<()> {
+    let (mut db, input_file) = db_with_file("native_functions.dada", SOURCE);
+    db.set_native_functions(input_file, &["host_add"]);
+    let bir = db.main_function(input_file).expect("no main function");
+
+    let mut kernel = BufferKernel::new();
+    assert!(kernel.interpret(&db, bir, vec![]).await.is_err());
+    Ok(())
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::{assigned_exprs, compile_main};
+use dada_ir::code::bir::ExprData;
+
+const SOURCE: &str = "\
+async fn main() {
+    let five = 5
+    let folded = -5
+    let unfolded = -five
+    print(folded == unfolded).await
+    print(folded == 0i - 5i).await
+    print(folded).await
+}
+";
+
+#[test]
+fn negated_literals_are_folded() {
+    let (db, _, bir) = compile_main("negative_literals.dada", SOURCE);
+    let assigned = assigned_exprs(bir.data(&db));
+
+    // `-5` is brewed as a single literal...
+    assert!(assigned.contains(&&ExprData::SignedIntegerLiteral(-5)));
+
+    // ...whereas `-five` still negates at runtime.
+    let unary_count = assigned
+        .iter()
+        .filter(|expr| matches!(expr, ExprData::Unary(..)))
+        .count();
+    assert_eq!(unary_count, 1);
+}
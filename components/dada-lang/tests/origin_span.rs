@@ -0,0 +1,58 @@
+mod common;
+
+use common::compile_main;
+use dada_ir::{
+    code::bir::{ActionData, ControlPointData, Expr, ExprData, Place, PlaceData},
+    span::FileSpan,
+};
+
+const SOURCE: &str = "let x = 22\nlet y = x.give\n";
+
+/// Returns the text of `SOURCE` covered by `span`.
+fn text(span: FileSpan) -> &'static str {
+    &SOURCE[usize::from(span.start)..usize::from(span.end)]
+}
+
+/// Returns the expressions assigned by the statements of `bir`.
+fn assigned_exprs(db: &dada_db::Db, bir: dada_ir::code::bir::Bir) -> Vec<Expr> {
+    let bir_data = bir.data(db);
+    bir_data
+        .control_points()
+        .into_iter()
+        .filter_map(|cp| match &bir_data.tables[cp] {
+            ControlPointData::Statement(statement) => match statement.action {
+                ActionData::AssignExpr(_, expr) => Some(expr),
+                _ => None,
+            },
+            ControlPointData::Terminator(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn origin_span_of_expr() {
+    let (db, _, bir) = compile_main("origin_span.dada", SOURCE);
+    let tables = &bir.data(&db).tables;
+
+    let literal = assigned_exprs(&db, bir)
+        .into_iter()
+        .find(|&expr| tables[expr] == ExprData::IntegerLiteral(22))
+        .expect("no integer literal");
+    assert_eq!(text(bir.origin_span(&db, literal)), "22");
+}
+
+#[test]
+fn origin_span_of_place() {
+    let (db, _, bir) = compile_main("origin_span.dada", SOURCE);
+    let tables = &bir.data(&db).tables;
+
+    let place: Place = assigned_exprs(&db, bir)
+        .into_iter()
+        .find_map(|expr| match tables[expr] {
+            ExprData::Give(place) => Some(place),
+            _ => None,
+        })
+        .expect("no give expression");
+    assert!(matches!(tables[place], PlaceData::LocalVariable(_)));
+    assert_eq!(text(bir.origin_span(&db, place)), "x");
+}
@@ -0,0 +1,47 @@
+mod common;
+
+const IF_ELSE_SOURCE: &str = "\
+async fn main() {
+    let c = true
+    let x = if c { 1 } else { 2 }
+    print(x).await
+}
+";
+
+#[test]
+fn if_else_pseudocode() {
+    let (db, _, bir) = common::compile_main("pseudocode.dada", IF_ELSE_SOURCE);
+
+    // The `if` branches to two blocks that both rejoin at `block3`; the call
+    // and the await each end a block, with the temporaries they used cleared
+    // at the start of the next one.
+    assert_eq!(
+        bir.to_pseudocode(&db),
+        "\
+block0:
+    c{0} := true
+    temp{3} := c{0}.share
+    if temp{3} goto block1 else block2
+block1:
+    x{1} := 1
+    goto block3
+block2:
+    x{1} := 2
+    goto block3
+block3:
+    clear temp{3}
+    temp{6} := Print.share
+    temp{7} := x{1}.share
+    temp{5} := temp{6}(temp{7}); goto block4
+block4:
+    clear temp{7}
+    clear temp{6}
+    temp{4} := temp{5}.await; goto block5
+block5:
+    clear temp{5}
+    clear temp{4}
+    temp{2} := ()
+    return temp{2}
+"
+    );
+}
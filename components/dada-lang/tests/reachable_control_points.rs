@@ -0,0 +1,22 @@
+mod common;
+
+use common::db_with_file;
+use dada_ir::code::bir::reachable_control_points;
+
+#[test]
+fn memoized_until_the_source_changes() {
+    let (mut db, input_file) = db_with_file("reachable.dada", "let x = 22\n");
+
+    let bir = db.main_function(input_file).expect("no main function");
+    let first = reachable_control_points(&db, bir);
+    let second = reachable_control_points(&db, bir);
+    assert!(std::ptr::eq(first, second));
+    assert_eq!(*first, bir.data(&db).control_points());
+    let num_points = first.len();
+
+    input_file.set_source_text(&mut db, "let x = 22\nlet y = x + 1\n".to_string());
+    let bir = db.main_function(input_file).expect("no main function");
+    let third = reachable_control_points(&db, bir);
+    assert_eq!(*third, bir.data(&db).control_points());
+    assert!(third.len() > num_points);
+}
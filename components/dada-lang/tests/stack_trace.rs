@@ -0,0 +1,33 @@
+mod common;
+
+use common::{db_with_file, runtime_error};
+use dada_execute::kernel::BufferKernel;
+
+const SOURCE: &str = "\
+async fn helper() {
+    break
+}
+
+async fn main() {
+    helper().await
+}
+";
+
+#[tokio::test]
+async fn error_labels_every_frame() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("stack_trace.dada", SOURCE);
+    let bir = db.main_function(input_file).expect("no main function");
+    let diagnostic = runtime_error(&db, bir, &mut BufferKernel::new()).await;
+    assert_eq!(diagnostic.message, "compilation error encountered 😢");
+
+    let labels: Vec<&str> = diagnostic
+        .labels
+        .iter()
+        .map(|label| label.message.as_str())
+        .collect();
+    assert_eq!(labels, vec!["in `helper`", "called from `main`"]);
+
+    // The caller's label points at the call site, in `main`.
+    assert!(diagnostic.labels[1].span.start > diagnostic.labels[0].span.end);
+    Ok(())
+}
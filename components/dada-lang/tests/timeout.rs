@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+mod common;
+
+use common::{compile_main, runtime_error};
+use dada_execute::kernel::BufferKernel;
+
+const AWAITING_SOURCE: &str = "\
+async fn greet(name) {
+    print(\"hello, {name}\").await
+}
+
+async fn main() {
+    greet(\"world\").await
+    greet(\"dada\").await
+}
+";
+
+const LOOPING_SOURCE: &str = "\
+async fn main() {
+    let i = 0
+    loop {
+        i += 1
+    }
+}
+";
+
+#[tokio::test]
+async fn awaiting_program_completes_within_timeout() -> eyre::Result<()> {
+    let (db, _, bir) = compile_main("timeout.dada", AWAITING_SOURCE);
+
+    let mut kernel = BufferKernel::new().timeout(Some(Duration::from_secs(60)));
+    kernel.interpret(&db, bir, vec![]).await?;
+    assert_eq!(kernel.buffer(), "hello, world\nhello, dada\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn infinite_loop_hits_timeout() {
+    let (db, input_file, bir) = compile_main("timeout.dada", LOOPING_SOURCE);
+
+    let mut kernel = BufferKernel::new().timeout(Some(Duration::from_millis(100)));
+    let diagnostic = runtime_error(&db, bir, &mut kernel).await;
+    assert_eq!(diagnostic.message, "time limit of 100ms exceeded");
+    assert_eq!(diagnostic.span.input_file, input_file);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::{db_with_file, runtime_error};
+use dada_execute::kernel::BufferKernel;
+
+const SOURCE: &str = "\
+class Point(x, y)
+
+async fn main() {
+    let p = Point(22, 44)
+    let q = p.give
+    print(p).await
+}
+";
+
+#[tokio::test]
+async fn use_after_give_points_at_the_give() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("use_after_give.dada", SOURCE);
+    let bir = db.main_function(input_file).expect("no main function");
+    let diagnostic = runtime_error(&db, bir, &mut BufferKernel::new()).await;
+    assert_eq!(
+        diagnostic.message,
+        "your lease to this object was cancelled"
+    );
+
+    let labels: Vec<(&str, &str)> = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            let text = &SOURCE[usize::from(label.span.start)..usize::from(label.span.end)];
+            (text, label.message.as_str())
+        })
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            ("p", "cancelled lease used here"),
+            ("p.give", "value was given away here"),
+        ]
+    );
+    Ok(())
+}
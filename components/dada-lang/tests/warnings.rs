@@ -0,0 +1,59 @@
+mod common;
+
+use common::db_with_file;
+use dada_execute::kernel::BufferKernel;
+
+/// The `print` after the `return` is unreachable, which is only a warning.
+const WARNING_SOURCE: &str = "\
+async fn main() {
+    print(22).await
+    return
+    print(44).await
+}
+";
+
+/// Assigning to a function is an error.
+const ERROR_SOURCE: &str = "\
+async fn main() {
+    print(22).await
+    main = 44
+    print(44).await
+}
+";
+
+#[tokio::test]
+async fn warnings_do_not_stop_execution() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("warnings.dada", WARNING_SOURCE);
+
+    let mut diagnostics = db.diagnostics_by_severity(input_file);
+    for item in db.items(input_file) {
+        diagnostics.extend(db.unreachable_code_warnings(item));
+    }
+    assert!(!diagnostics.has_errors());
+    assert!(!diagnostics.warnings.is_empty());
+    assert!(diagnostics
+        .warnings
+        .iter()
+        .all(|warning| warning.message == "unreachable code"));
+
+    let bir = db.main_function(input_file).expect("no main function");
+    let mut kernel = BufferKernel::new();
+    kernel.interpret(&db, bir, vec![]).await?;
+    assert_eq!(kernel.buffer(), "22\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn errors_stop_execution() -> eyre::Result<()> {
+    let (db, input_file) = db_with_file("errors.dada", ERROR_SOURCE);
+
+    let diagnostics = db.diagnostics_by_severity(input_file);
+    assert_eq!(diagnostics.errors.len(), 1);
+    assert!(diagnostics.warnings.is_empty());
+
+    let bir = db.main_function(input_file).expect("no main function");
+    let mut kernel = BufferKernel::new();
+    assert!(kernel.interpret(&db, bir, vec![]).await.is_err());
+    assert_eq!(kernel.buffer(), "22\n");
+    Ok(())
+}
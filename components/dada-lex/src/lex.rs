@@ -1,4 +1,6 @@
-use dada_ir::format_string::{FormatString, FormatStringSection, FormatStringSectionData};
+use dada_ir::format_string::{
+    FormatSpec, FormatString, FormatStringSection, FormatStringSectionData,
+};
 use dada_ir::input_file::InputFile;
 use dada_ir::span::{FileSpan, Offset, Span};
 use dada_ir::token::Token;
@@ -35,7 +37,7 @@ fn lex_text(
         chars,
         file_len: start_offset + source_text.len(),
     };
-    lexer.lex_tokens(None)
+    lexer.lex_tokens(&[])
 }
 
 #[track_caller]
@@ -50,7 +52,7 @@ pub fn closing_delimiter(ch: char) -> char {
 
 macro_rules! op {
     () => {
-        '+' | '-' | '/' | '*' | '>' | '<' | '&' | '|' | '.' | ':' | ';' | '='
+        '+' | '-' | '/' | '*' | '>' | '<' | '&' | '|' | '.' | ':' | ';' | '=' | '!' | '%' | '^'
     };
 }
 
@@ -69,7 +71,7 @@ where
     I: Iterator<Item = (usize, char)>,
 {
     #[tracing::instrument(level = "debug", skip(self))]
-    fn lex_tokens(&mut self, end_ch: Option<char>) -> TokenTree {
+    fn lex_tokens(&mut self, end_chs: &[char]) -> TokenTree {
         let mut tokens = vec![];
         let mut push_token = |t: Token| {
             tracing::debug!("push token: {:?}", t);
@@ -81,7 +83,7 @@ where
             start_pos = start_pos.min(pos);
             end_pos = end_pos.max(pos);
 
-            if Some(ch) == end_ch {
+            if end_chs.contains(&ch) {
                 break;
             }
 
@@ -91,7 +93,7 @@ where
                 '(' | '[' | '{' => {
                     push_token(Token::Delimiter(ch));
                     let closing_ch = closing_delimiter(ch);
-                    let tree = self.lex_tokens(Some(closing_ch));
+                    let tree = self.lex_tokens(&[closing_ch]);
                     push_token(Token::Tree(tree));
 
                     if let Some((_, next_ch)) = self.chars.peek() {
@@ -137,6 +139,9 @@ where
                 '"' => {
                     push_token(Token::FormatString(self.string_literal(Offset::from(pos))));
                 }
+                '\'' => {
+                    push_token(Token::CharLiteral(self.char_literal(Offset::from(pos))));
+                }
                 _ => {
                     if !ch.is_whitespace() {
                         push_token(Token::Unknown(ch));
@@ -191,6 +196,35 @@ where
         Word::intern(self.db, string)
     }
 
+    /// Invoked after consuming a `'`; accumulates the text of the character
+    /// literal up to and including the closing `'`.
+    fn char_literal(&mut self, start: Offset) -> Word {
+        let mut string = String::from("'");
+        let mut is_backslash_previous = false;
+        while let Some(&(_, ch)) = self.chars.peek() {
+            if ch == '\n' {
+                break;
+            }
+
+            string.push(ch);
+            self.chars.next();
+
+            if ch == '\'' && !is_backslash_previous {
+                return Word::intern(self.db, string);
+            }
+
+            is_backslash_previous = ch == '\\' && !is_backslash_previous;
+        }
+
+        let end = Offset::from(self.peek_offset());
+        dada_ir::error!(
+            Span { start, end }.anchor_to(self.db, self.input_file),
+            "character literal missing closing `'`"
+        )
+        .emit(self.db);
+        Word::intern(self.db, string)
+    }
+
     /// Invoked after consuming a `"`
     fn string_literal(&mut self, start: Offset) -> FormatString {
         let mut buffer = StringFormatBuffer::new(self.db);
@@ -203,9 +237,17 @@ where
             }
 
             if ch == '{' && !is_backslash_previous {
-                // Format string! Grab a token tree.
-                let tree = self.lex_tokens(Some('}'));
-                buffer.push_tree(tree);
+                // Format string! Grab a token tree, and then the format
+                // specifier that follows a `:` (if any).
+                let tree = self.lex_tokens(&['}', ':']);
+                let spec = match self.chars.peek() {
+                    Some(&(colon_offset, ':')) => {
+                        self.chars.next();
+                        self.format_spec(Offset::from(colon_offset + 1))
+                    }
+                    _ => None,
+                };
+                buffer.push_tree(tree, spec);
 
                 if let Some(&(_, '}')) = self.chars.peek() {
                     self.chars.next();
@@ -245,6 +287,30 @@ where
 
         FormatString::new(self.db, end - start, buffer.sections)
     }
+
+    /// Invoked after consuming the `:` in a format string's code section;
+    /// consumes the specifier up to (but not including) the closing `}`.
+    fn format_spec(&mut self, start: Offset) -> Option<FormatSpec> {
+        let mut text = String::new();
+        while let Some(&(_, ch)) = self.chars.peek() {
+            if ch == '}' || ch == '"' {
+                break;
+            }
+            text.push(ch);
+            self.chars.next();
+        }
+
+        let spec = FormatSpec::parse(&text);
+        if spec.is_none() {
+            let end = Offset::from(self.peek_offset());
+            dada_ir::error!(
+                Span { start, end }.anchor_to(self.db, self.input_file),
+                "invalid format specifier `{text}`"
+            )
+            .emit(self.db);
+        }
+        spec
+    }
 }
 
 struct StringFormatBuffer<'me> {
@@ -266,11 +332,11 @@ impl<'me> StringFormatBuffer<'me> {
         self.text.push(ch);
     }
 
-    fn push_tree(&mut self, token_tree: TokenTree) {
+    fn push_tree(&mut self, token_tree: TokenTree, spec: Option<FormatSpec>) {
         self.flush_text();
         self.sections.push(FormatStringSection::new(
             self.db,
-            FormatStringSectionData::TokenTree(token_tree),
+            FormatStringSectionData::TokenTree(token_tree, spec),
         ));
     }
 
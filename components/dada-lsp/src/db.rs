@@ -4,7 +4,7 @@ use dada_ir::{input_file::InputFile, span::Offset};
 use lsp_server::Message;
 use lsp_types::{
     notification::PublishDiagnostics, Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity,
-    DidChangeTextDocumentParams, DidOpenTextDocumentParams, Location, Position,
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, Location, NumberOrString, Position,
     PublishDiagnosticsParams, Range, Url,
 };
 use salsa::ParallelDatabase;
@@ -131,7 +131,9 @@ impl DadaLspMethods for dada_db::Db {
         Diagnostic {
             range,
             severity,
-            code: None,
+            code: dada_diagnostic
+                .code
+                .map(|code| NumberOrString::String(code.to_string())),
             source: None,
             message,
             related_information,
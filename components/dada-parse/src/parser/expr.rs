@@ -1,6 +1,6 @@
 use crate::{
     parser::Parser,
-    token_test::{Alphabetic, FormatStringLiteral, Identifier, Number},
+    token_test::{Alphabetic, CharLiteral, FormatStringLiteral, Identifier, Number},
 };
 
 use dada_ir::{
@@ -8,7 +8,7 @@ use dada_ir::{
         syntax::op::Op,
         syntax::{Expr, ExprData, LocalVariableDeclData, Name, NameData, NamedExpr, NamedExprData},
     },
-    format_string::FormatStringSectionData,
+    format_string::{FormatSpec, FormatStringSectionData},
     kw::Keyword,
     span::Span,
     token::Token,
@@ -125,11 +125,11 @@ impl CodeParser<'_, '_> {
             }
         }
 
-        self.parse_expr_6()
+        self.parse_expr_10()
     }
 
-    fn parse_expr_6(&mut self) -> Option<Expr> {
-        let mut expr = self.parse_expr_5()?;
+    fn parse_expr_10(&mut self) -> Option<Expr> {
+        let mut expr = self.parse_expr_9()?;
 
         loop {
             if let Some(expr1) = self.parse_binop(
@@ -141,7 +141,7 @@ impl CodeParser<'_, '_> {
                     Op::TimesEqual,
                     Op::Equal,
                 ],
-                Self::parse_expr_5,
+                Self::parse_expr_9,
             ) {
                 expr = expr1;
                 continue;
@@ -153,20 +153,51 @@ impl CodeParser<'_, '_> {
         Some(expr)
     }
 
-    fn parse_expr_5(&mut self) -> Option<Expr> {
-        let mut expr = self.parse_expr_4()?;
+    fn parse_expr_9(&mut self) -> Option<Expr> {
+        let mut expr = self.parse_expr_8()?;
+
+        loop {
+            if let Some(expr1) = self.parse_binop(expr, &[Op::OrOr], Self::parse_expr_8) {
+                expr = expr1;
+                continue;
+            }
+
+            break;
+        }
+
+        Some(expr)
+    }
+
+    fn parse_expr_8(&mut self) -> Option<Expr> {
+        let mut expr = self.parse_expr_7()?;
+
+        loop {
+            if let Some(expr1) = self.parse_binop(expr, &[Op::AndAnd], Self::parse_expr_7) {
+                expr = expr1;
+                continue;
+            }
+
+            break;
+        }
+
+        Some(expr)
+    }
+
+    fn parse_expr_7(&mut self) -> Option<Expr> {
+        let mut expr = self.parse_expr_6()?;
 
         loop {
             if let Some(expr1) = self.parse_binop(
                 expr,
                 &[
                     Op::EqualEqual,
+                    Op::NotEqual,
                     Op::LessThan,
                     Op::GreaterThan,
                     Op::GreaterEqual,
                     Op::LessEqual,
                 ],
-                Self::parse_expr_4,
+                Self::parse_expr_6,
             ) {
                 expr = expr1;
                 continue;
@@ -178,6 +209,42 @@ impl CodeParser<'_, '_> {
         Some(expr)
     }
 
+    fn parse_expr_6(&mut self) -> Option<Expr> {
+        let mut expr = self.parse_expr_5()?;
+
+        loop {
+            if let Some(expr1) = self.parse_binop(
+                expr,
+                &[Op::BitAnd, Op::BitOr, Op::BitXor],
+                Self::parse_expr_5,
+            ) {
+                expr = expr1;
+                continue;
+            }
+
+            break;
+        }
+
+        Some(expr)
+    }
+
+    fn parse_expr_5(&mut self) -> Option<Expr> {
+        let mut expr = self.parse_expr_4()?;
+
+        loop {
+            if let Some(expr1) =
+                self.parse_binop(expr, &[Op::ShiftLeft, Op::ShiftRight], Self::parse_expr_4)
+            {
+                expr = expr1;
+                continue;
+            }
+
+            break;
+        }
+
+        Some(expr)
+    }
+
     fn parse_expr_4(&mut self) -> Option<Expr> {
         let mut expr = self.parse_expr_3()?;
 
@@ -198,9 +265,11 @@ impl CodeParser<'_, '_> {
         let mut expr = self.parse_expr_2()?;
 
         loop {
-            if let Some(expr1) =
-                self.parse_binop(expr, &[Op::DividedBy, Op::Times], Self::parse_expr_2)
-            {
+            if let Some(expr1) = self.parse_binop(
+                expr,
+                &[Op::DividedBy, Op::Times, Op::Modulo],
+                Self::parse_expr_2,
+            ) {
                 expr = expr1;
                 continue;
             }
@@ -212,7 +281,7 @@ impl CodeParser<'_, '_> {
     }
 
     fn parse_expr_2(&mut self) -> Option<Expr> {
-        if let Some(expr) = self.parse_unary(&[Op::Minus], Self::parse_expr_2) {
+        if let Some(expr) = self.parse_unary(&[Op::Minus, Op::Not], Self::parse_expr_2) {
             return Some(expr);
         }
         self.parse_expr_1()
@@ -264,6 +333,19 @@ impl CodeParser<'_, '_> {
                 continue;
             }
 
+            if let Some((index_span, token_tree)) = self.delimited('[') {
+                // `base[index]`
+                let index_expr = self.with_sub_parser(token_tree, |sub_parser| {
+                    sub_parser
+                        .parse_only_expr()
+                        .or_report_error(sub_parser, || "expected index expression")
+                        .or_dummy_expr(sub_parser)
+                });
+                let span = self.spans[expr].to(index_span);
+                expr = self.add(ExprData::Index(expr, index_expr), span);
+                continue;
+            }
+
             break;
         }
 
@@ -316,6 +398,8 @@ impl CodeParser<'_, '_> {
                     }
                 }
             }
+        } else if let Some((char_span, word)) = self.eat(CharLiteral) {
+            Some(self.add(ExprData::CharLiteral(word), char_span))
         } else if let Some(expr) = self.parse_format_string() {
             Some(expr)
         } else if let Some(expr) = self.parse_block_expr() {
@@ -326,6 +410,8 @@ impl CodeParser<'_, '_> {
             let span = self.span_consumed_since_parsing(atomic_kw);
             tracing::debug!("atomic");
             Some(self.add(ExprData::Atomic(atomic_kw, body_expr), span))
+        } else if let Some((await_span, _)) = self.eat(Keyword::Await) {
+            self.parse_await_all_expr(await_span)
         } else if let Some((if_span, _)) = self.eat(Keyword::If) {
             self.parse_if_expr(if_span)
         } else if let Some((loop_span, _)) = self.eat(Keyword::Loop) {
@@ -354,11 +440,38 @@ impl CodeParser<'_, '_> {
                 },
                 span,
             ))
+        } else if let Some((span, token_tree)) = self.delimited('[') {
+            let exprs =
+                self.with_sub_parser(token_tree, |subparser| subparser.parse_only_expr_seq());
+            Some(self.add(ExprData::List(exprs), span))
         } else {
             None
         }
     }
 
+    /// Parses `all [a, b, ...]`, having already consumed the leading `await`.
+    fn parse_await_all_expr(&mut self, await_span: Span) -> Option<Expr> {
+        match self.peek(Identifier) {
+            Some(word) if word.as_str(self.db) == "all" => {
+                self.eat(Identifier);
+            }
+            _ => {
+                self.error_at_current_token("expected `all` after `await`")
+                    .emit(self.db);
+                return Some(self.add(ExprData::Error, self.span_consumed_since(await_span)));
+            }
+        }
+
+        let Some((_, token_tree)) = self.delimited('[') else {
+            self.error_at_current_token("expected `[` after `await all`")
+                .emit(self.db);
+            return Some(self.add(ExprData::Error, self.span_consumed_since(await_span)));
+        };
+        let exprs = self.with_sub_parser(token_tree, |subparser| subparser.parse_only_expr_seq());
+        let span = self.span_consumed_since(await_span);
+        Some(self.add(ExprData::AwaitAll(exprs), span))
+    }
+
     /// Parses `let [atomic] x = expr`
     #[tracing::instrument(level = "debug", skip_all)]
     fn parse_local_variable_decl(&mut self) -> Option<Expr> {
@@ -378,7 +491,15 @@ impl CodeParser<'_, '_> {
             .or_dummy_expr(self);
 
         if let Some(name) = name {
-            let local_variable_decl = self.add(LocalVariableDeclData { atomic, name, ty }, lv_span);
+            let local_variable_decl = self.add(
+                LocalVariableDeclData {
+                    atomic,
+                    name,
+                    ty,
+                    default: None,
+                },
+                lv_span,
+            );
 
             let var_span = self.span_consumed_since_parsing(local_variable_decl);
             Some(self.add(
@@ -440,14 +561,16 @@ impl CodeParser<'_, '_> {
     fn parse_format_string(&mut self) -> Option<Expr> {
         let (span, format_string) = self.eat(FormatStringLiteral)?;
 
-        let exprs: Vec<Expr> = format_string
+        let exprs: Vec<(Expr, Option<FormatSpec>)> = format_string
             .sections(self.db)
             .iter()
             .map(|section| match section.data(self.db) {
                 FormatStringSectionData::Text(word) => {
-                    self.add(ExprData::StringLiteral(word), span)
+                    (self.add(ExprData::StringLiteral(word), span), None)
+                }
+                FormatStringSectionData::TokenTree(tree, spec) => {
+                    (self.parse_required_sub_expr(tree), spec)
                 }
-                FormatStringSectionData::TokenTree(tree) => self.parse_required_sub_expr(tree),
             })
             .collect();
 
@@ -119,8 +119,22 @@ impl CodeParser<'_, '_> {
 
         let ty = self.parse_colon_ty();
 
+        // Parse an optional default value (`= expr`).
+        let default = self.eat_op(Op::Equal).and_then(|_| {
+            self.parse_expr()
+                .or_report_error(self, || "expected default value after `=`")
+        });
+
         let span = self.span_consumed_since_parsing(atomic.or_parsing(name));
-        let decl = self.add(LocalVariableDeclData { atomic, name, ty }, span);
+        let decl = self.add(
+            LocalVariableDeclData {
+                atomic,
+                name,
+                ty,
+                default,
+            },
+            span,
+        );
 
         Some(decl)
     }
@@ -101,6 +101,26 @@ impl TokenTest for FormatStringLiteral {
     }
 }
 
+/// A character literal like `'a'`; yields the text between the quotes
+/// (escapes are not yet processed).
+#[derive(Debug)]
+pub(crate) struct CharLiteral;
+impl TokenTest for CharLiteral {
+    type Narrow = Word;
+
+    fn test(self, db: &dyn crate::Db, token: Token, _span: FileSpan) -> Option<Word> {
+        match token {
+            Token::CharLiteral(w) => {
+                // The lexer has already reported an error if the closing quote is missing.
+                let text = &w.as_str(db)[1..];
+                let text = text.strip_suffix('\'').unwrap_or(text);
+                Some(Word::intern(db, text))
+            }
+            _ => None,
+        }
+    }
+}
+
 impl TokenTest for Token {
     type Narrow = Token;
 
@@ -26,6 +26,7 @@ pub(crate) enum Definition<LV> {
     Function(Function),
     Class(Class),
     Intrinsic(Intrinsic),
+    NativeFunction(Word),
 }
 
 impl<LV> Definition<LV> {
@@ -35,6 +36,7 @@ impl<LV> Definition<LV> {
             Definition::Function(_) => "functions",
             Definition::Class(_) => "classes",
             Definition::Intrinsic(_) => "functions",
+            Definition::NativeFunction(_) => "functions",
         }
     }
 
@@ -44,6 +46,7 @@ impl<LV> Definition<LV> {
             Definition::Function(f) => Definition::Function(f),
             Definition::Class(c) => Definition::Class(c),
             Definition::Intrinsic(i) => Definition::Intrinsic(i),
+            Definition::NativeFunction(w) => Definition::NativeFunction(w),
         }
     }
 }
@@ -63,6 +66,7 @@ impl<LV> TryInto<Item> for Definition<LV> {
         match self {
             Definition::LocalVariable(_) => Err(()),
             Definition::Intrinsic(_) => Err(()),
+            Definition::NativeFunction(_) => Err(()),
             Definition::Function(f) => Ok(Item::Function(f)),
             Definition::Class(c) => Ok(Item::Class(c)),
         }
@@ -154,12 +158,27 @@ impl RootDefinitions {
             }
         }
 
+        // Items from imported files are visible too, unless this file defines
+        // something with the same name
+        for &imported_file in input_file.imported_files(db) {
+            for &item in imported_file.items(db) {
+                names
+                    .entry(item.name(db))
+                    .or_insert_with(|| Definition::from(item));
+            }
+        }
+
         // Populate with intrinsics from the prelude (these can be shadowed, so don't error if
         // user generates something with the same name)
         for &intrinsic in Intrinsic::ALL {
             names.insert(intrinsic.name(db), Definition::Intrinsic(intrinsic));
         }
 
+        // Functions provided by the host (these take precedence over intrinsics of the same name)
+        for &name in input_file.native_functions(db) {
+            names.insert(name, Definition::NativeFunction(name));
+        }
+
         RootDefinitions { names }
     }
 }
@@ -7,13 +7,14 @@ use dada_ir::diagnostic::ErrorReported;
 use dada_ir::error;
 use dada_ir::function::{Function, FunctionSignature};
 use dada_ir::signature::{
-    self, ClassStructure, Field, GenericParameter, GenericParameterKind, KnownPermissionKind,
-    ParameterIndex, Permission,
+    self, ClassStructure, DefaultValue, Field, GenericParameter, GenericParameterKind,
+    KnownPermissionKind, ParameterIndex, Permission,
 };
 use dada_ir::span::Anchored;
 use dada_ir::storage::Atomic;
 use dada_ir::word::Words;
 use derive_new::new;
+use std::str::FromStr;
 
 use crate::name_lookup::Definition;
 
@@ -42,10 +43,14 @@ pub(crate) fn validate_class_structure(
         .map(|(lv, input_ty)| {
             let lv_data = lv.data(tables);
             let atomic = Atomic::from(lv_data.atomic);
+            let default = lv_data
+                .default
+                .and_then(|expr| validate_field_default(db, class, class_signature_syntax, expr));
             Field {
                 atomic,
                 name: input_ty.name,
                 ty: input_ty.ty.clone(),
+                default,
             }
         })
         .collect();
@@ -70,7 +75,69 @@ pub(crate) fn validate_function_signature(
             output: None,
         },
 
-        FunctionSignature::Syntax(s) => signature(db, &function, s),
+        FunctionSignature::Syntax(s) => {
+            for parameter in &s.parameters {
+                if let Some(default) = parameter.data(&s.tables).default {
+                    error!(
+                        s.spans[default].anchor_to(db, &function),
+                        "default values are only supported for class fields"
+                    )
+                    .emit(db);
+                }
+            }
+            signature(db, &function, s)
+        }
+    }
+}
+
+/// Computes the default value of a class field from its syntax (e.g., the `0`
+/// in `class Point(x, y = 0)`). Only literals are supported; anything else is
+/// reported as an error and treated as though there were no default.
+fn validate_field_default(
+    db: &dyn crate::Db,
+    class: Class,
+    signature: &syntax::Signature,
+    expr: syntax::Expr,
+) -> Option<DefaultValue> {
+    let span = signature.spans[expr].anchor_to(db, &class);
+    let result = match expr.data(&signature.tables) {
+        syntax::ExprData::BooleanLiteral(b) => Ok(DefaultValue::Bool(*b)),
+        syntax::ExprData::IntegerLiteral(w, suffix) => {
+            let digits: String = w.as_str(db).chars().filter(|&c| c != '_').collect();
+            let invalid =
+                |e: std::num::ParseIntError| format!("`{digits}` is not a valid integer: {e}");
+            match suffix.map(|suffix| suffix.as_str(db)) {
+                None => u64::from_str(&digits)
+                    .map(DefaultValue::Int)
+                    .map_err(invalid),
+                Some("u") => u64::from_str(&digits)
+                    .map(DefaultValue::UnsignedInt)
+                    .map_err(invalid),
+                Some("i") => i64::from_str(&digits)
+                    .map(DefaultValue::SignedInt)
+                    .map_err(invalid),
+                Some(suffix) => Err(format!("`{suffix}` is not a valid integer suffix")),
+            }
+        }
+        syntax::ExprData::FloatLiteral(w_int, w_frac) => {
+            let digits: String = format!("{}.{}", w_int.as_str(db), w_frac.as_str(db))
+                .chars()
+                .filter(|&c| c != '_')
+                .collect();
+            f64::from_str(&digits)
+                .map(|v| DefaultValue::Float(eq_float::F64(v)))
+                .map_err(|e| format!("`{digits}` is not a valid float: {e}"))
+        }
+        syntax::ExprData::Error => return None,
+        _ => Err("default values must be boolean or numeric literals".to_string()),
+    };
+
+    match result {
+        Ok(value) => Some(value),
+        Err(message) => {
+            error!(span, "{message}").emit(db);
+            None
+        }
     }
 }
 
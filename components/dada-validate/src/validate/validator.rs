@@ -17,6 +17,8 @@ use dada_ir::storage::Atomic;
 use dada_ir::word::Word;
 use dada_lex::prelude::*;
 use dada_parse::prelude::*;
+use std::num::IntErrorKind;
+use std::num::ParseIntError;
 use std::rc::Rc;
 use std::str::FromStr;
 
@@ -83,6 +85,31 @@ impl<'me> Validator<'me> {
         (self.effect_span)(self)
     }
 
+    /// Reports an error for an `await` (at `await_span`) in a context whose
+    /// effect does not permit awaiting.
+    fn report_await_not_permitted(&self, await_span: FileSpan) {
+        match self.effect {
+            Effect::Atomic => {
+                dada_ir::error!(await_span, "await is not permitted inside atomic sections")
+                    .primary_label("await is here")
+                    .secondary_label(self.effect_span(), "atomic section entered here")
+                    .emit(self.db);
+            }
+            Effect::Default => {
+                dada_ir::error!(
+                    await_span,
+                    "await is not permitted outside of async functions",
+                )
+                .primary_label("await is here")
+                .secondary_label(self.effect_span(), "fn not declared `async`")
+                .emit(self.db);
+            }
+            Effect::Async => {
+                unreachable!();
+            }
+        }
+    }
+
     fn with_loop_expr(mut self, e: validated::Expr) -> Self {
         self.loop_stack.push(e);
         self
@@ -203,10 +230,11 @@ impl<'me> Validator<'me> {
     fn validate_expr(&mut self, expr: syntax::Expr) -> validated::Expr {
         tracing::trace!("expr.data = {:?}", expr.data(self.syntax_tables));
         match expr.data(self.syntax_tables) {
-            syntax::ExprData::Dot(..) | syntax::ExprData::Id(_) => self
-                .with_expr_validated_as_place(expr, &mut |this, place| {
+            syntax::ExprData::Dot(..) | syntax::ExprData::Id(_) | syntax::ExprData::Index(..) => {
+                self.with_expr_validated_as_place(expr, &mut |this, place| {
                     this.add(validated::ExprData::Share(place), expr)
-                }),
+                })
+            }
 
             syntax::ExprData::BooleanLiteral(b) => {
                 self.add(validated::ExprData::BooleanLiteral(*b), expr)
@@ -219,6 +247,13 @@ impl<'me> Validator<'me> {
                     dada_ir::error!(this.span(expr), "{}", e,).emit(this.db);
                     this.add(validated::ExprData::Error, expr)
                 };
+                let parse_int_error = |this: &mut Validator, e: ParseIntError| {
+                    let message = match e.kind() {
+                        IntErrorKind::PosOverflow => "integer literal too large".to_string(),
+                        _ => format!("`{}` is not a valid integer: {}", &without_underscore, e),
+                    };
+                    parse_error(this, message)
+                };
                 match suffix {
                     Some(suffix) => {
                         let suffix_str = suffix.as_str(self.db);
@@ -227,25 +262,13 @@ impl<'me> Validator<'me> {
                                 Ok(v) => {
                                     self.add(validated::ExprData::UnsignedIntegerLiteral(v), expr)
                                 }
-                                Err(e) => parse_error(
-                                    self,
-                                    format!(
-                                        "`{}` is not a valid integer: {}",
-                                        &without_underscore, e
-                                    ),
-                                ),
+                                Err(e) => parse_int_error(self, e),
                             },
                             "i" => match i64::from_str(&without_underscore) {
                                 Ok(v) => {
                                     self.add(validated::ExprData::SignedIntegerLiteral(v), expr)
                                 }
-                                Err(e) => parse_error(
-                                    self,
-                                    format!(
-                                        "`{}` is not a valid integer: {}",
-                                        &without_underscore, e
-                                    ),
-                                ),
+                                Err(e) => parse_int_error(self, e),
                             },
                             _ => parse_error(
                                 self,
@@ -255,10 +278,7 @@ impl<'me> Validator<'me> {
                     }
                     None => match u64::from_str(&without_underscore) {
                         Ok(v) => self.add(validated::ExprData::IntegerLiteral(v), expr),
-                        Err(e) => parse_error(
-                            self,
-                            format!("`{}` is not a valid integer: {}", &without_underscore, e),
-                        ),
+                        Err(e) => parse_int_error(self, e),
                     },
                 }
             }
@@ -298,38 +318,45 @@ impl<'me> Validator<'me> {
                 self.add(validated::ExprData::StringLiteral(word), expr)
             }
 
+            syntax::ExprData::CharLiteral(word) => {
+                let escaped = self.support_escape(expr, word.as_str(self.db));
+                let mut chars = escaped.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => self.add(validated::ExprData::CharLiteral(ch), expr),
+                    _ => {
+                        dada_ir::error!(
+                            self.span(expr),
+                            "character literal must contain exactly one character"
+                        )
+                        .emit(self.db);
+                        self.add(validated::ExprData::Error, expr)
+                    }
+                }
+            }
+
             syntax::ExprData::Await(future_expr) => {
                 if !self.effect.permits_await() {
                     let await_span = self.span(expr).trailing_keyword(self.db, Keyword::Await);
-                    match self.effect {
-                        Effect::Atomic => {
-                            dada_ir::error!(
-                                await_span,
-                                "await is not permitted inside atomic sections",
-                            )
-                            .primary_label("await is here")
-                            .secondary_label(self.effect_span(), "atomic section entered here")
-                            .emit(self.db);
-                        }
-                        Effect::Default => {
-                            dada_ir::error!(
-                                await_span,
-                                "await is not permitted outside of async functions",
-                            )
-                            .primary_label("await is here")
-                            .secondary_label(self.effect_span(), "fn not declared `async`")
-                            .emit(self.db);
-                        }
-                        Effect::Async => {
-                            unreachable!();
-                        }
-                    }
+                    self.report_await_not_permitted(await_span);
                 }
 
                 let validated_future_expr = self.give_validated_expr(expr, *future_expr);
                 self.add(validated::ExprData::Await(validated_future_expr), expr)
             }
 
+            syntax::ExprData::AwaitAll(future_exprs) => {
+                if !self.effect.permits_await() {
+                    let await_span = self.span(expr).leading_keyword(self.db, Keyword::Await);
+                    self.report_await_not_permitted(await_span);
+                }
+
+                let validated_future_exprs = future_exprs
+                    .iter()
+                    .map(|future_expr| self.give_validated_expr(expr, *future_expr))
+                    .collect();
+                self.add(validated::ExprData::AwaitAll(validated_future_exprs), expr)
+            }
+
             syntax::ExprData::Call(func_expr, named_exprs) => {
                 let validated_func_expr = self.validate_expr(*func_expr);
                 let validated_named_exprs = self.validate_named_exprs(named_exprs);
@@ -393,6 +420,14 @@ impl<'me> Validator<'me> {
                 self.add(validated::ExprData::Tuple(validated_exprs), expr)
             }
 
+            syntax::ExprData::List(element_exprs) => {
+                let validated_exprs = element_exprs
+                    .iter()
+                    .map(|expr| self.validate_expr(*expr))
+                    .collect();
+                self.add(validated::ExprData::List(validated_exprs), expr)
+            }
+
             syntax::ExprData::Concatenate(exprs) => self.concatenate(expr, exprs),
 
             syntax::ExprData::If(condition_expr, then_expr, else_expr) => {
@@ -481,6 +516,10 @@ impl<'me> Validator<'me> {
                 loop_expr
             }
 
+            syntax::ExprData::Op(lhs_expr, op @ (Op::AndAnd | Op::OrOr), rhs_expr) => {
+                self.validate_short_circuit(expr, *lhs_expr, *op, *rhs_expr)
+            }
+
             syntax::ExprData::Op(lhs_expr, op, rhs_expr) => {
                 let validated_lhs_expr = self.validate_expr(*lhs_expr);
                 let validated_rhs_expr = self.validate_expr(*rhs_expr);
@@ -493,7 +532,7 @@ impl<'me> Validator<'me> {
 
             syntax::ExprData::Unary(op, rhs_expr) => {
                 let validated_rhs_expr = self.validate_expr(*rhs_expr);
-                let validated_op = self.validated_op(*op);
+                let validated_op = self.validated_unary_op(*op);
                 self.add(
                     validated::ExprData::Unary(validated_op, validated_rhs_expr),
                     expr,
@@ -665,6 +704,40 @@ impl<'me> Validator<'me> {
         self.seq(Some(temporary_assign_expr), assign_field_expr)
     }
 
+    /// Lowers `a && b` to `if a { b } else { false }` and `a || b` to
+    /// `if a { true } else { b }`, so that `b` is only evaluated when needed.
+    fn validate_short_circuit(
+        &mut self,
+        expr: syntax::Expr,
+        lhs_expr: syntax::Expr,
+        op: Op,
+        rhs_expr: syntax::Expr,
+    ) -> validated::Expr {
+        let validated_lhs_expr = self.validate_expr(lhs_expr);
+        let validated_rhs_expr = self.subscope().validate_expr_and_exit(rhs_expr);
+        let (then_expr, else_expr) = match op {
+            Op::AndAnd => {
+                let false_expr = self.add(
+                    validated::ExprData::BooleanLiteral(false),
+                    expr.synthesized(),
+                );
+                (validated_rhs_expr, false_expr)
+            }
+            Op::OrOr => {
+                let true_expr = self.add(
+                    validated::ExprData::BooleanLiteral(true),
+                    expr.synthesized(),
+                );
+                (true_expr, validated_rhs_expr)
+            }
+            _ => unreachable!("`{op}` does not short-circuit"),
+        };
+        self.add(
+            validated::ExprData::If(validated_lhs_expr, then_expr, else_expr),
+            expr,
+        )
+    }
+
     fn with_expr_validated_as_target_place(
         &mut self,
         expr: syntax::Expr,
@@ -689,7 +762,8 @@ impl<'me> Validator<'me> {
 
                 Some(definition @ Definition::Function(_))
                 | Some(definition @ Definition::Class(_))
-                | Some(definition @ Definition::Intrinsic(_)) => {
+                | Some(definition @ Definition::Intrinsic(_))
+                | Some(definition @ Definition::NativeFunction(_)) => {
                     let ErrorReported = dada_ir::error!(
                         self.span(expr),
                         "you can only assign to local variables or fields, not {} like `{}`",
@@ -784,7 +858,9 @@ impl<'me> Validator<'me> {
 
     fn is_place_expression(&self, expr: syntax::Expr) -> bool {
         match expr.data(self.syntax_tables) {
-            syntax::ExprData::Id(_) | syntax::ExprData::Dot(..) => true,
+            syntax::ExprData::Id(_) | syntax::ExprData::Dot(..) | syntax::ExprData::Index(..) => {
+                true
+            }
             syntax::ExprData::Parenthesized(parenthesized_expr) => {
                 self.is_place_expression(*parenthesized_expr)
             }
@@ -815,6 +891,9 @@ impl<'me> Validator<'me> {
                     Some(Definition::Intrinsic(i)) => {
                         self.add(validated::PlaceData::Intrinsic(i), expr)
                     }
+                    Some(Definition::NativeFunction(name)) => {
+                        self.add(validated::PlaceData::NativeFunction(name), expr)
+                    }
                     None => {
                         let ErrorReported = dada_ir::error!(
                             self.span(expr),
@@ -833,6 +912,15 @@ impl<'me> Validator<'me> {
                     op(this, dot_place)
                 })
             }
+            syntax::ExprData::Index(owner_expr, index_expr) => {
+                self.with_expr_validated_as_place(*owner_expr, &mut |this, owner_place| {
+                    let (assign_expr, index_place) = this.validate_expr_in_temporary(*index_expr);
+                    let index_place =
+                        this.add(validated::PlaceData::Index(owner_place, index_place), expr);
+                    let expr = op(this, index_place);
+                    this.seq(Some(assign_expr), expr)
+                })
+            }
             syntax::ExprData::Parenthesized(parenthesized_expr) => {
                 self.with_expr_validated_as_place(*parenthesized_expr, op)
             }
@@ -947,14 +1035,24 @@ impl<'me> Validator<'me> {
 
             // Binops
             syntax::op::Op::EqualEqual => validated::op::Op::EqualEqual,
+            syntax::op::Op::NotEqual => validated::op::Op::NotEqual,
             syntax::op::Op::GreaterEqual => validated::op::Op::GreaterEqual,
             syntax::op::Op::LessEqual => validated::op::Op::LessEqual,
             syntax::op::Op::Plus => validated::op::Op::Plus,
             syntax::op::Op::Minus => validated::op::Op::Minus,
             syntax::op::Op::Times => validated::op::Op::Times,
             syntax::op::Op::DividedBy => validated::op::Op::DividedBy,
+            syntax::op::Op::Modulo => validated::op::Op::Modulo,
             syntax::op::Op::LessThan => validated::op::Op::LessThan,
             syntax::op::Op::GreaterThan => validated::op::Op::GreaterThan,
+            syntax::op::Op::BitAnd => validated::op::Op::BitAnd,
+            syntax::op::Op::BitOr => validated::op::Op::BitOr,
+            syntax::op::Op::BitXor => validated::op::Op::BitXor,
+            syntax::op::Op::ShiftLeft => validated::op::Op::ShiftLeft,
+            syntax::op::Op::ShiftRight => validated::op::Op::ShiftRight,
+
+            // Only appears as a unary op, see `validated_unary_op`.
+            syntax::op::Op::Not => unreachable!("unexpected op"),
 
             // These are parsed into other syntax elements and should not appear
             // at this stage of compilation.
@@ -967,6 +1065,19 @@ impl<'me> Validator<'me> {
             | syntax::op::Op::RightArrow => {
                 unreachable!("unexpected op")
             }
+
+            // Short-circuiting ops are lowered to `if` by `validate_short_circuit`.
+            syntax::op::Op::AndAnd | syntax::op::Op::OrOr => {
+                unreachable!("unexpected op")
+            }
+        }
+    }
+
+    fn validated_unary_op(&self, op: syntax::op::Op) -> validated::op::UnaryOp {
+        match op {
+            syntax::op::Op::Minus => validated::op::UnaryOp::Minus,
+            syntax::op::Op::Not => validated::op::UnaryOp::Not,
+            _ => unreachable!("unexpected unary op `{op}`"),
         }
     }
 
@@ -976,6 +1087,11 @@ impl<'me> Validator<'me> {
         lhs_expr: syntax::Expr,
         initializer_expr: syntax::Expr,
     ) -> validated::Expr {
+        if let Some(element_exprs) = self.tuple_target_elements(lhs_expr) {
+            let validated_expr = self.validate_expr(initializer_expr);
+            return self.validate_tuple_assign(assign_expr, element_exprs, validated_expr);
+        }
+
         self.with_expr_validated_as_target_place(lhs_expr, &mut |this, target_place| {
             let validated_expr = this.validate_expr(initializer_expr);
             this.add(
@@ -984,6 +1100,72 @@ impl<'me> Validator<'me> {
             )
         })
     }
+
+    /// If `lhs_expr` is a tuple like `(a, b)` (possibly parenthesized),
+    /// returns its elements.
+    fn tuple_target_elements(&self, lhs_expr: syntax::Expr) -> Option<Vec<syntax::Expr>> {
+        match lhs_expr.data(self.syntax_tables) {
+            syntax::ExprData::Tuple(element_exprs) => Some(element_exprs.clone()),
+            syntax::ExprData::Parenthesized(target_expr) => {
+                self.tuple_target_elements(*target_expr)
+            }
+            _ => None,
+        }
+    }
+
+    fn validate_tuple_assign(
+        &mut self,
+        assign_expr: syntax::Expr,
+        element_exprs: Vec<syntax::Expr>,
+        validated_expr: validated::Expr,
+    ) -> validated::Expr {
+        // if user wrote `(a, b) = <rhs>`, we generate
+        //
+        // {
+        //     temp_value = <rhs>
+        //     a = temp_value.0.give
+        //     b = temp_value.1.give
+        // }
+        //
+        // Giving each element moves it out of the tuple if the tuple is
+        // owned, and copies it if the tuple is shared. Elements that are
+        // themselves tuples, like `(a, (b, c)) = <rhs>`, are destructured
+        // recursively.
+        let (temporary_assign_expr, temporary_place) =
+            self.store_validated_expr_in_temporary(validated_expr);
+
+        let mut assign_exprs = vec![temporary_assign_expr];
+        for (index, element_expr) in element_exprs.into_iter().enumerate() {
+            // `temp_value.N.give`
+            let element_place = self.add(
+                validated::PlaceData::Dot(temporary_place, Word::intern(self.db, index)),
+                element_expr.synthesized(),
+            );
+            let element_give = self.add(
+                validated::ExprData::Give(element_place),
+                element_expr.synthesized(),
+            );
+
+            let assign_element_expr = match self.tuple_target_elements(element_expr) {
+                Some(nested_exprs) => {
+                    self.validate_tuple_assign(element_expr, nested_exprs, element_give)
+                }
+                None => self.with_expr_validated_as_target_place(
+                    element_expr,
+                    &mut |this, target_place| {
+                        this.add(
+                            validated::ExprData::Assign(target_place, element_give),
+                            element_expr.synthesized(),
+                        )
+                    },
+                ),
+            };
+            assign_exprs.push(assign_element_expr);
+        }
+
+        let unit = self.empty_tuple(assign_expr);
+        self.seq(assign_exprs, unit)
+    }
 }
 
 fn count_bytes_in_common(s1: &[u8], s2: &[u8]) -> usize {
@@ -1,3 +1,5 @@
+use dada_ir::format_string::FormatSpec;
+
 use super::*;
 
 impl Validator<'_> {
@@ -5,12 +7,15 @@ impl Validator<'_> {
     pub(super) fn concatenate(
         &mut self,
         concatenate_expr: syntax::Expr,
-        exprs: &[syntax::Expr],
+        exprs: &[(syntax::Expr, Option<FormatSpec>)],
     ) -> validated::Expr {
         // See https://dada-lang.org/docs/reference/string-literals for full details.
 
         let validated_exprs = if !self.should_strip_margin(exprs) {
-            exprs.iter().map(|expr| self.validate_expr(*expr)).collect()
+            exprs
+                .iter()
+                .map(|&(expr, spec)| (self.validate_expr(expr), spec))
+                .collect()
         } else {
             self.strip_margin_from_exprs(exprs)
         };
@@ -28,7 +33,7 @@ impl Validator<'_> {
             if ch == '\\' {
                 if let Some(c) = chars.peek() {
                     match c {
-                        'n' | 'r' | 't' | '"' | '\\' | '{' | '}' => {
+                        'n' | 'r' | 't' | '"' | '\'' | '\\' | '{' | '}' => {
                             buffer.push(escape(*c));
                             chars.next();
                             continue;
@@ -56,6 +61,7 @@ impl Validator<'_> {
                 'r' => '\r',
                 '\\' => '\\',
                 '"' => '\"',
+                '\'' => '\'',
                 '{' => '{',
                 '}' => '}',
                 _ => panic!("not a escape: {ch:?}"),
@@ -65,12 +71,12 @@ impl Validator<'_> {
 
     /// If the initial string begins with a literal (not escaped) newline, we
     /// need to strip margin. Otherwise, we do nothing but modify escapes.
-    fn should_strip_margin(&self, exprs: &[syntax::Expr]) -> bool {
+    fn should_strip_margin(&self, exprs: &[(syntax::Expr, Option<FormatSpec>)]) -> bool {
         if exprs.is_empty() {
             return false;
         }
 
-        if let syntax::ExprData::StringLiteral(word) = exprs[0].data(self.syntax_tables) {
+        if let syntax::ExprData::StringLiteral(word) = exprs[0].0.data(self.syntax_tables) {
             let word_str = word.as_str(self.db);
             word_str.starts_with('\n')
         } else {
@@ -78,11 +84,14 @@ impl Validator<'_> {
         }
     }
 
-    fn strip_margin_from_exprs(&mut self, exprs: &[syntax::Expr]) -> Vec<validated::Expr> {
+    fn strip_margin_from_exprs(
+        &mut self,
+        exprs: &[(syntax::Expr, Option<FormatSpec>)],
+    ) -> Vec<(validated::Expr, Option<FormatSpec>)> {
         let margin = self.compute_margin(exprs);
 
         let mut validated_exprs = Vec::with_capacity(exprs.len());
-        for (expr, index) in exprs.iter().zip(0..) {
+        for ((expr, spec), index) in exprs.iter().zip(0..) {
             if let syntax::ExprData::StringLiteral(word) = expr.data(self.syntax_tables) {
                 let word_str = word.as_str(self.db);
                 let without_margin = self.strip_margin_from_str(margin, word_str);
@@ -114,9 +123,10 @@ impl Validator<'_> {
                 // Finally, expand escapes.
                 let escaped = self.support_escape(*expr, without_margin);
                 let word = Word::intern(self.db, escaped);
-                validated_exprs.push(self.add(validated::ExprData::StringLiteral(word), *expr));
+                let validated_expr = self.add(validated::ExprData::StringLiteral(word), *expr);
+                validated_exprs.push((validated_expr, *spec));
             } else {
-                validated_exprs.push(self.validate_expr(*expr));
+                validated_exprs.push((self.validate_expr(*expr), *spec));
             }
         }
         validated_exprs
@@ -126,9 +136,9 @@ impl Validator<'_> {
     /// every (non-empty) line. Dada format string literals automatically strip
     /// the "margin", which is common whitespace that appears at the start of
     /// every line (ignoring empty lines).
-    fn compute_margin(&self, exprs: &[syntax::Expr]) -> usize {
+    fn compute_margin(&self, exprs: &[(syntax::Expr, Option<FormatSpec>)]) -> usize {
         let mut dummy_string = String::new();
-        for expr in exprs {
+        for (expr, _) in exprs {
             if let syntax::ExprData::StringLiteral(s) = expr.data(self.syntax_tables) {
                 dummy_string.push_str(s.as_str(self.db));
             } else {